@@ -0,0 +1,461 @@
+use axum::extract::{ConnectInfo, Path, Query, Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::config::Config;
+use crate::pool::SlotStats;
+use crate::reload::{self, SharedState};
+
+/// 관리 API 경로 접두사 (이 경로의 요청은 업스트림으로 보내지 않음)
+pub const ADMIN_PREFIX: &str = "/_summon";
+
+/// 최근 요청 요약 보관 개수
+const RECENT_LIMIT: usize = 100;
+
+/// 라우팅 대상이 아닌 요청의 진행 중 카운터 이름
+const PASSTHROUGH: &str = "";
+
+/// 설정 조회 시 가리는 값
+const REDACTED: &str = "***";
+
+/// 인증 설정에서 가릴 필드
+const SECRET_FIELDS: [&str; 6] = [
+    "value",
+    "client_secret",
+    "refresh_token",
+    "access_key_id",
+    "secret_access_key",
+    "session_token",
+];
+
+/// 관리 API 상태 — 리로드해도 유지 (드레인 표시는 라우트 match 패턴 기준)
+pub struct AdminState {
+    started: Instant,
+    drained: Mutex<HashSet<String>>,
+    /// 라우트 match 패턴별 진행 중 요청 수 (패스스루는 빈 문자열)
+    in_flight: Mutex<HashMap<String, usize>>,
+    /// 최근 /v1/messages 요청 (최신이 앞)
+    recent: Mutex<VecDeque<RequestSummary>>,
+}
+
+/// 완료된 요청 요약
+#[derive(Debug, Serialize, Clone)]
+pub struct RequestSummary {
+    /// 요청 시작 시각 (Unix epoch 초)
+    pub at: u64,
+    pub model: String,
+    /// 매칭된 라우트의 match 패턴 (None이면 패스스루)
+    pub route: Option<String>,
+    pub stream: bool,
+    pub status: u16,
+    /// 응답 본문(스트림 포함)이 끝날 때까지 걸린 시간
+    pub duration_ms: u64,
+}
+
+/// 요청 요약 기록 가드 — 응답 본문에 부착해 스트림이 끝나면 기록
+pub struct RequestTracker {
+    admin: Arc<AdminState>,
+    started: Instant,
+    summary: RequestSummary,
+}
+
+impl RequestTracker {
+    pub fn set_status(&mut self, status: StatusCode) {
+        self.summary.status = status.as_u16();
+    }
+}
+
+impl Drop for RequestTracker {
+    fn drop(&mut self) {
+        self.summary.duration_ms = self.started.elapsed().as_millis() as u64;
+        let key = self.summary.route.as_deref().unwrap_or(PASSTHROUGH);
+        if let Ok(mut in_flight) = self.admin.in_flight.lock() {
+            if let Some(count) = in_flight.get_mut(key) {
+                *count = count.saturating_sub(1);
+            }
+        }
+        if let Ok(mut recent) = self.admin.recent.lock() {
+            recent.push_front(self.summary.clone());
+            recent.truncate(RECENT_LIMIT);
+        }
+    }
+}
+
+impl AdminState {
+    pub fn new() -> Self {
+        AdminState {
+            started: Instant::now(),
+            drained: Mutex::new(HashSet::new()),
+            in_flight: Mutex::new(HashMap::new()),
+            recent: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// 요청 추적 시작 (진행 중 요청 수 증가, 가드 drop 시 감소 + 요약 기록)
+    pub fn track(self: &Arc<Self>, model: &str, route: Option<&str>, stream: bool) -> RequestTracker {
+        if let Ok(mut in_flight) = self.in_flight.lock() {
+            *in_flight.entry(route.unwrap_or(PASSTHROUGH).to_string()).or_default() += 1;
+        }
+        let at = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or(Duration::ZERO).as_secs();
+        RequestTracker {
+            admin: self.clone(),
+            started: Instant::now(),
+            summary: RequestSummary {
+                at,
+                model: model.to_string(),
+                route: route.map(String::from),
+                stream,
+                status: 0,
+                duration_ms: 0,
+            },
+        }
+    }
+
+    /// 라우트가 드레인 중인지 (새 요청을 업스트림으로 보내지 않음)
+    pub fn is_draining(&self, match_pattern: &str) -> bool {
+        self.drained.lock().map(|d| d.contains(match_pattern)).unwrap_or(false)
+    }
+
+    fn set_draining(&self, match_pattern: &str, draining: bool) {
+        if let Ok(mut drained) = self.drained.lock() {
+            if draining {
+                drained.insert(match_pattern.to_string());
+            } else {
+                drained.remove(match_pattern);
+            }
+        }
+    }
+
+    fn in_flight(&self, route: Option<&str>) -> usize {
+        self.in_flight
+            .lock()
+            .ok()
+            .and_then(|m| m.get(route.unwrap_or(PASSTHROUGH)).copied())
+            .unwrap_or(0)
+    }
+
+    fn recent(&self) -> Vec<RequestSummary> {
+        self.recent.lock().map(|r| r.iter().cloned().collect()).unwrap_or_default()
+    }
+}
+
+/// 관리 API 라우터 (`ADMIN_PREFIX` 아래에 마운트)
+///
+/// - GET  /health, /config, /routes, /requests
+/// - POST /routes/{idx}/keys/{key}/cooldown?secs=N (0이면 쿨다운 해제)
+/// - POST/DELETE /routes/{idx}/drain
+/// - POST /reload
+pub fn router(shared: SharedState) -> Router<SharedState> {
+    Router::new()
+        .route("/health", get(health))
+        .route("/config", get(config))
+        .route("/routes", get(routes))
+        .route("/requests", get(requests))
+        .route("/routes/{idx}/keys/{key}/cooldown", post(cooldown))
+        .route("/routes/{idx}/drain", post(drain).delete(undrain))
+        .route("/reload", post(reload_config))
+        .route_layer(middleware::from_fn_with_state(shared, authorize))
+        .fallback(|| async { admin_error(StatusCode::NOT_FOUND, "알 수 없는 관리 API 경로입니다") })
+}
+
+fn admin_error(status: StatusCode, message: &str) -> Response {
+    (status, Json(json!({"error": message}))).into_response()
+}
+
+/// 토큰이 설정되어 있으면 Bearer 토큰 확인, 없으면 로컬(loopback) 요청만 허용
+async fn authorize(
+    State(shared): State<SharedState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let state = shared.current();
+    let allowed = match state.config.server.admin_token.as_deref().filter(|t| !t.is_empty()) {
+        Some(token) => {
+            let presented = req
+                .headers()
+                .get(header::AUTHORIZATION)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.strip_prefix("Bearer "));
+            presented.is_some_and(|p| constant_time_eq(p.as_bytes(), token.as_bytes()))
+        }
+        None => addr.ip().is_loopback(),
+    };
+    if !allowed {
+        tracing::warn!(client = %addr, path = %req.uri().path(), "관리 API 접근 거부");
+        return admin_error(StatusCode::UNAUTHORIZED, "관리 API 접근 권한이 없습니다");
+    }
+    next.run(req).await
+}
+
+/// 토큰 비교 (일치 여부가 비교 시간으로 드러나지 않도록 끝까지 비교)
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+async fn health(State(shared): State<SharedState>) -> Json<Value> {
+    let state = shared.current();
+    let in_flight: usize = state.admin.in_flight.lock().map(|m| m.values().sum()).unwrap_or(0);
+    Json(json!({
+        "status": "ok",
+        "version": env!("CARGO_PKG_VERSION"),
+        "uptime_secs": state.admin.started.elapsed().as_secs(),
+        "routes": state.config.routes.len(),
+        "in_flight": in_flight,
+        "open_circuits": state.breaker.snapshot().len(),
+    }))
+}
+
+async fn config(State(shared): State<SharedState>) -> Json<Value> {
+    Json(redacted_config(&shared.current().config))
+}
+
+/// 라우트별 진행 중 요청, 키/업스트림 상태, 계정 세마포어 대기열, 서킷
+async fn routes(State(shared): State<SharedState>) -> Json<Value> {
+    let state = shared.current();
+    let circuits = state.breaker.snapshot();
+    let slots = |stats: Option<Vec<SlotStats>>| {
+        stats.map(|stats| {
+            stats
+                .iter()
+                .enumerate()
+                .map(|(i, s)| {
+                    json!({
+                        "index": i,
+                        "in_flight": s.in_flight,
+                        "cooldown_secs": s.cooldown_secs,
+                        "latency_ms": s.latency_ms,
+                    })
+                })
+                .collect::<Vec<_>>()
+        })
+    };
+    let routes: Vec<Value> = state
+        .config
+        .routes
+        .iter()
+        .enumerate()
+        .map(|(idx, route)| {
+            let pattern = route.match_pattern.as_str();
+            let account = state.account_semaphore.stats(idx).map(|q| {
+                json!({"limit": q.limit, "available": q.available, "waiting": q.waiting})
+            });
+            json!({
+                "index": idx,
+                "match": pattern,
+                "upstream": route.upstream_summary(),
+                "draining": state.admin.is_draining(pattern),
+                "in_flight": state.admin.in_flight(Some(pattern)),
+                "keys": slots(state.key_pool.key_stats(idx)),
+                "upstreams": slots(state.upstream_pool.upstream_stats(idx)),
+                "account": account,
+                "circuits": circuits.iter().filter(|c| c.route == pattern).collect::<Vec<_>>(),
+            })
+        })
+        .collect();
+    Json(json!({
+        "passthrough_in_flight": state.admin.in_flight(None),
+        "routes": routes,
+    }))
+}
+
+async fn requests(State(shared): State<SharedState>) -> Json<Vec<RequestSummary>> {
+    Json(shared.current().admin.recent())
+}
+
+#[derive(Deserialize)]
+struct CooldownParams {
+    /// 쿨다운 시간 (생략 시 60초)
+    secs: Option<u64>,
+}
+
+/// 키 풀의 키를 쿨다운 (secs=0이면 해제)
+async fn cooldown(
+    State(shared): State<SharedState>,
+    Path((idx, key)): Path<(usize, usize)>,
+    Query(params): Query<CooldownParams>,
+) -> Response {
+    let state = shared.current();
+    let Some(keys) = state.key_pool.key_stats(idx) else {
+        return admin_error(StatusCode::NOT_FOUND, "키 풀이 있는 라우트가 아닙니다");
+    };
+    if key >= keys.len() {
+        return admin_error(StatusCode::NOT_FOUND, "키 인덱스가 범위를 벗어났습니다");
+    }
+    state.key_pool.set_manual_cooldown(idx, key, params.secs);
+    let cooldown_secs = state.key_pool.key_stats(idx).and_then(|k| k.get(key).map(|s| s.cooldown_secs));
+    Json(json!({"route": idx, "key": key, "cooldown_secs": cooldown_secs})).into_response()
+}
+
+async fn drain(State(shared): State<SharedState>, Path(idx): Path<usize>) -> Response {
+    set_draining(&shared, idx, true)
+}
+
+async fn undrain(State(shared): State<SharedState>, Path(idx): Path<usize>) -> Response {
+    set_draining(&shared, idx, false)
+}
+
+fn set_draining(shared: &SharedState, idx: usize, draining: bool) -> Response {
+    let state = shared.current();
+    let Some(route) = state.config.routes.get(idx) else {
+        return admin_error(StatusCode::NOT_FOUND, "라우트 인덱스가 범위를 벗어났습니다");
+    };
+    state.admin.set_draining(&route.match_pattern, draining);
+    tracing::info!(route_idx = idx, route = %route.match_pattern, draining, "관리 API: 라우트 드레인 변경");
+    Json(json!({
+        "index": idx,
+        "match": route.match_pattern,
+        "draining": draining,
+        "in_flight": state.admin.in_flight(Some(&route.match_pattern)),
+    }))
+    .into_response()
+}
+
+async fn reload_config(State(shared): State<SharedState>) -> Response {
+    match reload::reload(&shared, "admin").await {
+        Ok(()) => Json(json!({"status": "reloaded", "routes": shared.current().config.routes.len()})).into_response(),
+        Err(e) => admin_error(StatusCode::BAD_REQUEST, &e),
+    }
+}
+
+/// 설정을 JSON으로 변환하면서 인증 값, 트랜스포머 헤더, 관리 토큰을 가림
+fn redacted_config(config: &Config) -> Value {
+    let mut value = serde_json::to_value(config).unwrap_or(Value::Null);
+    redact(&mut value);
+    if value["server"]["admin_token"].is_string() {
+        value["server"]["admin_token"] = REDACTED.into();
+    }
+    value
+}
+
+fn redact(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, v) in map.iter_mut() {
+                match key.as_str() {
+                    "auth" => redact_auth(v),
+                    "headers" => mask_strings(v),
+                    _ => redact(v),
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact),
+        _ => {}
+    }
+}
+
+fn redact_auth(auth: &mut Value) {
+    let Some(map) = auth.as_object_mut() else {
+        return;
+    };
+    for field in SECRET_FIELDS {
+        if let Some(v) = map.get_mut(field).filter(|v| v.is_string()) {
+            *v = REDACTED.into();
+        }
+    }
+    if let Some(pool) = map.get_mut("pool") {
+        mask_strings(pool);
+    }
+}
+
+fn mask_strings(value: &mut Value) {
+    match value {
+        Value::String(s) => *s = REDACTED.to_string(),
+        Value::Object(map) => map.values_mut().for_each(mask_strings),
+        Value::Array(items) => items.iter_mut().for_each(mask_strings),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redacted_config_hides_secrets() {
+        let yaml = r#"
+server:
+  host: "127.0.0.1"
+  port: 18081
+  admin_token: "admin-secret"
+default:
+  url: "https://api.anthropic.com"
+routes:
+  - match: "glm"
+    upstream:
+      url: "https://api.z.ai"
+      auth:
+        header: "x-api-key"
+        value: "key-1"
+        pool: ["key-2", "key-3"]
+    fallback:
+      - upstream:
+          url: "https://openrouter.ai/api/v1"
+          auth:
+            header: "Authorization"
+            value: "Bearer or-key"
+        transformer: "openai"
+transformers:
+  - name: "custom"
+    headers:
+      X-Api-Key: "header-secret"
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        let redacted = redacted_config(&config);
+        let text = redacted.to_string();
+        for secret in ["admin-secret", "key-1", "key-2", "key-3", "or-key", "header-secret"] {
+            assert!(!text.contains(secret), "{secret} 노출");
+        }
+        // 비밀이 아닌 값은 유지
+        assert_eq!(redacted["routes"][0]["upstream"]["auth"]["header"], "x-api-key");
+        assert_eq!(redacted["routes"][0]["upstream"]["url"], "https://api.z.ai");
+        assert_eq!(redacted["routes"][0]["upstream"]["auth"]["pool"][1], REDACTED);
+    }
+
+    #[test]
+    fn test_tracker_records_summary_and_in_flight() {
+        let admin = Arc::new(AdminState::new());
+        let mut tracker = admin.track("glm-5", Some("glm"), true);
+        let passthrough = admin.track("claude-sonnet-4-5", None, false);
+        assert_eq!(admin.in_flight(Some("glm")), 1);
+        assert_eq!(admin.in_flight(None), 1);
+
+        tracker.set_status(StatusCode::OK);
+        drop(tracker);
+        drop(passthrough);
+        assert_eq!(admin.in_flight(Some("glm")), 0);
+
+        let recent = admin.recent();
+        assert_eq!(recent.len(), 2);
+        // 최신이 앞
+        assert_eq!(recent[0].route, None);
+        assert_eq!(recent[1].model, "glm-5");
+        assert_eq!(recent[1].status, 200);
+        assert!(recent[1].stream);
+
+        for _ in 0..RECENT_LIMIT {
+            drop(admin.track("m", None, false));
+        }
+        assert_eq!(admin.recent().len(), RECENT_LIMIT);
+    }
+
+    #[test]
+    fn test_drain_by_match_pattern() {
+        let admin = AdminState::new();
+        assert!(!admin.is_draining("glm"));
+        admin.set_draining("glm", true);
+        assert!(admin.is_draining("glm"));
+        assert!(!admin.is_draining("kimi"));
+        admin.set_draining("glm", false);
+        assert!(!admin.is_draining("glm"));
+    }
+}
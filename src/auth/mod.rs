@@ -0,0 +1,3 @@
+pub mod gcp;
+pub mod oauth;
+pub mod sigv4;
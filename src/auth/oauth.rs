@@ -0,0 +1,346 @@
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use super::gcp::{self, ServiceAccountKey};
+use crate::config::AuthConfig;
+use crate::util::uri_encode;
+use crate::HttpClient;
+
+/// 만료 전 미리 갱신하는 여유 시간
+const REFRESH_AHEAD_SECS: u64 = 60;
+/// 토큰 응답에 expires_in이 없을 때 가정하는 유효 기간
+const DEFAULT_EXPIRES_IN_SECS: u64 = 3600;
+
+/// 캐시된 액세스 토큰
+struct CachedToken {
+    access_token: String,
+    /// 이 시각 이후에는 갱신 (실제 만료보다 REFRESH_AHEAD_SECS 앞)
+    refresh_at: Instant,
+    /// 토큰 엔드포인트가 새 refresh_token을 발급한 경우 (rotation)
+    refresh_token: Option<String>,
+}
+
+/// 액세스 토큰 관리자 (`type: "oauth"`, `type: "gcp_service_account"`)
+///
+/// oauth는 refresh_token이 있으면 refresh_token grant, 없으면 client_credentials grant,
+/// 서비스 계정은 JWT-bearer grant로 액세스 토큰을 받아 만료 직전까지 캐시한다.
+/// 같은 자격 증명의 동시 갱신은 항목별 잠금으로 한 번만 수행된다.
+/// 토큰 요청은 `timeout` 안에 끝나야 하며, 초과하면 잠금을 풀고 오류를 반환한다.
+pub struct TokenManager {
+    client: HttpClient,
+    entries: Mutex<HashMap<String, Arc<tokio::sync::Mutex<Option<CachedToken>>>>>,
+}
+
+impl TokenManager {
+    pub fn new(client: HttpClient) -> Self {
+        TokenManager {
+            client,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 자격 증명별 캐시 항목 (token_url + client_id + refresh_token + 키 파일 기준)
+    fn entry(&self, auth: &AuthConfig) -> Arc<tokio::sync::Mutex<Option<CachedToken>>> {
+        let key = format!(
+            "{}\n{}\n{}\n{}",
+            auth.token_url.as_deref().unwrap_or(""),
+            auth.client_id.as_deref().unwrap_or(""),
+            auth.refresh_token.as_deref().unwrap_or(""),
+            auth.credentials_file.as_deref().unwrap_or(""),
+        );
+        self.entries.lock().unwrap().entry(key).or_default().clone()
+    }
+
+    /// 인증 헤더 값 (`Bearer <access_token>`), 캐시가 유효하면 재사용
+    ///
+    /// `timeout`은 토큰 엔드포인트 요청(응답 본문 수신까지)의 제한 시간
+    pub async fn header_value(&self, auth: &AuthConfig, timeout: Duration) -> Result<String, String> {
+        let entry = self.entry(auth);
+        let mut cached = entry.lock().await;
+        if let Some(token) = cached.as_ref().filter(|t| Instant::now() < t.refresh_at) {
+            return Ok(format!("Bearer {}", token.access_token));
+        }
+
+        let rotated = cached.as_ref().and_then(|t| t.refresh_token.clone());
+        let token = self.fetch(auth, rotated.as_deref(), timeout).await?;
+        let value = format!("Bearer {}", token.access_token);
+        *cached = Some(token);
+        Ok(value)
+    }
+
+    /// 캐시된 토큰을 만료 처리 (업스트림 401 시 강제 갱신용, rotation된 refresh_token은 유지)
+    pub async fn invalidate(&self, auth: &AuthConfig) {
+        let entry = self.entry(auth);
+        let mut cached = entry.lock().await;
+        if let Some(token) = cached.as_mut() {
+            token.refresh_at = Instant::now();
+        }
+    }
+
+    /// 토큰 엔드포인트 호출
+    async fn fetch(&self, auth: &AuthConfig, rotated: Option<&str>, timeout: Duration) -> Result<CachedToken, String> {
+        let (token_url, form) = if auth.is_gcp_service_account() {
+            service_account_grant(auth)?
+        } else {
+            oauth_grant(auth, rotated)
+        };
+        let body: String = form
+            .iter()
+            .map(|(k, v)| format!("{k}={}", uri_encode(v)))
+            .collect::<Vec<_>>()
+            .join("&");
+
+        let req = hyper::Request::builder()
+            .method("POST")
+            .uri(token_url.as_str())
+            .header("Content-Type", "application/x-www-form-urlencoded")
+            .header("Accept", "application/json")
+            .body(Full::new(Bytes::from(body)))
+            .map_err(|e| format!("토큰 요청 생성 실패: {e}"))?;
+
+        // 응답이 멈춘 토큰 엔드포인트가 같은 자격 증명의 모든 요청을 붙잡지 않도록 전체 교환에 제한 시간 적용
+        let exchange = async {
+            let resp = self
+                .client
+                .request(req)
+                .await
+                .map_err(|e| format!("토큰 엔드포인트 연결 실패: {e}"))?;
+            let status = resp.status();
+            let bytes = resp
+                .into_body()
+                .collect()
+                .await
+                .map_err(|e| format!("토큰 응답 읽기 실패: {e}"))?
+                .to_bytes();
+            Ok::<_, String>((status, bytes))
+        };
+        let (status, bytes) = tokio::time::timeout(timeout, exchange)
+            .await
+            .map_err(|_| format!("토큰 엔드포인트가 {}초 안에 응답하지 않았습니다", timeout.as_secs()))??;
+        if !status.is_success() {
+            return Err(format!(
+                "토큰 발급 실패 ({status}): {}",
+                String::from_utf8_lossy(&bytes).trim()
+            ));
+        }
+        let data: Value = serde_json::from_slice(&bytes).map_err(|e| format!("토큰 응답 파싱 실패: {e}"))?;
+        parse_token(&data, Instant::now())
+    }
+}
+
+/// 토큰 요청 (token_url, 폼 필드)
+type TokenRequest = (String, Vec<(&'static str, String)>);
+
+/// OAuth 토큰 요청: refresh_token grant 또는 client_credentials grant
+fn oauth_grant(auth: &AuthConfig, rotated: Option<&str>) -> TokenRequest {
+    let refresh_token = rotated.or(auth.refresh_token.as_deref()).filter(|t| !t.is_empty());
+    let mut form = match refresh_token {
+        Some(rt) => vec![("grant_type", "refresh_token".to_string()), ("refresh_token", rt.to_string())],
+        None => vec![("grant_type", "client_credentials".to_string())],
+    };
+    if let Some(id) = &auth.client_id {
+        form.push(("client_id", id.clone()));
+    }
+    if let Some(secret) = &auth.client_secret {
+        form.push(("client_secret", secret.clone()));
+    }
+    (auth.token_url.clone().unwrap_or_default(), form)
+}
+
+/// 서비스 계정 토큰 요청: 키 파일로 서명한 JWT assertion (token_url 지정 시 키 파일의 token_uri 대신 사용)
+fn service_account_grant(auth: &AuthConfig) -> Result<TokenRequest, String> {
+    let key = ServiceAccountKey::load(auth.credentials_file.as_deref().unwrap_or(""))?;
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    let assertion = key.assertion(auth.scope.as_deref().unwrap_or(gcp::DEFAULT_SCOPE), now)?;
+    let token_url = auth.token_url.clone().unwrap_or_else(|| key.token_uri().to_string());
+    Ok((token_url, vec![("grant_type", gcp::JWT_BEARER_GRANT.to_string()), ("assertion", assertion)]))
+}
+
+/// 토큰 응답 `{"access_token","expires_in","refresh_token"}` 해석
+fn parse_token(data: &Value, now: Instant) -> Result<CachedToken, String> {
+    let access_token = data["access_token"]
+        .as_str()
+        .filter(|t| !t.is_empty())
+        .ok_or("토큰 응답에 access_token이 없습니다")?;
+    // 일부 제공자는 expires_in을 문자열로 반환
+    let expires_in = data["expires_in"]
+        .as_u64()
+        .or_else(|| data["expires_in"].as_str().and_then(|s| s.parse().ok()))
+        .unwrap_or(DEFAULT_EXPIRES_IN_SECS);
+    // 수명이 짧은 토큰은 수명의 절반 시점에 갱신
+    let ahead = REFRESH_AHEAD_SECS.min(expires_in / 2);
+    Ok(CachedToken {
+        access_token: access_token.to_string(),
+        refresh_at: now + Duration::from_secs(expires_in - ahead),
+        refresh_token: data["refresh_token"].as_str().map(String::from),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::extract::State;
+    use axum::routing::post;
+    use hyper_util::client::legacy::Client;
+    use hyper_util::rt::TokioExecutor;
+    use serde_json::json;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    /// (호출 횟수, 받은 폼 본문)
+    type TokenServerState = (Arc<AtomicUsize>, Arc<Mutex<Vec<String>>>);
+
+    /// 로컬 토큰 엔드포인트: 호출마다 token-1, token-2, ... 발급, 받은 폼 기록
+    async fn spawn_token_server(expires_in: u64) -> (String, Arc<AtomicUsize>, Arc<Mutex<Vec<String>>>) {
+        let hits = Arc::new(AtomicUsize::new(0));
+        let forms = Arc::new(Mutex::new(Vec::new()));
+        let app = axum::Router::new()
+            .route(
+                "/token",
+                post(move |State((hits, forms)): State<TokenServerState>, body: String| async move {
+                    let n = hits.fetch_add(1, Ordering::SeqCst) + 1;
+                    forms.lock().unwrap().push(body);
+                    axum::Json(json!({
+                        "access_token": format!("token-{n}"),
+                        "token_type": "Bearer",
+                        "expires_in": expires_in,
+                        "refresh_token": format!("rt-{n}"),
+                    }))
+                }),
+            )
+            .with_state((hits.clone(), forms.clone()));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (format!("http://{addr}/token"), hits, forms)
+    }
+
+    fn make_client() -> HttpClient {
+        let _ = rustls::crypto::ring::default_provider().install_default();
+        let tls = rustls::ClientConfig::builder()
+            .with_root_certificates(rustls::RootCertStore::empty())
+            .with_no_client_auth();
+        let https = hyper_rustls::HttpsConnectorBuilder::new()
+            .with_tls_config(tls)
+            .https_or_http()
+            .enable_http1()
+            .build();
+        Client::builder(TokioExecutor::new()).build(https)
+    }
+
+    fn make_auth(token_url: &str, refresh_token: Option<&str>) -> AuthConfig {
+        AuthConfig {
+            auth_type: "oauth".into(),
+            client_id: Some("summon".into()),
+            client_secret: Some("s3cret/+".into()),
+            refresh_token: refresh_token.map(String::from),
+            token_url: Some(token_url.into()),
+            ..AuthConfig::default()
+        }
+    }
+
+    /// client_credentials: 유효 기간 동안 캐시, invalidate 후 재발급
+    #[tokio::test]
+    async fn test_client_credentials_cache_and_invalidate() {
+        let (url, hits, forms) = spawn_token_server(3600).await;
+        let manager = TokenManager::new(make_client());
+        let auth = make_auth(&url, None);
+
+        assert_eq!(manager.header_value(&auth, TIMEOUT).await.unwrap(), "Bearer token-1");
+        assert_eq!(manager.header_value(&auth, TIMEOUT).await.unwrap(), "Bearer token-1");
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            forms.lock().unwrap()[0],
+            "grant_type=client_credentials&client_id=summon&client_secret=s3cret%2F%2B"
+        );
+
+        manager.invalidate(&auth).await;
+        assert_eq!(manager.header_value(&auth, TIMEOUT).await.unwrap(), "Bearer token-2");
+        // 응답에 refresh_token이 오면 다음 갱신부터 refresh_token grant 사용
+        assert!(forms.lock().unwrap()[1].starts_with("grant_type=refresh_token&refresh_token=rt-1"));
+    }
+
+    /// refresh_token grant, 만료 임박 토큰은 미리 갱신
+    #[tokio::test]
+    async fn test_refresh_token_grant_refreshes_ahead() {
+        let (url, hits, forms) = spawn_token_server(0).await;
+        let manager = TokenManager::new(make_client());
+        let auth = make_auth(&url, Some("initial-rt"));
+
+        assert_eq!(manager.header_value(&auth, TIMEOUT).await.unwrap(), "Bearer token-1");
+        assert!(forms.lock().unwrap()[0].contains("refresh_token=initial-rt"));
+        // expires_in 0 → 즉시 갱신 대상
+        assert_eq!(manager.header_value(&auth, TIMEOUT).await.unwrap(), "Bearer token-2");
+        assert_eq!(hits.load(Ordering::SeqCst), 2);
+    }
+
+    /// 서비스 계정: JWT-bearer grant로 토큰 발급
+    #[tokio::test]
+    async fn test_service_account_grant() {
+        let (url, hits, forms) = spawn_token_server(3599).await;
+        let key_path = std::env::temp_dir().join("_summon_test_service_account.json");
+        let key = json!({
+            "type": "service_account",
+            "client_email": "summon@project.iam.gserviceaccount.com",
+            "private_key": include_str!("testdata/service_account_key.pem"),
+            "private_key_id": "key-1",
+        });
+        std::fs::write(&key_path, key.to_string()).unwrap();
+
+        let manager = TokenManager::new(make_client());
+        let auth = AuthConfig {
+            auth_type: "gcp_service_account".into(),
+            credentials_file: Some(key_path.to_string_lossy().into_owned()),
+            token_url: Some(url),
+            ..AuthConfig::default()
+        };
+        assert_eq!(manager.header_value(&auth, TIMEOUT).await.unwrap(), "Bearer token-1");
+        assert_eq!(manager.header_value(&auth, TIMEOUT).await.unwrap(), "Bearer token-1");
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+
+        let form = forms.lock().unwrap()[0].clone();
+        assert!(form.starts_with("grant_type=urn%3Aietf%3Aparams%3Aoauth%3Agrant-type%3Ajwt-bearer&assertion="));
+        // header.claims.signature
+        assert_eq!(form.split_once("assertion=").unwrap().1.split('.').count(), 3);
+
+        let _ = std::fs::remove_file(&key_path);
+    }
+
+    /// 응답하지 않는 토큰 엔드포인트는 제한 시간 후 오류, 잠금도 풀려 다음 요청이 진행됨
+    #[tokio::test]
+    async fn test_fetch_timeout() {
+        let app = axum::Router::new().route(
+            "/token",
+            post(|| async {
+                tokio::time::sleep(Duration::from_secs(60)).await;
+                "{}"
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let manager = TokenManager::new(make_client());
+        let auth = make_auth(&format!("http://{addr}/token"), None);
+        let timeout = Duration::from_millis(100);
+        let err = manager.header_value(&auth, timeout).await.unwrap_err();
+        assert!(err.contains("응답하지 않았습니다"));
+        assert!(manager.header_value(&auth, timeout).await.is_err());
+    }
+
+    #[test]
+    fn test_parse_token() {
+        let now = Instant::now();
+        let token = parse_token(&json!({"access_token": "abc", "expires_in": "600"}), now).unwrap();
+        assert_eq!(token.access_token, "abc");
+        assert_eq!(token.refresh_at, now + Duration::from_secs(540));
+        assert!(token.refresh_token.is_none());
+
+        assert!(parse_token(&json!({"error": "invalid_client"}), now).is_err());
+    }
+}
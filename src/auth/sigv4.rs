@@ -0,0 +1,234 @@
+use ring::{digest, hmac};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::config::AuthConfig;
+use crate::util::uri_encode;
+
+/// AWS Signature Version 4 서명기
+pub struct SigV4Signer<'a> {
+    pub access_key_id: &'a str,
+    pub secret_access_key: &'a str,
+    pub session_token: Option<&'a str>,
+    pub region: &'a str,
+    pub service: &'a str,
+}
+
+impl<'a> SigV4Signer<'a> {
+    /// `type: "aws_sigv4"` 인증 설정에서 서명기 생성 (필수 필드는 로드 시 검증됨)
+    pub fn from_auth(auth: &'a AuthConfig) -> Self {
+        SigV4Signer {
+            access_key_id: auth.access_key_id.as_deref().unwrap_or(""),
+            secret_access_key: auth.secret_access_key.as_deref().unwrap_or(""),
+            session_token: auth.session_token.as_deref().filter(|t| !t.is_empty()),
+            region: auth.region.as_deref().unwrap_or(""),
+            service: auth.service.as_deref().unwrap_or("bedrock"),
+        }
+    }
+
+    /// 현재 시각으로 서명하여 추가할 헤더 목록 반환
+    pub fn sign(&self, method: &str, uri: &str, headers: &[(&str, &str)], body: &[u8]) -> Vec<(String, String)> {
+        self.sign_at(method, uri, headers, body, SystemTime::now())
+    }
+
+    /// 지정 시각으로 서명 (`Authorization`, `X-Amz-Date`, 필요 시 `X-Amz-Security-Token`)
+    ///
+    /// `headers`는 host/x-amz-date 외에 서명에 포함할 헤더 (예: content-type)
+    pub fn sign_at(
+        &self,
+        method: &str,
+        uri: &str,
+        headers: &[(&str, &str)],
+        body: &[u8],
+        now: SystemTime,
+    ) -> Vec<(String, String)> {
+        let (date, amz_date) = amz_dates(now);
+        let (host, path, query) = split_uri(uri);
+
+        // 서명 대상 헤더 (소문자 이름 정렬)
+        let mut signed: Vec<(String, String)> = vec![
+            ("host".into(), host.to_string()),
+            ("x-amz-date".into(), amz_date.clone()),
+        ];
+        if let Some(token) = self.session_token {
+            signed.push(("x-amz-security-token".into(), token.to_string()));
+        }
+        for (name, value) in headers {
+            signed.push((name.to_ascii_lowercase(), value.trim().to_string()));
+        }
+        signed.sort();
+
+        let canonical_headers: String = signed.iter().map(|(k, v)| format!("{k}:{v}\n")).collect();
+        let signed_headers = signed.iter().map(|(k, _)| k.as_str()).collect::<Vec<_>>().join(";");
+
+        let canonical_request = format!(
+            "{method}\n{}\n{}\n{canonical_headers}\n{signed_headers}\n{}",
+            canonical_path(path),
+            canonical_query(query),
+            hex_sha256(body),
+        );
+
+        let scope = format!("{date}/{}/{}/aws4_request", self.region, self.service);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{amz_date}\n{scope}\n{}",
+            hex_sha256(canonical_request.as_bytes())
+        );
+
+        let mut key = hmac_sha256(format!("AWS4{}", self.secret_access_key).as_bytes(), date.as_bytes());
+        for part in [self.region, self.service, "aws4_request"] {
+            key = hmac_sha256(&key, part.as_bytes());
+        }
+        let signature = hex(&hmac_sha256(&key, string_to_sign.as_bytes()));
+
+        let mut out = vec![
+            (
+                "Authorization".to_string(),
+                format!(
+                    "AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders={signed_headers}, Signature={signature}",
+                    self.access_key_id
+                ),
+            ),
+            ("X-Amz-Date".to_string(), amz_date),
+        ];
+        if let Some(token) = self.session_token {
+            out.push(("X-Amz-Security-Token".to_string(), token.to_string()));
+        }
+        out
+    }
+}
+
+/// URI → (host, path, query)
+fn split_uri(uri: &str) -> (&str, &str, &str) {
+    let rest = uri.split_once("://").map_or(uri, |(_, r)| r);
+    let (authority, path_query) = match rest.find('/') {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, "/"),
+    };
+    let (path, query) = path_query.split_once('?').unwrap_or((path_query, ""));
+    (authority, if path.is_empty() { "/" } else { path }, query)
+}
+
+/// 경로 세그먼트를 한 번 더 인코딩 (S3 외 서비스 규칙: 요청 경로는 이미 1회 인코딩됨)
+fn canonical_path(path: &str) -> String {
+    path.split('/').map(uri_encode).collect::<Vec<_>>().join("/")
+}
+
+/// 쿼리 파라미터를 이름순 정렬 후 인코딩
+fn canonical_query(query: &str) -> String {
+    let mut pairs: Vec<(String, String)> = query
+        .split('&')
+        .filter(|p| !p.is_empty())
+        .map(|p| {
+            let (k, v) = p.split_once('=').unwrap_or((p, ""));
+            (uri_encode(&percent_decode(k)), uri_encode(&percent_decode(v)))
+        })
+        .collect();
+    pairs.sort();
+    pairs.iter().map(|(k, v)| format!("{k}={v}")).collect::<Vec<_>>().join("&")
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+            if let Some(b) = hex.and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                out.push(b);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    hmac::sign(&hmac::Key::new(hmac::HMAC_SHA256, key), data).as_ref().to_vec()
+}
+
+fn hex_sha256(data: &[u8]) -> String {
+    hex(digest::digest(&digest::SHA256, data).as_ref())
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// 시각 → ("YYYYMMDD", "YYYYMMDDTHHMMSSZ") (UTC)
+fn amz_dates(now: SystemTime) -> (String, String) {
+    let secs = now.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    let (days, rem) = (secs / 86_400, secs % 86_400);
+    let (year, month, day) = civil_from_days(days as i64);
+    let date = format!("{year:04}{month:02}{day:02}");
+    let amz_date = format!("{date}T{:02}{:02}{:02}Z", rem / 3600, rem % 3600 / 60, rem % 60);
+    (date, amz_date)
+}
+
+/// 1970-01-01 기준 일수 → (년, 월, 일) (Howard Hinnant의 civil_from_days)
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    /// AWS 문서의 SigV4 예제 (IAM ListUsers, 2015-08-30T12:36:00Z)
+    #[test]
+    fn test_sign_aws_documentation_example() {
+        let signer = SigV4Signer {
+            access_key_id: "AKIDEXAMPLE",
+            secret_access_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+            session_token: None,
+            region: "us-east-1",
+            service: "iam",
+        };
+        let now = UNIX_EPOCH + Duration::from_secs(1_440_938_160);
+        let headers = signer.sign_at(
+            "GET",
+            "https://iam.amazonaws.com/?Action=ListUsers&Version=2010-05-08",
+            &[("Content-Type", "application/x-www-form-urlencoded; charset=utf-8")],
+            b"",
+            now,
+        );
+
+        assert_eq!(headers[1], ("X-Amz-Date".to_string(), "20150830T123600Z".to_string()));
+        assert_eq!(
+            headers[0].1,
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/iam/aws4_request, \
+             SignedHeaders=content-type;host;x-amz-date, \
+             Signature=5d672d79c15b13162d9279b0855cfba6789a8edb4c82c400e06b5924a6f2b5d7"
+        );
+    }
+
+    /// Bedrock 모델 ID의 ':'는 요청 경로에서 1회, 정규 경로에서 2회 인코딩
+    #[test]
+    fn test_canonical_path_double_encodes() {
+        let model = uri_encode("anthropic.claude-sonnet-4-v1:0");
+        assert_eq!(model, "anthropic.claude-sonnet-4-v1%3A0");
+        assert_eq!(
+            canonical_path(&format!("/model/{model}/converse")),
+            "/model/anthropic.claude-sonnet-4-v1%253A0/converse"
+        );
+    }
+
+    #[test]
+    fn test_civil_from_days() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(16_677), (2015, 8, 30));
+        assert_eq!(civil_from_days(11_016), (2000, 2, 29));
+    }
+}
@@ -0,0 +1,421 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::config::{CircuitBreakerConfig, Config, UpstreamFailure};
+
+/// 서킷 단위 (라우트 전체, 키 풀의 키, `upstreams` 항목)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CircuitScope {
+    Route,
+    Key(usize),
+    Upstream(usize),
+}
+
+impl std::fmt::Display for CircuitScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CircuitScope::Route => write!(f, "route"),
+            CircuitScope::Key(i) => write!(f, "key[{i}]"),
+            CircuitScope::Upstream(i) => write!(f, "upstreams[{i}]"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CircuitState {
+    Closed,
+    /// 열림: until까지 요청 차단
+    Open { until: Instant },
+    /// 반열림: 시험 요청 1건 진행 중 (started 이후 open_secs가 지나면 다시 시험 허용)
+    HalfOpen { started: Instant },
+}
+
+#[derive(Clone)]
+struct Circuit {
+    state: CircuitState,
+    consecutive_failures: u32,
+    /// 최근 요청 결과 (true = 실패)
+    recent: VecDeque<bool>,
+}
+
+impl Circuit {
+    fn new() -> Self {
+        Circuit {
+            state: CircuitState::Closed,
+            consecutive_failures: 0,
+            recent: VecDeque::new(),
+        }
+    }
+
+    fn error_rate(&self) -> f64 {
+        match self.recent.len() {
+            0 => 0.0,
+            n => self.recent.iter().filter(|&&f| f).count() as f64 / n as f64,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.state = CircuitState::Closed;
+        self.consecutive_failures = 0;
+        self.recent.clear();
+    }
+}
+
+/// `summon status`에 표시할 열린/반열린 서킷
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CircuitStatus {
+    pub route: String,
+    pub scope: String,
+    /// "open" 또는 "half_open"
+    pub state: String,
+    pub consecutive_failures: u32,
+    pub error_rate: f64,
+    /// 열림 상태의 시험 요청 시각 (Unix epoch 초)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub open_until: Option<u64>,
+}
+
+/// 라우트/키/업스트림별 서킷 브레이커
+///
+/// 연결 실패, 타임아웃, 5xx를 실패로 센다 (429는 KeyPool/UpstreamPool 쿨다운이 담당).
+/// 열린 서킷은 업스트림을 건너뛰고 바로 폴백하게 하고, `open_secs` 후 요청 1건을
+/// 시험 요청으로 통과시켜 성공하면 닫고 실패하면 다시 연다.
+pub struct CircuitBreaker {
+    /// 라우트별 설정 (None = 비활성)
+    settings: Vec<Option<CircuitBreakerConfig>>,
+    /// 라우트별 표시 이름 (match 패턴)
+    labels: Vec<String>,
+    circuits: Mutex<HashMap<(usize, CircuitScope), Circuit>>,
+    /// 상태 변경 시 열린 서킷 목록을 기록할 파일
+    state_file: Option<PathBuf>,
+}
+
+impl CircuitBreaker {
+    /// Config로부터 CircuitBreaker 생성
+    pub fn from_config(config: &Config) -> Self {
+        let settings = config
+            .routes
+            .iter()
+            .map(|route| route.circuit_breaker.clone().filter(|settings| settings.enabled))
+            .collect();
+        let labels = config.routes.iter().map(|r| r.match_pattern.clone()).collect();
+
+        CircuitBreaker {
+            settings,
+            labels,
+            circuits: Mutex::new(HashMap::new()),
+            state_file: None,
+        }
+    }
+
+    /// 상태 파일 지정 (이전 실행의 기록은 비움)
+    pub fn with_state_file(mut self, path: PathBuf) -> Self {
+        self.state_file = Some(path);
+        self.persist(&HashMap::new());
+        self
+    }
+
+    /// 리로드된 Config로 새 CircuitBreaker 생성 (변경 없는 라우트의 서킷 상태 유지)
+    ///
+    /// `previous`는 `Config::unchanged_routes` 결과. 바뀌거나 사라진 라우트의 서킷은 버린다.
+    pub fn reload(&self, config: &Config, previous: &[Option<usize>]) -> Self {
+        let mut breaker = Self::from_config(config);
+        breaker.state_file = self.state_file.clone();
+        let carried: HashMap<(usize, CircuitScope), Circuit> = match self.circuits.lock() {
+            Ok(circuits) => previous
+                .iter()
+                .enumerate()
+                .filter_map(|(new_idx, prev)| prev.map(|old_idx| (new_idx, old_idx)))
+                .flat_map(|(new_idx, old_idx)| {
+                    circuits
+                        .iter()
+                        .filter(move |((idx, _), _)| *idx == old_idx)
+                        .map(move |(&(_, scope), c)| ((new_idx, scope), c.clone()))
+                })
+                .collect(),
+            Err(_) => HashMap::new(),
+        };
+        breaker.persist(&carried);
+        breaker.circuits = Mutex::new(carried);
+        breaker
+    }
+
+    /// 요청을 보내도 되는지 확인 (열림이면 false, 반열림 전환 시 시험 요청 1건만 true)
+    pub fn allow(&self, route_idx: usize, scope: CircuitScope) -> bool {
+        self.allow_at(route_idx, scope, Instant::now())
+    }
+
+    fn allow_at(&self, route_idx: usize, scope: CircuitScope, now: Instant) -> bool {
+        let Some(Some(settings)) = self.settings.get(route_idx) else {
+            return true;
+        };
+        let Ok(mut circuits) = self.circuits.lock() else {
+            return true;
+        };
+        let Some(circuit) = circuits.get_mut(&(route_idx, scope)) else {
+            return true;
+        };
+        let probe = match circuit.state {
+            CircuitState::Closed => return true,
+            CircuitState::Open { until } => now >= until,
+            CircuitState::HalfOpen { started } => now >= started + Duration::from_secs(settings.open_secs),
+        };
+        if probe {
+            circuit.state = CircuitState::HalfOpen { started: now };
+            tracing::info!(route = %self.label(route_idx), scope = %scope, "서킷 반열림, 시험 요청 전송");
+            self.persist(&circuits);
+        }
+        probe
+    }
+
+    /// 업스트림 시도 결과 기록 (`failure`가 None이면 성공)
+    pub fn record(&self, route_idx: usize, scope: CircuitScope, failure: Option<UpstreamFailure>) {
+        self.record_at(route_idx, scope, failure, Instant::now());
+    }
+
+    fn record_at(&self, route_idx: usize, scope: CircuitScope, failure: Option<UpstreamFailure>, now: Instant) {
+        let Some(Some(settings)) = self.settings.get(route_idx) else {
+            return;
+        };
+        let failed = failure.is_some_and(counts_as_failure);
+        let Ok(mut circuits) = self.circuits.lock() else {
+            return;
+        };
+        // 성공만 있었던 서킷은 만들지 않음
+        if !failed && !circuits.contains_key(&(route_idx, scope)) {
+            return;
+        }
+        let circuit = circuits.entry((route_idx, scope)).or_insert_with(Circuit::new);
+        let open_until = now + Duration::from_secs(settings.open_secs);
+
+        match circuit.state {
+            // 열리기 전에 보낸 요청의 늦은 결과는 무시
+            CircuitState::Open { .. } => return,
+            CircuitState::HalfOpen { .. } if failed => {
+                circuit.state = CircuitState::Open { until: open_until };
+                tracing::warn!(route = %self.label(route_idx), scope = %scope, failure = ?failure, "시험 요청 실패, 서킷 다시 열림");
+            }
+            CircuitState::HalfOpen { .. } => {
+                circuit.reset();
+                tracing::info!(route = %self.label(route_idx), scope = %scope, "시험 요청 성공, 서킷 닫힘");
+            }
+            CircuitState::Closed => {
+                circuit.recent.push_back(failed);
+                while circuit.recent.len() > settings.window {
+                    circuit.recent.pop_front();
+                }
+                circuit.consecutive_failures = if failed { circuit.consecutive_failures + 1 } else { 0 };
+
+                let error_rate = circuit.error_rate();
+                let tripped = circuit.consecutive_failures >= settings.failure_threshold
+                    || (circuit.recent.len() >= settings.min_requests && error_rate >= settings.error_rate);
+                if !failed || !tripped {
+                    return;
+                }
+                circuit.state = CircuitState::Open { until: open_until };
+                tracing::warn!(
+                    route = %self.label(route_idx),
+                    scope = %scope,
+                    consecutive_failures = circuit.consecutive_failures,
+                    error_rate,
+                    open_secs = settings.open_secs,
+                    "서킷 열림, 업스트림 건너뜀"
+                );
+            }
+        }
+        self.persist(&circuits);
+    }
+
+    /// 열린/반열린 서킷 목록
+    pub fn snapshot(&self) -> Vec<CircuitStatus> {
+        match self.circuits.lock() {
+            Ok(circuits) => self.statuses(&circuits),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    fn statuses(&self, circuits: &HashMap<(usize, CircuitScope), Circuit>) -> Vec<CircuitStatus> {
+        let now = Instant::now();
+        let epoch = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or(Duration::ZERO);
+        let mut list: Vec<CircuitStatus> = circuits
+            .iter()
+            .filter_map(|(&(route_idx, scope), c)| {
+                let (state, open_until) = match c.state {
+                    CircuitState::Closed => return None,
+                    CircuitState::Open { until } => ("open", Some((epoch + until.saturating_duration_since(now)).as_secs())),
+                    CircuitState::HalfOpen { .. } => ("half_open", None),
+                };
+                Some(CircuitStatus {
+                    route: self.label(route_idx).to_string(),
+                    scope: scope.to_string(),
+                    state: state.into(),
+                    consecutive_failures: c.consecutive_failures,
+                    error_rate: c.error_rate(),
+                    open_until,
+                })
+            })
+            .collect();
+        list.sort_by(|a, b| (&a.route, &a.scope).cmp(&(&b.route, &b.scope)));
+        list
+    }
+
+    /// 상태 파일 갱신 (실패해도 요청 처리에는 영향 없음)
+    fn persist(&self, circuits: &HashMap<(usize, CircuitScope), Circuit>) {
+        let Some(path) = &self.state_file else {
+            return;
+        };
+        let result = serde_json::to_string_pretty(&self.statuses(circuits))
+            .map_err(|e| e.to_string())
+            .and_then(|json| std::fs::write(path, json).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            tracing::debug!(error = %e, path = %path.display(), "서킷 상태 파일 기록 실패");
+        }
+    }
+
+    fn label(&self, route_idx: usize) -> &str {
+        self.labels.get(route_idx).map(String::as_str).unwrap_or("?")
+    }
+}
+
+/// 서킷 실패로 셀 결과인지 (연결 실패, 타임아웃, 5xx — 프록시 쪽 실패는 제외)
+pub fn counts_as_failure(failure: UpstreamFailure) -> bool {
+    match failure {
+        UpstreamFailure::Connect | UpstreamFailure::Timeout => true,
+        UpstreamFailure::Status(s) => (500..600).contains(&s),
+        UpstreamFailure::Local => false,
+    }
+}
+
+/// 상태 파일에서 열린 서킷 목록 읽기 (`summon status`용)
+pub fn read_state_file(path: &std::path::Path) -> Vec<CircuitStatus> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RouteConfig;
+
+    fn make_breaker(settings: Option<CircuitBreakerConfig>) -> CircuitBreaker {
+        let mut config = Config::default_config();
+        let route: RouteConfig = serde_yaml::from_str("match: \"glm\"\nupstream:\n  url: \"https://api.z.ai\"\n").unwrap();
+        config.routes.push(RouteConfig { circuit_breaker: settings, ..route });
+        CircuitBreaker::from_config(&config)
+    }
+
+    const CONNECT: Option<UpstreamFailure> = Some(UpstreamFailure::Connect);
+
+    #[test]
+    fn test_consecutive_failures_open_then_probe() {
+        let breaker = make_breaker(Some(CircuitBreakerConfig::default()));
+        let t0 = Instant::now();
+        for _ in 0..4 {
+            breaker.record_at(0, CircuitScope::Route, CONNECT, t0);
+        }
+        assert!(breaker.allow_at(0, CircuitScope::Route, t0));
+        breaker.record_at(0, CircuitScope::Route, Some(UpstreamFailure::Status(503)), t0);
+        assert!(!breaker.allow_at(0, CircuitScope::Route, t0));
+        assert_eq!(breaker.snapshot()[0].state, "open");
+        // 다른 범위에는 영향 없음
+        assert!(breaker.allow_at(0, CircuitScope::Key(1), t0));
+
+        // open_secs 경과 → 시험 요청 1건만 허용
+        let t1 = t0 + Duration::from_secs(30);
+        assert!(breaker.allow_at(0, CircuitScope::Route, t1));
+        assert!(!breaker.allow_at(0, CircuitScope::Route, t1));
+        assert_eq!(breaker.snapshot()[0].state, "half_open");
+
+        // 시험 실패 → 다시 열림, 다음 시험 성공 → 닫힘
+        breaker.record_at(0, CircuitScope::Route, Some(UpstreamFailure::Timeout), t1);
+        assert!(!breaker.allow_at(0, CircuitScope::Route, t1 + Duration::from_secs(29)));
+        let t2 = t1 + Duration::from_secs(30);
+        assert!(breaker.allow_at(0, CircuitScope::Route, t2));
+        breaker.record_at(0, CircuitScope::Route, None, t2);
+        assert!(breaker.allow_at(0, CircuitScope::Route, t2));
+        assert!(breaker.snapshot().is_empty());
+    }
+
+    #[test]
+    fn test_error_rate_opens_and_non_failures_ignored() {
+        let breaker = make_breaker(Some(CircuitBreakerConfig {
+            failure_threshold: 100,
+            min_requests: 4,
+            window: 4,
+            ..Default::default()
+        }));
+        let t0 = Instant::now();
+        // 성공만 있으면 서킷을 만들지 않음, 429/4xx/프록시 쪽 실패는 실패로 세지 않음
+        breaker.record_at(0, CircuitScope::Key(0), Some(UpstreamFailure::Status(429)), t0);
+        breaker.record_at(0, CircuitScope::Key(0), Some(UpstreamFailure::Local), t0);
+        assert!(breaker.circuits.lock().unwrap().is_empty());
+
+        breaker.record_at(0, CircuitScope::Key(0), CONNECT, t0);
+        breaker.record_at(0, CircuitScope::Key(0), None, t0);
+        breaker.record_at(0, CircuitScope::Key(0), Some(UpstreamFailure::Status(400)), t0);
+        assert!(breaker.allow_at(0, CircuitScope::Key(0), t0));
+        // 최근 4건 중 2건 실패 = 0.5
+        breaker.record_at(0, CircuitScope::Key(0), CONNECT, t0);
+        assert!(!breaker.allow_at(0, CircuitScope::Key(0), t0));
+        assert_eq!(breaker.snapshot()[0].scope, "key[0]");
+    }
+
+    #[test]
+    fn test_disabled_and_state_file() {
+        let breaker = make_breaker(Some(CircuitBreakerConfig { enabled: false, ..Default::default() }));
+        for _ in 0..10 {
+            breaker.record(0, CircuitScope::Route, CONNECT);
+        }
+        assert!(breaker.allow(0, CircuitScope::Route));
+
+        let breaker = make_breaker(None);
+        for _ in 0..10 {
+            breaker.record(0, CircuitScope::Route, CONNECT);
+        }
+        assert!(breaker.allow(0, CircuitScope::Route));
+
+        let path = std::env::temp_dir().join(format!("_summon_breaker_state_test_{}.json", uuid::Uuid::new_v4()));
+        let breaker = make_breaker(Some(CircuitBreakerConfig::default())).with_state_file(path.clone());
+        assert!(read_state_file(&path).is_empty());
+        for _ in 0..5 {
+            breaker.record(0, CircuitScope::Upstream(1), CONNECT);
+        }
+        let statuses = read_state_file(&path);
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(statuses.len(), 1);
+        assert_eq!(statuses[0].route, "glm");
+        assert_eq!(statuses[0].scope, "upstreams[1]");
+        assert_eq!(statuses[0].consecutive_failures, 5);
+        assert!(statuses[0].open_until.is_some());
+    }
+
+    /// 리로드: 변경 없는 라우트의 서킷은 새 인덱스로 옮기고, 바뀐 라우트의 서킷은 버림
+    #[test]
+    fn test_reload_keeps_unchanged_circuits() {
+        let breaker = make_breaker(Some(CircuitBreakerConfig::default()));
+        let t0 = Instant::now();
+        for _ in 0..5 {
+            breaker.record_at(0, CircuitScope::Key(1), CONNECT, t0);
+        }
+        assert!(!breaker.allow_at(0, CircuitScope::Key(1), t0));
+
+        let mut old = Config::default_config();
+        old.routes.push(serde_yaml::from_str("match: \"glm\"\nupstream:\n  url: \"https://api.z.ai\"\ncircuit_breaker: {}\n").unwrap());
+        let mut new = old.clone();
+        new.routes.insert(0, serde_yaml::from_str("match: \"kimi\"\nupstream:\n  url: \"https://api.kimi.com\"\ncircuit_breaker: {}\n").unwrap());
+
+        let reloaded = breaker.reload(&new, &new.unchanged_routes(&old));
+        assert!(reloaded.allow_at(0, CircuitScope::Key(1), t0));
+        assert!(!reloaded.allow_at(1, CircuitScope::Key(1), t0));
+        assert_eq!(reloaded.snapshot()[0].route, "glm");
+
+        new.routes[1].upstream.url = "https://open.bigmodel.cn".into();
+        let reloaded = breaker.reload(&new, &new.unchanged_routes(&old));
+        assert!(reloaded.snapshot().is_empty());
+    }
+}
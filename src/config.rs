@@ -1,25 +1,39 @@
 use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::util::{expand_path_template, uri_encode};
 
 /// 서버 바인딩 설정
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct ServerConfig {
     pub host: String,
     pub port: u16,
+    /// 관리 API(`/_summon/...`) 토큰 (지정 시 `Authorization: Bearer <토큰>` 필요, 생략 시 로컬 요청만 허용)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub admin_token: Option<String>,
 }
 
 /// 기본 업스트림 (Anthropic API)
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct DefaultConfig {
     pub url: String,
+    /// 패스스루(및 Anthropic API 폴백) 요청의 재시도 정책
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry: Option<RetryConfig>,
+    /// 패스스루 요청 및 라우트에서 생략한 타임아웃 항목의 기본값
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeouts: Option<TimeoutConfig>,
 }
 
 /// 인증 헤더 설정
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct AuthConfig {
-    /// 인증 타입: "api_key" (기본) 또는 "oauth" (향후 v0.3+)
+    /// 인증 타입: "api_key" (기본), "aws_sigv4", "oauth" 또는 "gcp_service_account"
     #[serde(rename = "type", default = "default_auth_type", skip_serializing_if = "is_default_auth_type")]
     pub auth_type: String,
 
@@ -29,7 +43,7 @@ pub struct AuthConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub value: Option<String>,
 
-    // OAuth 방식 (v0.3+ — 현재는 파싱만)
+    // OAuth 방식 (type: "oauth") — refresh_token이 있으면 refresh grant, 없으면 client_credentials
     #[serde(skip_serializing_if = "Option::is_none")]
     pub client_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -39,12 +53,56 @@ pub struct AuthConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub token_url: Option<String>,
 
+    // AWS SigV4 방식 (type: "aws_sigv4") — 요청마다 서명, 정적 헤더 미사용
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub access_key_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret_access_key: Option<String>,
+    /// 임시 자격 증명(STS)의 세션 토큰
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_token: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+    /// 서명 서비스 이름 (기본값: "bedrock")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub service: Option<String>,
+
+    // Google 서비스 계정 방식 (type: "gcp_service_account") — 키 파일로 서명한 JWT를 액세스 토큰으로 교환
+    /// 서비스 계정 JSON 키 파일 경로
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub credentials_file: Option<String>,
+    /// 요청 scope (기본값: "https://www.googleapis.com/auth/cloud-platform")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+
     /// 추가 API 키 풀 (같은 header, 다른 value)
     /// Least-Connections 방식으로 분배됨
     #[serde(skip_serializing_if = "Option::is_none")]
     pub pool: Option<Vec<String>>,
 }
 
+impl Default for AuthConfig {
+    fn default() -> Self {
+        AuthConfig {
+            auth_type: default_auth_type(),
+            header: None,
+            value: None,
+            client_id: None,
+            client_secret: None,
+            refresh_token: None,
+            token_url: None,
+            access_key_id: None,
+            secret_access_key: None,
+            session_token: None,
+            region: None,
+            service: None,
+            credentials_file: None,
+            scope: None,
+            pool: None,
+        }
+    }
+}
+
 fn default_auth_type() -> String {
     "api_key".to_string()
 }
@@ -57,19 +115,112 @@ fn is_default_auth_type(s: &String) -> bool {
 /// - false: 폴백 없음
 /// - true: 원본 모델명 그대로 Anthropic API로 폴백
 /// - "모델명": 지정된 모델명으로 교체 후 Anthropic API로 폴백
-#[derive(Debug, Clone)]
+/// - 목록: 순서대로 시도하는 폴백 체인 (대상별 트리거)
+#[derive(Debug, Clone, Default)]
 pub enum Fallback {
     /// 폴백 비활성화
     Disabled,
     /// 원본 모델명 그대로 폴백
+    #[default]
     Passthrough,
     /// 지정된 모델명으로 교체 후 폴백
     Model(String),
+    /// 폴백 체인
+    Chain(Vec<FallbackTarget>),
 }
 
-impl Default for Fallback {
-    fn default() -> Self {
-        Fallback::Passthrough
+/// 폴백 체인의 대상 하나
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct FallbackTarget {
+    /// 대상 업스트림 (생략 시 default.url, 즉 Anthropic API 패스스루)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub upstream: Option<UpstreamConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transformer: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model_map: Option<String>,
+    /// 직전 시도가 어떤 실패일 때 이 대상을 시도할지 (해당하지 않으면 건너뛰고 다음 대상 판정)
+    #[serde(default = "default_triggers")]
+    pub triggers: Vec<FallbackTrigger>,
+}
+
+/// 폴백 트리거
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum FallbackTrigger {
+    /// 연결 실패 등 쓸 수 있는 응답을 받지 못한 경우 (응답 변환 실패 포함)
+    #[serde(rename = "connect_error")]
+    ConnectError,
+    /// 타임아웃 (업스트림 408/504 응답 포함)
+    #[serde(rename = "timeout")]
+    Timeout,
+    #[serde(rename = "429")]
+    RateLimited,
+    #[serde(rename = "5xx")]
+    ServerError,
+    /// 429 외 4xx (기본 트리거 아님)
+    #[serde(rename = "4xx")]
+    ClientError,
+}
+
+fn default_triggers() -> Vec<FallbackTrigger> {
+    vec![
+        FallbackTrigger::ConnectError,
+        FallbackTrigger::Timeout,
+        FallbackTrigger::RateLimited,
+        FallbackTrigger::ServerError,
+    ]
+}
+
+/// 업스트림 시도의 실패 종류
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpstreamFailure {
+    Connect,
+    Timeout,
+    Status(u16),
+    /// 업스트림 연결과 무관한 프록시 쪽 실패 (응답 파싱/변환, 토큰 발급 등 — 재시도와 서킷 집계에서 제외)
+    Local,
+}
+
+impl FallbackTrigger {
+    pub fn matches(&self, failure: UpstreamFailure) -> bool {
+        match (self, failure) {
+            (FallbackTrigger::ConnectError, UpstreamFailure::Connect | UpstreamFailure::Local) => true,
+            (FallbackTrigger::Timeout, UpstreamFailure::Timeout) => true,
+            (FallbackTrigger::Timeout, UpstreamFailure::Status(s)) => s == 408 || s == 504,
+            (FallbackTrigger::RateLimited, UpstreamFailure::Status(s)) => s == 429,
+            (FallbackTrigger::ServerError, UpstreamFailure::Status(s)) => (500..600).contains(&s),
+            (FallbackTrigger::ClientError, UpstreamFailure::Status(s)) => (400..500).contains(&s) && s != 429,
+            _ => false,
+        }
+    }
+}
+
+impl FallbackTarget {
+    /// 이 대상으로 넘어올 실패인지 확인
+    pub fn triggered_by(&self, failure: UpstreamFailure) -> bool {
+        self.triggers.iter().any(|t| t.matches(failure))
+    }
+
+    /// 대상을 단일 라우트로 변환 (upstream 생략 시 None → 기본 업스트림 패스스루)
+    pub fn to_route(&self) -> Option<RouteConfig> {
+        Some(RouteConfig {
+            match_pattern: String::new(),
+            matcher: None,
+            when: None,
+            upstream: self.upstream.clone()?,
+            transformer: self.transformer.clone(),
+            model_map: self.model_map.clone(),
+            fallback: Fallback::Disabled,
+            concurrency: None,
+            account_concurrency: None,
+            upstreams: vec![],
+            balance: Default::default(),
+            circuit_breaker: None,
+            retry: None,
+            timeouts: None,
+            stream_recovery: false,
+            reasoning_effort: Default::default(),
+        })
     }
 }
 
@@ -79,6 +230,21 @@ impl Fallback {
         !matches!(self, Fallback::Disabled)
     }
 
+    /// 체인 대상의 인증/Vertex 설정 검증
+    fn validate(&self) -> Result<(), String> {
+        let Fallback::Chain(targets) = self else {
+            return Ok(());
+        };
+        for (i, upstream) in targets.iter().enumerate().filter_map(|(i, t)| Some((i, t.upstream.as_ref()?))) {
+            upstream
+                .auth
+                .validate()
+                .and_then(|_| upstream.vertex.as_ref().map_or(Ok(()), VertexConfig::validate))
+                .map_err(|e| format!("fallback[{i}]: {e}"))?;
+        }
+        Ok(())
+    }
+
     /// 폴백 시 교체할 모델명 (None이면 원본 유지)
     pub fn model(&self) -> Option<&str> {
         match self {
@@ -94,6 +260,7 @@ impl Serialize for Fallback {
             Fallback::Disabled => serializer.serialize_bool(false),
             Fallback::Passthrough => serializer.serialize_bool(true),
             Fallback::Model(m) => serializer.serialize_str(m),
+            Fallback::Chain(targets) => targets.serialize(serializer),
         }
     }
 }
@@ -106,7 +273,14 @@ impl<'de> Deserialize<'de> for Fallback {
             serde_yaml::Value::Bool(false) => Ok(Fallback::Disabled),
             serde_yaml::Value::String(s) if s.is_empty() => Ok(Fallback::Disabled),
             serde_yaml::Value::String(s) => Ok(Fallback::Model(s)),
-            _ => Err(serde::de::Error::custom("fallback은 bool 또는 모델명 문자열이어야 합니다")),
+            serde_yaml::Value::Sequence(_) => {
+                let targets: Vec<FallbackTarget> = serde_yaml::from_value(value).map_err(serde::de::Error::custom)?;
+                if targets.is_empty() {
+                    return Err(serde::de::Error::custom("fallback 체인이 비어 있습니다"));
+                }
+                Ok(Fallback::Chain(targets))
+            }
+            _ => Err(serde::de::Error::custom("fallback은 bool, 모델명 문자열 또는 대상 목록이어야 합니다")),
         }
     }
 }
@@ -115,6 +289,61 @@ fn is_default_fallback(f: &Fallback) -> bool {
     matches!(f, Fallback::Passthrough)
 }
 
+/// thinking이 켜진 요청에 보낼 추론 강도 (`reasoning_effort` / `reasoning.effort`)
+/// - false: 보내지 않음 (기본값 — 추론 모델이 아니면 400으로 거부하는 제공자가 많음)
+/// - true: thinking.budget_tokens로 단계 결정 (low/medium/high)
+/// - "단계": 지정된 값 그대로 전달 (예: "minimal", "high")
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum ReasoningEffort {
+    #[default]
+    Off,
+    /// budget_tokens에서 단계 결정
+    Budget,
+    /// 고정 단계
+    Level(String),
+}
+
+impl ReasoningEffort {
+    pub fn is_off(&self) -> bool {
+        matches!(self, ReasoningEffort::Off)
+    }
+
+    /// thinking budget_tokens에 대응하는 강도 (None이면 보내지 않음)
+    pub fn level(&self, budget: u64) -> Option<&str> {
+        match self {
+            ReasoningEffort::Off => None,
+            ReasoningEffort::Budget => Some(match budget {
+                0..4096 => "low",
+                4096..16384 => "medium",
+                _ => "high",
+            }),
+            ReasoningEffort::Level(level) => Some(level),
+        }
+    }
+}
+
+impl Serialize for ReasoningEffort {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            ReasoningEffort::Off => serializer.serialize_bool(false),
+            ReasoningEffort::Budget => serializer.serialize_bool(true),
+            ReasoningEffort::Level(level) => serializer.serialize_str(level),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ReasoningEffort {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match serde_yaml::Value::deserialize(deserializer)? {
+            serde_yaml::Value::Bool(true) => Ok(ReasoningEffort::Budget),
+            serde_yaml::Value::Bool(false) => Ok(ReasoningEffort::Off),
+            serde_yaml::Value::String(s) if s.is_empty() => Ok(ReasoningEffort::Off),
+            serde_yaml::Value::String(s) => Ok(ReasoningEffort::Level(s)),
+            _ => Err(serde::de::Error::custom("reasoning_effort는 bool 또는 단계 문자열이어야 합니다")),
+        }
+    }
+}
+
 impl AuthConfig {
     /// 하위 호환: header/value를 직접 반환 (api_key 방식)
     pub fn header_name(&self) -> &str {
@@ -125,6 +354,66 @@ impl AuthConfig {
         self.value.as_deref().unwrap_or("")
     }
 
+    /// 보낼 인증 값이 있는지 (없으면 인증 헤더 생략)
+    pub fn has_credentials(&self) -> bool {
+        !self.header_value().is_empty() || self.has_pool() || self.uses_token_manager()
+    }
+
+    /// OAuth 토큰 발급 방식인지 확인
+    pub fn is_oauth(&self) -> bool {
+        self.auth_type == "oauth"
+    }
+
+    /// Google 서비스 계정 방식인지 확인
+    pub fn is_gcp_service_account(&self) -> bool {
+        self.auth_type == "gcp_service_account"
+    }
+
+    /// 액세스 토큰을 발급받아 쓰는 방식인지 (oauth, gcp_service_account)
+    pub fn uses_token_manager(&self) -> bool {
+        self.is_oauth() || self.is_gcp_service_account()
+    }
+
+    /// AWS SigV4 서명 방식인지 확인
+    pub fn is_sigv4(&self) -> bool {
+        self.auth_type == "aws_sigv4"
+    }
+
+    /// 인증 타입별 필수 필드 검증
+    pub fn validate(&self) -> Result<(), String> {
+        if self.is_sigv4() {
+            for (name, value) in [
+                ("access_key_id", &self.access_key_id),
+                ("secret_access_key", &self.secret_access_key),
+                ("region", &self.region),
+            ] {
+                if value.as_deref().is_none_or(str::is_empty) {
+                    return Err(format!("aws_sigv4 인증에 {name}이(가) 필요합니다"));
+                }
+            }
+        }
+        if self.is_oauth() {
+            let present = |v: &Option<String>| v.as_deref().is_some_and(|s| !s.is_empty());
+            if !present(&self.token_url) {
+                return Err("oauth 인증에 token_url이 필요합니다".into());
+            }
+            let client_credentials = present(&self.client_id) && present(&self.client_secret);
+            if !present(&self.refresh_token) && !client_credentials {
+                return Err("oauth 인증에 refresh_token 또는 client_id/client_secret이 필요합니다".into());
+            }
+        }
+        if self.is_gcp_service_account() {
+            match self.credentials_file.as_deref().filter(|f| !f.is_empty()) {
+                None => return Err("gcp_service_account 인증에 credentials_file이 필요합니다".into()),
+                Some(file) if !std::path::Path::new(file).is_file() => {
+                    return Err(format!("서비스 계정 키 파일을 찾을 수 없습니다: {file}"));
+                }
+                Some(_) => {}
+            }
+        }
+        Ok(())
+    }
+
     /// 키 풀이 설정되어 있는지 확인
     pub fn has_pool(&self) -> bool {
         self.pool.as_ref().is_some_and(|p| !p.is_empty())
@@ -141,29 +430,126 @@ impl AuthConfig {
 }
 
 /// 업스트림 제공자 설정
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
 pub struct UpstreamConfig {
     pub url: String,
+    /// 인증 (생략 시 인증 헤더 없음 — Ollama 등 로컬 서버)
+    #[serde(default)]
     pub auth: AuthConfig,
+    /// Vertex AI 모드 (지정 시 `/v1/projects/{project}/locations/{location}/publishers/...` 경로 사용)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vertex: Option<VertexConfig>,
+    /// 변환된 요청의 경로 템플릿 (`{model}`은 model_map 또는 원본 모델명으로 치환)
+    /// 예: Azure OpenAI `"/openai/deployments/{model}/chat/completions"`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// 변환된 요청에 추가할 쿼리 파라미터 (예: `api-version`)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub query: BTreeMap<String, String>,
+}
+
+impl UpstreamConfig {
+    /// `upstreams`만 쓰는 라우트처럼 단일 업스트림이 지정되지 않았는지
+    pub fn is_unset(&self) -> bool {
+        self.url.is_empty()
+    }
+
+    /// 트랜스포머가 만든 경로에 라우트의 경로 템플릿과 쿼리 파라미터 적용
+    pub fn request_path(&self, transformed: &str, model: &str) -> String {
+        let mut path = match &self.path {
+            Some(template) => expand_path_template(template, model),
+            None => transformed.to_string(),
+        };
+        for (key, value) in &self.query {
+            path.push(if path.contains('?') { '&' } else { '?' });
+            path.push_str(&format!("{}={}", uri_encode(key), uri_encode(value)));
+        }
+        path
+    }
+}
+
+/// Vertex AI 모델 경로 설정
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct VertexConfig {
+    pub project: String,
+    /// 리전 (예: "us-central1", "global")
+    pub location: String,
+    /// 모델 제공자: "google" (기본, Gemini) 또는 "anthropic" (Claude 패스스루)
+    #[serde(default = "default_publisher")]
+    pub publisher: String,
+}
+
+fn default_publisher() -> String {
+    "google".to_string()
+}
+
+impl VertexConfig {
+    /// `/v1/projects/{project}/locations/{location}/publishers/{publisher}/models/{model}:{method}`
+    pub fn model_path(&self, model: &str, method: &str) -> String {
+        format!(
+            "/v1/projects/{}/locations/{}/publishers/{}/models/{model}:{method}",
+            self.project, self.location, self.publisher
+        )
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.project.is_empty() || self.location.is_empty() {
+            return Err("vertex 설정에 project와 location이 필요합니다".into());
+        }
+        Ok(())
+    }
 }
 
 /// 라우팅 규칙
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct RouteConfig {
-    /// 모델명 부분 문자열 매칭 패턴
+    /// 모델명 매칭 패턴
+    /// - 접두사 없음: 부분 문자열 포함 (하위 호환)
+    /// - `exact:`, `prefix:`, `glob:`, `regex:` 명시 모드
     #[serde(rename = "match")]
     pub match_pattern: String,
+    /// 로드 시 컴파일된 매처 (None이면 매칭 시 match_pattern을 해석)
+    #[serde(skip)]
+    pub matcher: Option<ModelMatch>,
+    /// 추가 매칭 조건 (모두 충족해야 매칭, 생략 시 모델명만으로 결정)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub when: Option<RouteConditions>,
+    /// 단일 업스트림 (`upstreams`를 쓰면 생략)
+    #[serde(default, skip_serializing_if = "UpstreamConfig::is_unset")]
     pub upstream: UpstreamConfig,
-    /// 트랜스포머 이름: "openai", "gemini" 등 (None이면 패스스루)
+    /// 복수 업스트림 부하 분산 (지정 시 `upstream` 대신 사용)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub upstreams: Vec<BalancedUpstream>,
+    /// `upstreams` 선택 전략 (기본값: weighted)
+    #[serde(default, skip_serializing_if = "BalanceStrategy::is_default")]
+    pub balance: BalanceStrategy,
+    /// 서킷 브레이커 (생략 시 비활성, `{}`면 기본값으로 활성화)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub circuit_breaker: Option<CircuitBreakerConfig>,
+    /// 일시적 실패 시 같은 업스트림 재시도 정책 (생략 시 재시도 없음)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry: Option<RetryConfig>,
+    /// 연결/첫 바이트/스트림 유휴/대기열 타임아웃 (생략한 항목은 default.timeouts → 기본값)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeouts: Option<TimeoutConfig>,
+    /// 스트리밍 응답이 중간에 끊기면 폴백 대상으로 이어받기 (기본값: false)
+    /// 이미 보낸 텍스트를 assistant prefill로 붙여 재요청하고 같은 클라이언트 스트림에 이어 붙인다.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub stream_recovery: bool,
+    /// thinking 요청에 추론 강도 전달 (openai/responses 트랜스포머, 기본값: 보내지 않음)
+    #[serde(default, skip_serializing_if = "ReasoningEffort::is_off")]
+    pub reasoning_effort: ReasoningEffort,
+    /// 트랜스포머 이름: "openai", "gemini", "responses", "ollama", "bedrock" 또는 `transformers:` 정의 (None이면 패스스루)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub transformer: Option<String>,
     /// 업스트림 모델명 (원본 모델명을 이 값으로 교체)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub model_map: Option<String>,
-    /// 외부 제공자 실패 시 Anthropic API로 폴백
+    /// 외부 제공자 실패 시 폴백
     /// - false: 폴백 없음
-    /// - true: 원본 모델명 그대로 폴백 (기본값)
-    /// - "모델명": 지정된 모델명으로 교체 후 폴백
+    /// - true: 원본 모델명 그대로 default.url로 폴백 (기본값)
+    /// - "모델명": 지정된 모델명으로 교체 후 default.url로 폴백
+    /// - 목록: 대상별 upstream/트리거를 가진 폴백 체인 (순서대로 시도)
     #[serde(default, skip_serializing_if = "is_default_fallback")]
     pub fallback: Fallback,
     /// API 키당 동시 요청 제한 (기본값: 제한 없음)
@@ -176,12 +562,653 @@ pub struct RouteConfig {
     pub account_concurrency: Option<usize>,
 }
 
+/// 서킷 브레이커 설정 (라우트, 키 풀의 키, `upstreams` 항목별로 따로 동작)
+///
+/// 연속 실패 또는 최근 요청의 오류율이 기준을 넘으면 서킷을 열어 `open_secs` 동안
+/// 업스트림을 건너뛰고 바로 폴백한다. 이후 요청 1건을 시험 요청으로 보내 복구를 확인한다.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct CircuitBreakerConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// 서킷을 여는 연속 실패 횟수
+    #[serde(default = "default_failure_threshold")]
+    pub failure_threshold: u32,
+    /// 서킷을 여는 최근 요청 오류율 (0.0~1.0)
+    #[serde(default = "default_error_rate")]
+    pub error_rate: f64,
+    /// 오류율 판정에 필요한 최소 요청 수
+    #[serde(default = "default_min_requests")]
+    pub min_requests: usize,
+    /// 오류율 계산에 쓰는 최근 요청 수
+    #[serde(default = "default_window")]
+    pub window: usize,
+    /// 서킷이 열린 뒤 시험 요청까지 대기 시간 (초)
+    #[serde(default = "default_open_secs")]
+    pub open_secs: u64,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_failure_threshold() -> u32 {
+    5
+}
+
+fn default_error_rate() -> f64 {
+    0.5
+}
+
+fn default_min_requests() -> usize {
+    10
+}
+
+fn default_window() -> usize {
+    20
+}
+
+fn default_open_secs() -> u64 {
+    30
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        CircuitBreakerConfig {
+            enabled: true,
+            failure_threshold: default_failure_threshold(),
+            error_rate: default_error_rate(),
+            min_requests: default_min_requests(),
+            window: default_window(),
+            open_secs: default_open_secs(),
+        }
+    }
+}
+
+impl CircuitBreakerConfig {
+    fn validate(&self) -> Result<(), String> {
+        if self.failure_threshold == 0 {
+            return Err("circuit_breaker.failure_threshold는 1 이상이어야 합니다".into());
+        }
+        if !(self.error_rate > 0.0 && self.error_rate <= 1.0) {
+            return Err("circuit_breaker.error_rate는 0보다 크고 1 이하여야 합니다".into());
+        }
+        if self.min_requests == 0 || self.min_requests > self.window {
+            return Err("circuit_breaker.min_requests는 1 이상, window 이하여야 합니다".into());
+        }
+        Ok(())
+    }
+}
+
+/// 타임아웃 설정 (초)
+///
+/// 초과 시 504(계정 대기열은 503) Anthropic 에러로 응답하며, 폴백 체인의 `timeout` 트리거에 해당한다.
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+pub struct TimeoutConfig {
+    /// TCP 연결 (기본값: 10)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub connect_secs: Option<u64>,
+    /// 요청 전송 후 응답 헤더 수신까지 (기본값: 600)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub first_byte_secs: Option<u64>,
+    /// 응답 본문 청크 사이 최대 간격 (기본값: 300)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub idle_secs: Option<u64>,
+    /// account_concurrency 대기열 (기본값: 120)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub queue_secs: Option<u64>,
+}
+
+const DEFAULT_CONNECT_TIMEOUT_SECS: u64 = 10;
+const DEFAULT_FIRST_BYTE_TIMEOUT_SECS: u64 = 600;
+const DEFAULT_IDLE_TIMEOUT_SECS: u64 = 300;
+const DEFAULT_QUEUE_TIMEOUT_SECS: u64 = 120;
+
+impl TimeoutConfig {
+    /// 지정하지 않은 항목을 `base`의 값으로 채움
+    pub fn or(&self, base: &TimeoutConfig) -> TimeoutConfig {
+        TimeoutConfig {
+            connect_secs: self.connect_secs.or(base.connect_secs),
+            first_byte_secs: self.first_byte_secs.or(base.first_byte_secs),
+            idle_secs: self.idle_secs.or(base.idle_secs),
+            queue_secs: self.queue_secs.or(base.queue_secs),
+        }
+    }
+
+    pub fn connect(&self) -> Duration {
+        Duration::from_secs(self.connect_secs.unwrap_or(DEFAULT_CONNECT_TIMEOUT_SECS))
+    }
+
+    pub fn first_byte(&self) -> Duration {
+        Duration::from_secs(self.first_byte_secs.unwrap_or(DEFAULT_FIRST_BYTE_TIMEOUT_SECS))
+    }
+
+    pub fn idle(&self) -> Duration {
+        Duration::from_secs(self.idle_secs.unwrap_or(DEFAULT_IDLE_TIMEOUT_SECS))
+    }
+
+    pub fn queue(&self) -> Duration {
+        Duration::from_secs(self.queue_secs.unwrap_or(DEFAULT_QUEUE_TIMEOUT_SECS))
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        for (name, value) in [
+            ("connect_secs", self.connect_secs),
+            ("first_byte_secs", self.first_byte_secs),
+            ("idle_secs", self.idle_secs),
+            ("queue_secs", self.queue_secs),
+        ] {
+            if value == Some(0) {
+                return Err(format!("timeouts.{name}는 1 이상이어야 합니다"));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 일시적 업스트림 실패 재시도 정책
+///
+/// 응답을 클라이언트로 보내기 전(상태 코드를 받은 시점)에만 재시도하므로
+/// 스트리밍이 시작된 응답은 다시 보내지 않는다.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct RetryConfig {
+    /// 최초 시도를 포함한 최대 시도 횟수
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+    /// 첫 재시도 전 대기 (ms, 이후 2배씩 증가)
+    #[serde(default = "default_initial_backoff_ms")]
+    pub initial_backoff_ms: u64,
+    /// 대기 상한 (ms)
+    #[serde(default = "default_max_backoff_ms")]
+    pub max_backoff_ms: u64,
+    /// 대기 시간에 무작위 편차 적용 (대기의 절반~전체)
+    #[serde(default = "default_true")]
+    pub jitter: bool,
+    /// 재시도할 응답 상태 코드
+    #[serde(default = "default_retry_statuses")]
+    pub statuses: Vec<u16>,
+    /// 연결 실패(연결 거부/리셋 등) 재시도
+    #[serde(default = "default_true")]
+    pub connect_error: bool,
+    /// 타임아웃 재시도
+    #[serde(default = "default_true")]
+    pub timeout: bool,
+    /// Retry-After가 이 값(초)보다 길면 재시도하지 않고 응답 반환 (폴백 판정으로 넘김)
+    #[serde(default = "default_max_retry_after_secs")]
+    pub max_retry_after_secs: u64,
+}
+
+fn default_max_attempts() -> u32 {
+    3
+}
+
+fn default_initial_backoff_ms() -> u64 {
+    500
+}
+
+fn default_max_backoff_ms() -> u64 {
+    8_000
+}
+
+fn default_retry_statuses() -> Vec<u16> {
+    vec![500, 502, 503, 504, 529]
+}
+
+fn default_max_retry_after_secs() -> u64 {
+    30
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        RetryConfig {
+            max_attempts: default_max_attempts(),
+            initial_backoff_ms: default_initial_backoff_ms(),
+            max_backoff_ms: default_max_backoff_ms(),
+            jitter: true,
+            statuses: default_retry_statuses(),
+            connect_error: true,
+            timeout: true,
+            max_retry_after_secs: default_max_retry_after_secs(),
+        }
+    }
+}
+
+impl RetryConfig {
+    /// 실패 종류가 재시도 대상인지
+    pub fn retries(&self, failure: UpstreamFailure) -> bool {
+        match failure {
+            UpstreamFailure::Connect => self.connect_error,
+            UpstreamFailure::Timeout => self.timeout,
+            UpstreamFailure::Status(s) => self.statuses.contains(&s),
+            UpstreamFailure::Local => false,
+        }
+    }
+
+    /// `attempt`번째 시도 실패 후 대기 시간 (None이면 Retry-After가 상한을 넘어 재시도 안 함)
+    ///
+    /// `random`은 지터용 난수. Retry-After가 있으면 백오프보다 짧게 기다리지 않는다.
+    pub fn delay(&self, attempt: u32, retry_after_secs: Option<u64>, random: u64) -> Option<Duration> {
+        let exp = self
+            .initial_backoff_ms
+            .saturating_mul(1u64 << attempt.saturating_sub(1).min(20))
+            .min(self.max_backoff_ms);
+        let backoff = match self.jitter {
+            true if exp > 0 => exp / 2 + random % (exp - exp / 2 + 1),
+            _ => exp,
+        };
+        let backoff = Duration::from_millis(backoff);
+        match retry_after_secs {
+            Some(secs) if secs > self.max_retry_after_secs => None,
+            Some(secs) => Some(backoff.max(Duration::from_secs(secs))),
+            None => Some(backoff),
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.max_attempts == 0 {
+            return Err("retry.max_attempts는 1 이상이어야 합니다".into());
+        }
+        if self.initial_backoff_ms > self.max_backoff_ms {
+            return Err("retry.initial_backoff_ms가 max_backoff_ms보다 큽니다".into());
+        }
+        Ok(())
+    }
+}
+
+/// 부하 분산 대상 업스트림 (`upstreams` 항목)
+///
+/// `transformer`/`model_map`을 생략하면 라우트의 값을 그대로 사용
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct BalancedUpstream {
+    #[serde(flatten)]
+    pub upstream: UpstreamConfig,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transformer: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model_map: Option<String>,
+    /// weighted 전략의 가중치 (기본값: 1, 0이면 다른 대상이 모두 불가할 때만 사용)
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+    /// 상대 비용 (cheapest 전략, 예: 100만 토큰당 가격 — 생략 시 가장 비싼 것으로 취급)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cost: Option<f64>,
+    /// 이 업스트림의 동시 요청 제한 (기본값: 제한 없음)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub concurrency: Option<usize>,
+}
+
+fn default_weight() -> u32 {
+    1
+}
+
+/// `upstreams` 선택 전략
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum BalanceStrategy {
+    /// 가중치 비례 무작위
+    #[default]
+    Weighted,
+    /// 응답 지연(EWMA)이 가장 짧은 대상 (측정 전 대상 우선)
+    LeastLatency,
+    /// 진행 중 요청이 가장 적은 대상
+    LeastInFlight,
+    /// cost가 가장 낮은 대상 (동률이면 가중치 비례)
+    Cheapest,
+}
+
+impl BalanceStrategy {
+    fn is_default(&self) -> bool {
+        *self == BalanceStrategy::Weighted
+    }
+}
+
+/// 모델명 매칭 방식 (`match` 필드의 접두사로 지정)
+#[derive(Debug, Clone)]
+pub enum ModelMatch {
+    /// 부분 문자열 포함 (접두사 없음)
+    Contains(String),
+    /// `exact:` 정확히 일치
+    Exact(String),
+    /// `prefix:` 접두사 일치
+    Prefix(String),
+    /// `glob:`(`*`, `?` → 캡처 그룹) 또는 `regex:`
+    Pattern(Regex),
+}
+
+impl ModelMatch {
+    /// `match` 문자열 해석 (glob/regex 컴파일 오류는 Err)
+    pub fn parse(pattern: &str) -> Result<Self, String> {
+        let compile = |re: &str| Regex::new(re).map_err(|e| format!("잘못된 매칭 패턴 '{pattern}': {e}"));
+        if let Some(p) = pattern.strip_prefix("exact:") {
+            Ok(ModelMatch::Exact(p.to_string()))
+        } else if let Some(p) = pattern.strip_prefix("prefix:") {
+            Ok(ModelMatch::Prefix(p.to_string()))
+        } else if let Some(p) = pattern.strip_prefix("glob:") {
+            Ok(ModelMatch::Pattern(compile(&glob_to_regex(p))?))
+        } else if let Some(p) = pattern.strip_prefix("regex:") {
+            Ok(ModelMatch::Pattern(compile(p)?))
+        } else {
+            Ok(ModelMatch::Contains(pattern.to_string()))
+        }
+    }
+
+    pub fn is_match(&self, model: &str) -> bool {
+        match self {
+            ModelMatch::Contains(p) => model.contains(p.as_str()),
+            ModelMatch::Exact(p) => model == p,
+            ModelMatch::Prefix(p) => model.starts_with(p.as_str()),
+            ModelMatch::Pattern(re) => re.is_match(model),
+        }
+    }
+
+    /// 캡처 그룹(`$1`, `${name}`)을 템플릿에 치환 (패턴 모드가 아니거나 매칭 실패 시 원본)
+    pub fn expand(&self, model: &str, template: &str) -> String {
+        match self {
+            ModelMatch::Pattern(re) => match re.captures(model) {
+                Some(caps) => {
+                    let mut out = String::new();
+                    caps.expand(template, &mut out);
+                    out
+                }
+                None => template.to_string(),
+            },
+            _ => template.to_string(),
+        }
+    }
+}
+
+/// glob → 전체 일치 정규식 (`*` → `(.*)`, `?` → `(.)`)
+fn glob_to_regex(glob: &str) -> String {
+    let mut re = String::from("^");
+    for c in glob.chars() {
+        match c {
+            '*' => re.push_str("(.*)"),
+            '?' => re.push_str("(.)"),
+            c => re.push_str(&regex::escape(c.encode_utf8(&mut [0; 4]))),
+        }
+    }
+    re.push('$');
+    re
+}
+
+/// 모델명 외의 요청 속성 조건 (지정한 항목만 검사)
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+pub struct RouteConditions {
+    /// tools 유무
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tools: Option<bool>,
+    /// 이미지 블록 유무 (tool_result 안의 이미지 포함)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub images: Option<bool>,
+    /// 확장 사고(thinking) 활성화 여부
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thinking: Option<bool>,
+    /// 추정 입력 토큰 하한 (이상)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_input_tokens: Option<u32>,
+    /// 추정 입력 토큰 상한 (미만)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_input_tokens: Option<u32>,
+    /// 요청 헤더 값 일치 (이름은 대소문자 무시)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+    /// system 프롬프트에 포함되어야 하는 문자열 (예: 프로젝트 경로)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_contains: Option<String>,
+}
+
+impl RouteConditions {
+    pub fn matches(&self, req: &RequestAttributes) -> bool {
+        let flag = |cond: Option<bool>, actual: bool| cond.is_none_or(|c| c == actual);
+        flag(self.tools, req.has_tools)
+            && flag(self.images, req.has_images)
+            && flag(self.thinking, req.thinking)
+            && self.min_input_tokens.is_none_or(|min| req.input_tokens >= min)
+            && self.max_input_tokens.is_none_or(|max| req.input_tokens < max)
+            && self.headers.iter().all(|(name, value)| {
+                req.headers
+                    .iter()
+                    .any(|(k, v)| k.eq_ignore_ascii_case(name) && v == value)
+            })
+            && self
+                .system_contains
+                .as_deref()
+                .is_none_or(|s| req.system.contains(s))
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if let (Some(min), Some(max)) = (self.min_input_tokens, self.max_input_tokens) {
+            if min >= max {
+                return Err(format!("when: min_input_tokens({min})는 max_input_tokens({max})보다 작아야 합니다"));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 라우트 조건 평가에 쓰는 요청 속성
+#[derive(Debug, Default)]
+pub struct RequestAttributes {
+    pub model: String,
+    pub has_tools: bool,
+    pub has_images: bool,
+    pub thinking: bool,
+    /// 추정 입력 토큰 수
+    pub input_tokens: u32,
+    /// system 프롬프트 텍스트 (블록 배열이면 이어붙임)
+    pub system: String,
+    pub headers: Vec<(String, String)>,
+}
+
+impl RequestAttributes {
+    /// 모델명만 있는 속성 (조건 없는 라우트 검색용)
+    pub fn model_only(model: &str) -> Self {
+        RequestAttributes {
+            model: model.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+impl RouteConfig {
+    fn model_match(&self) -> Cow<'_, ModelMatch> {
+        match &self.matcher {
+            Some(m) => Cow::Borrowed(m),
+            None => Cow::Owned(
+                ModelMatch::parse(&self.match_pattern)
+                    .unwrap_or_else(|_| ModelMatch::Exact(self.match_pattern.clone())),
+            ),
+        }
+    }
+
+    /// 요청이 이 라우트에 매칭되는지 확인 (모델명 + when 조건)
+    pub fn matches(&self, req: &RequestAttributes) -> bool {
+        self.model_match().is_match(&req.model) && self.when.as_ref().is_none_or(|w| w.matches(req))
+    }
+
+    /// `upstreams`의 한 대상을 단일 업스트림 라우트로 변환
+    /// (transformer/model_map 생략 시 라우트 값 상속, retry는 라우트 값 사용, 폴백은 원래 라우트에서 처리하되
+    /// stream_recovery가 켜져 있으면 스트림 이어받기 대상으로 폴백 설정을 유지)
+    pub fn member_route(&self, idx: usize) -> Option<RouteConfig> {
+        let member = self.upstreams.get(idx)?;
+        Some(RouteConfig {
+            match_pattern: self.match_pattern.clone(),
+            matcher: self.matcher.clone(),
+            when: None,
+            upstream: member.upstream.clone(),
+            transformer: member.transformer.clone().or_else(|| self.transformer.clone()),
+            model_map: member.model_map.clone().or_else(|| self.model_map.clone()),
+            fallback: if self.stream_recovery { self.fallback.clone() } else { Fallback::Disabled },
+            concurrency: member.concurrency,
+            account_concurrency: None,
+            upstreams: vec![],
+            balance: BalanceStrategy::default(),
+            circuit_breaker: None,
+            retry: self.retry.clone(),
+            timeouts: self.timeouts.clone(),
+            stream_recovery: self.stream_recovery,
+            reasoning_effort: self.reasoning_effort.clone(),
+        })
+    }
+
+    /// 목록 표시용 업스트림 요약 (복수면 URL 나열)
+    pub fn upstream_summary(&self) -> String {
+        match self.upstreams.as_slice() {
+            [] => self.upstream.url.clone(),
+            members => members.iter().map(|m| m.upstream.url.as_str()).collect::<Vec<_>>().join(", "),
+        }
+    }
+
+    /// 단일 업스트림 또는 `upstreams` 목록의 인증/Vertex 설정 검증
+    fn validate_upstreams(&self) -> Result<(), String> {
+        let check = |upstream: &UpstreamConfig| {
+            upstream
+                .auth
+                .validate()
+                .and_then(|_| upstream.vertex.as_ref().map_or(Ok(()), VertexConfig::validate))
+        };
+        if self.upstreams.is_empty() {
+            if self.upstream.is_unset() {
+                return Err("upstream 또는 upstreams가 필요합니다".into());
+            }
+            return check(&self.upstream);
+        }
+        if !self.upstream.is_unset() {
+            return Err("upstream과 upstreams는 함께 쓸 수 없습니다".into());
+        }
+        for (i, member) in self.upstreams.iter().enumerate() {
+            check(&member.upstream)
+                .and_then(|_| match member.upstream.auth.has_pool() {
+                    true => Err("upstreams 항목에는 auth.pool을 쓸 수 없습니다 (항목을 나눠 지정)".into()),
+                    false => Ok(()),
+                })
+                .map_err(|e| format!("upstreams[{i}]: {e}"))?;
+        }
+        Ok(())
+    }
+
+    /// model_map의 캡처 그룹 참조를 요청 모델명으로 치환한 라우트
+    /// (참조가 없으면 복제하지 않음)
+    pub fn resolve_for(&self, model: &str) -> Cow<'_, RouteConfig> {
+        match self.model_map.as_deref().filter(|m| m.contains('$')) {
+            Some(template) => {
+                let mut route = self.clone();
+                route.model_map = Some(self.model_match().expand(model, template));
+                Cow::Owned(route)
+            }
+            None => Cow::Borrowed(self),
+        }
+    }
+}
+
+/// 응답 필드 매핑 (JSON Pointer, RFC 6901)
+///
+/// 지정하지 않은 항목은 기반 트랜스포머의 변환 결과를 그대로 사용
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct ResponseMapping {
+    /// 응답 텍스트 (예: "/choices/0/message/content")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    /// 종료 사유 (예: "/choices/0/finish_reason")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finish_reason: Option<String>,
+    /// 입력 토큰 수 (예: "/usage/prompt_tokens")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input_tokens: Option<String>,
+    /// 출력 토큰 수 (예: "/usage/completion_tokens")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_tokens: Option<String>,
+}
+
+impl ResponseMapping {
+    /// 지정된 매핑이 하나도 없는지 확인
+    pub fn is_empty(&self) -> bool {
+        self.text.is_none()
+            && self.finish_reason.is_none()
+            && self.input_tokens.is_none()
+            && self.output_tokens.is_none()
+    }
+
+    fn pointers(&self) -> impl Iterator<Item = &String> {
+        [&self.text, &self.finish_reason, &self.input_tokens, &self.output_tokens]
+            .into_iter()
+            .flatten()
+    }
+}
+
+/// 선언형 트랜스포머 정의 (`transformers:` 섹션)
+///
+/// 내장 트랜스포머(`base`)의 변환 결과를 필드 단위로 보정한다.
+/// 필드명이 조금 다른 OpenAI/Gemini 호환 제공자를 코드 수정 없이 추가하기 위한 용도.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct TransformerConfig {
+    /// 라우트의 `transformer`에서 참조할 이름 (내장 이름과 같으면 이 정의가 우선)
+    pub name: String,
+    /// 기반 내장 트랜스포머: "openai" (기본), "gemini", "responses", "ollama", "bedrock"
+    #[serde(default = "default_transformer_base")]
+    pub base: String,
+    /// 요청 경로 템플릿 (`{model}` 치환), 예: "/v2/chat?model={model}"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// 스트리밍 요청 경로 템플릿 (없으면 `path` 사용)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream_path: Option<String>,
+    /// 요청 최상위 필드 이름 변경 (예: max_tokens → max_completion_tokens)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub rename: BTreeMap<String, String>,
+    /// 요청에서 제거할 최상위 필드
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub remove: Vec<String>,
+    /// 요청에 없으면 추가할 기본 필드 (객체는 없는 키만 병합)
+    #[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
+    pub defaults: serde_json::Map<String, serde_json::Value>,
+    /// 추가 요청 헤더
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+    /// 비스트리밍 응답 필드 매핑
+    #[serde(default, skip_serializing_if = "ResponseMapping::is_empty")]
+    pub response: ResponseMapping,
+    /// 스트리밍 청크 필드 매핑
+    #[serde(default, skip_serializing_if = "ResponseMapping::is_empty")]
+    pub stream: ResponseMapping,
+    /// 제공자 종료 사유 → Anthropic stop_reason (기본 매핑에 추가)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub stop_reasons: BTreeMap<String, String>,
+}
+
+fn default_transformer_base() -> String {
+    "openai".to_string()
+}
+
+impl TransformerConfig {
+    /// 기반 트랜스포머와 JSON Pointer 형식 검증
+    pub fn validate(&self) -> Result<(), String> {
+        if !matches!(self.base.as_str(), "openai" | "gemini" | "responses" | "ollama" | "bedrock") {
+            return Err(format!(
+                "트랜스포머 '{}': 알 수 없는 base '{}' (openai, gemini, responses, ollama, bedrock)",
+                self.name, self.base
+            ));
+        }
+        for pointer in self.response.pointers().chain(self.stream.pointers()) {
+            if !pointer.is_empty() && !pointer.starts_with('/') {
+                return Err(format!(
+                    "트랜스포머 '{}': JSON Pointer는 '/'로 시작해야 합니다: {}",
+                    self.name, pointer
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
 /// 최상위 설정
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Config {
     pub server: ServerConfig,
     pub default: DefaultConfig,
     pub routes: Vec<RouteConfig>,
+    /// 선언형 트랜스포머 정의
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub transformers: Vec<TransformerConfig>,
 }
 
 /// 환경변수 치환: `${VAR_NAME}` → 실제 값
@@ -198,7 +1225,27 @@ impl Config {
     pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
         let raw = fs::read_to_string(path)?;
         let resolved = resolve_env(&raw);
-        let config: Config = serde_yaml::from_str(&resolved)?;
+        let mut config: Config = serde_yaml::from_str(&resolved)?;
+        for route in &mut config.routes {
+            route.matcher = Some(ModelMatch::parse(&route.match_pattern)?);
+            route
+                .validate_upstreams()
+                .and_then(|_| route.when.as_ref().map_or(Ok(()), RouteConditions::validate))
+                .and_then(|_| route.circuit_breaker.as_ref().map_or(Ok(()), CircuitBreakerConfig::validate))
+                .and_then(|_| route.retry.as_ref().map_or(Ok(()), RetryConfig::validate))
+                .and_then(|_| route.timeouts.as_ref().map_or(Ok(()), TimeoutConfig::validate))
+                .and_then(|_| route.fallback.validate())
+                .map_err(|e| format!("라우트 '{}': {e}", route.match_pattern))?;
+        }
+        if let Some(retry) = &config.default.retry {
+            retry.validate().map_err(|e| format!("default: {e}"))?;
+        }
+        if let Some(timeouts) = &config.default.timeouts {
+            timeouts.validate().map_err(|e| format!("default: {e}"))?;
+        }
+        for t in &config.transformers {
+            t.validate()?;
+        }
         Ok(config)
     }
 
@@ -208,27 +1255,77 @@ impl Config {
             server: ServerConfig {
                 host: "127.0.0.1".into(),
                 port: 18081,
+                admin_token: None,
             },
             default: DefaultConfig {
                 url: "https://api.anthropic.com".into(),
+                retry: None,
+                timeouts: None,
             },
             routes: vec![],
+            transformers: vec![],
         }
     }
 
     /// YAML 파일로 설정 저장
+    ///
+    /// 실행 중인 프록시가 쓰다 만 파일을 읽지 않도록 임시 파일에 쓴 뒤 교체
     pub fn save(&self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
         let yaml = serde_yaml::to_string(self)?;
-        fs::write(path, yaml)?;
+        let tmp = format!("{path}.tmp");
+        fs::write(&tmp, yaml)?;
+        fs::rename(&tmp, path)?;
         Ok(())
     }
 
+    /// 리로드 시 새 라우트마다 내용이 같은 기존 라우트의 인덱스 (없으면 None)
+    ///
+    /// 순서가 바뀌어도 같은 라우트로 보며, 같은 내용의 라우트가 여럿이면 앞에서부터 짝짓는다.
+    pub fn unchanged_routes(&self, previous: &Config) -> Vec<Option<usize>> {
+        let old: Vec<Option<serde_json::Value>> =
+            previous.routes.iter().map(|r| serde_json::to_value(r).ok()).collect();
+        let mut taken = vec![false; old.len()];
+        self.routes
+            .iter()
+            .map(|route| {
+                let value = serde_json::to_value(route).ok()?;
+                let idx = (0..old.len()).find(|&i| !taken[i] && old[i].as_ref() == Some(&value))?;
+                taken[idx] = true;
+                Some(idx)
+            })
+            .collect()
+    }
+
+    /// 라우트(None이면 패스스루)에 적용할 타임아웃 (라우트 → default 순으로 병합)
+    pub fn timeouts_for(&self, route: Option<&RouteConfig>) -> TimeoutConfig {
+        let base = self.default.timeouts.clone().unwrap_or_default();
+        match route.and_then(|r| r.timeouts.as_ref()) {
+            Some(t) => t.or(&base),
+            None => base,
+        }
+    }
+
+    /// 설정에 쓰인 모든 연결 타임아웃 (라우트별 HTTP 클라이언트 생성용)
+    pub fn connect_timeouts(&self) -> Vec<Duration> {
+        let mut all: Vec<Duration> = std::iter::once(self.timeouts_for(None).connect())
+            .chain(self.routes.iter().map(|r| self.timeouts_for(Some(r)).connect()))
+            .collect();
+        all.sort();
+        all.dedup();
+        all
+    }
+
     /// 모델명으로 라우트 검색 (첫 번째 매칭의 인덱스 + 참조 반환)
     pub fn find_route(&self, model: &str) -> Option<(usize, &RouteConfig)> {
+        self.find_route_for(&RequestAttributes::model_only(model))
+    }
+
+    /// 요청 속성으로 라우트 검색 (when 조건 포함)
+    pub fn find_route_for(&self, req: &RequestAttributes) -> Option<(usize, &RouteConfig)> {
         self.routes
             .iter()
             .enumerate()
-            .find(|(_, r)| model.contains(&r.match_pattern))
+            .find(|(_, r)| r.matches(req))
     }
 
     /// XDG Base Directory 준수 설정 파일 검색
@@ -384,6 +1481,186 @@ routes:
         let _ = fs::remove_file(path);
     }
 
+    /// YAML 파싱: auth 생략 (로컬 서버) → 인증 헤더 없음
+    #[test]
+    fn test_config_load_route_without_auth() {
+        let yaml = r#"
+server:
+  host: "127.0.0.1"
+  port: 18081
+default:
+  url: "https://api.anthropic.com"
+routes:
+  - match: "local"
+    upstream:
+      url: "http://localhost:11434"
+    transformer: "ollama"
+"#;
+        let path = "/tmp/_config_test_no_auth.yaml";
+        fs::write(path, yaml).expect("임시 파일 작성 실패");
+
+        let config = Config::load(path).expect("설정 로드 실패");
+        let auth = &config.routes[0].upstream.auth;
+        assert_eq!(auth.auth_type, "api_key");
+        assert!(!auth.has_credentials());
+
+        let _ = fs::remove_file(path);
+    }
+
+    /// aws_sigv4 인증은 필수 필드 누락 시 로드 실패
+    #[test]
+    fn test_config_load_sigv4_requires_region() {
+        let yaml = r#"
+server:
+  host: "127.0.0.1"
+  port: 18081
+default:
+  url: "https://api.anthropic.com"
+routes:
+  - match: "bedrock"
+    upstream:
+      url: "https://bedrock-runtime.us-east-1.amazonaws.com"
+      auth:
+        type: "aws_sigv4"
+        access_key_id: "AKID"
+        secret_access_key: "secret"
+    transformer: "bedrock"
+"#;
+        let path = "/tmp/_config_test_sigv4.yaml";
+        fs::write(path, yaml).expect("임시 파일 작성 실패");
+
+        let err = Config::load(path).unwrap_err().to_string();
+        assert!(err.contains("region"), "{err}");
+
+        fs::write(path, yaml.replace("secret\"\n", "secret\"\n        region: \"us-east-1\"\n")).unwrap();
+        let config = Config::load(path).expect("설정 로드 실패");
+        assert!(config.routes[0].upstream.auth.is_sigv4());
+
+        let _ = fs::remove_file(path);
+    }
+
+    /// 경로 템플릿 + 쿼리 파라미터 (Azure OpenAI 배포)
+    #[test]
+    fn test_upstream_request_path() {
+        let upstream: UpstreamConfig = serde_yaml::from_str(
+            r#"
+url: "https://my-resource.openai.azure.com"
+auth:
+  header: "api-key"
+  value: "key"
+path: "/openai/deployments/{model}/chat/completions"
+query:
+  api-version: "2024-10-21"
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            upstream.request_path("/v1/chat/completions", "gpt-4o-prod"),
+            "/openai/deployments/gpt-4o-prod/chat/completions?api-version=2024-10-21"
+        );
+
+        // 템플릿 없이 쿼리만: 기존 쿼리 뒤에 추가
+        let upstream = UpstreamConfig { path: None, ..upstream };
+        assert_eq!(
+            upstream.request_path("/v1beta/models/g:streamGenerateContent?alt=sse", "g"),
+            "/v1beta/models/g:streamGenerateContent?alt=sse&api-version=2024-10-21"
+        );
+    }
+
+    /// vertex 설정: publisher 기본값과 project/location 검증
+    #[test]
+    fn test_config_load_vertex() {
+        let yaml = r#"
+server:
+  host: "127.0.0.1"
+  port: 18081
+default:
+  url: "https://api.anthropic.com"
+routes:
+  - match: "gemini"
+    upstream:
+      url: "https://us-central1-aiplatform.googleapis.com"
+      vertex:
+        project: "my-proj"
+        location: "us-central1"
+    transformer: "gemini"
+"#;
+        let path = "/tmp/_config_test_vertex.yaml";
+        fs::write(path, yaml).expect("임시 파일 작성 실패");
+
+        let config = Config::load(path).expect("설정 로드 실패");
+        let vertex = config.routes[0].upstream.vertex.as_ref().unwrap();
+        assert_eq!(vertex.publisher, "google");
+        assert_eq!(
+            vertex.model_path("gemini-2.5-pro", "generateContent"),
+            "/v1/projects/my-proj/locations/us-central1/publishers/google/models/gemini-2.5-pro:generateContent"
+        );
+
+        fs::write(path, yaml.replace("my-proj", "")).unwrap();
+        let err = Config::load(path).unwrap_err().to_string();
+        assert!(err.contains("project"), "{err}");
+
+        let _ = fs::remove_file(path);
+    }
+
+    /// oauth 인증은 token_url과 grant에 필요한 자격 증명 검증
+    #[test]
+    fn test_auth_validate_oauth() {
+        let mut auth = AuthConfig {
+            auth_type: "oauth".into(),
+            client_id: Some("id".into()),
+            ..AuthConfig::default()
+        };
+        assert!(auth.validate().unwrap_err().contains("token_url"));
+
+        auth.token_url = Some("https://auth.example.com/token".into());
+        assert!(auth.validate().unwrap_err().contains("client_secret"));
+
+        auth.client_secret = Some("secret".into());
+        assert!(auth.validate().is_ok());
+        assert!(auth.has_credentials());
+
+        // refresh_token grant는 client_secret 없이도 허용 (공개 클라이언트)
+        auth.client_secret = None;
+        auth.refresh_token = Some("rt".into());
+        assert!(auth.validate().is_ok());
+    }
+
+    /// YAML 파싱: transformers 섹션 + base 검증
+    #[test]
+    fn test_config_load_transformers() {
+        let yaml = r#"
+server:
+  host: "127.0.0.1"
+  port: 18081
+default:
+  url: "https://api.anthropic.com"
+routes: []
+transformers:
+  - name: "acme"
+    path: "/v2/chat"
+    rename:
+      max_tokens: "max_completion_tokens"
+    response:
+      text: "/output/text"
+"#;
+        let path = "/tmp/_config_test_transformers.yaml";
+        fs::write(path, yaml).expect("임시 파일 작성 실패");
+
+        let config = Config::load(path).expect("설정 로드 실패");
+        let acme = &config.transformers[0];
+        assert_eq!(acme.base, "openai");
+        assert_eq!(acme.rename["max_tokens"], "max_completion_tokens");
+        assert_eq!(acme.response.text.as_deref(), Some("/output/text"));
+        assert!(acme.stream.is_empty());
+
+        // 알 수 없는 base → 로드 실패
+        fs::write(path, yaml.replace("name: \"acme\"", "name: \"acme\"\n    base: \"cohere\"")).unwrap();
+        assert!(Config::load(path).is_err());
+
+        let _ = fs::remove_file(path);
+    }
+
     /// find_route: 매칭되는 경우
     #[test]
     fn test_find_route_matches() {
@@ -443,6 +1720,439 @@ routes:
         }
     }
 
+    /// 매칭 모드: exact/prefix/glob/regex와 하위 호환 부분 문자열
+    #[test]
+    fn test_model_match_modes() {
+        let m = |p: &str| ModelMatch::parse(p).unwrap();
+        assert!(m("glm").is_match("glm-4-plus"));
+        assert!(m("exact:glm-5").is_match("glm-5"));
+        assert!(!m("exact:glm-5").is_match("glm-5-air"));
+        assert!(m("prefix:gpt-").is_match("gpt-4o"));
+        assert!(!m("prefix:gpt-").is_match("chatgpt-4o"));
+        assert!(m("glob:claude-*-haiku").is_match("claude-3-5-haiku"));
+        assert!(!m("glob:claude-*-haiku").is_match("claude-3-5-haiku-latest"));
+        assert!(m("glob:kimi-k?").is_match("kimi-k2"));
+        assert!(m("regex:^openrouter/(.+)$").is_match("openrouter/qwen/qwen3"));
+
+        assert!(ModelMatch::parse("regex:(unclosed").is_err());
+    }
+
+    /// regex/glob 캡처 그룹을 model_map에서 참조
+    #[test]
+    fn test_resolve_model_map_captures() {
+        let mut config = make_test_config();
+        config.routes[0].match_pattern = "regex:^openrouter/(.+)$".into();
+        config.routes[0].model_map = Some("$1".into());
+        let (_, route) = config.find_route("openrouter/qwen/qwen3-coder").unwrap();
+        assert_eq!(route.resolve_for("openrouter/qwen/qwen3-coder").model_map.as_deref(), Some("qwen/qwen3-coder"));
+
+        config.routes[0].match_pattern = "glob:local-*".into();
+        config.routes[0].model_map = Some("${1}:latest".into());
+        let (_, route) = config.find_route("local-llama3").unwrap();
+        assert_eq!(route.resolve_for("local-llama3").model_map.as_deref(), Some("llama3:latest"));
+
+        // 캡처 참조가 없으면 그대로
+        config.routes[0].model_map = Some("llama3".into());
+        assert!(matches!(config.routes[0].resolve_for("local-x"), Cow::Borrowed(_)));
+    }
+
+    /// when 조건: 모든 조건 충족 시에만 매칭, 불충족 시 다음 라우트
+    #[test]
+    fn test_find_route_with_conditions() {
+        let mut config = make_test_config();
+        let mut long_context = config.routes[0].clone();
+        long_context.upstream.url = "https://long.example.com".into();
+        long_context.when = Some(RouteConditions {
+            min_input_tokens: Some(100_000),
+            headers: BTreeMap::from([("X-Project".to_string(), "acme".to_string())]),
+            system_contains: Some("/work/acme".into()),
+            ..Default::default()
+        });
+        config.routes.insert(0, long_context);
+
+        let mut req = RequestAttributes {
+            model: "zai-model".into(),
+            input_tokens: 150_000,
+            system: "cwd: /work/acme/api".into(),
+            headers: vec![("x-project".into(), "acme".into())],
+            ..Default::default()
+        };
+        assert_eq!(config.find_route_for(&req).unwrap().0, 0);
+
+        req.input_tokens = 5_000;
+        assert_eq!(config.find_route_for(&req).unwrap().0, 1);
+
+        req.input_tokens = 150_000;
+        req.headers.clear();
+        assert_eq!(config.find_route_for(&req).unwrap().0, 1);
+
+        // 모델명만으로 검색하면 조건 있는 라우트는 건너뜀
+        assert_eq!(config.find_route("zai-model").unwrap().0, 1);
+    }
+
+    #[test]
+    fn test_route_conditions_flags() {
+        let when = RouteConditions {
+            tools: Some(true),
+            images: Some(false),
+            thinking: Some(true),
+            max_input_tokens: Some(32_000),
+            ..Default::default()
+        };
+        let mut req = RequestAttributes {
+            has_tools: true,
+            thinking: true,
+            input_tokens: 1_000,
+            ..Default::default()
+        };
+        assert!(when.matches(&req));
+        req.has_images = true;
+        assert!(!when.matches(&req));
+        req.has_images = false;
+        req.input_tokens = 32_000;
+        assert!(!when.matches(&req));
+
+        let invalid = RouteConditions { min_input_tokens: Some(10), max_input_tokens: Some(10), ..Default::default() };
+        assert!(invalid.validate().is_err());
+    }
+
+    /// fallback 목록 → 체인 (대상별 트리거, 기본 트리거, upstream 생략 = 기본 업스트림)
+    #[test]
+    fn test_config_load_fallback_chain() {
+        let yaml = r#"
+server:
+  host: "127.0.0.1"
+  port: 18081
+default:
+  url: "https://api.anthropic.com"
+routes:
+  - match: "glm"
+    upstream:
+      url: "https://api.z.ai/api/anthropic"
+      auth:
+        header: "x-api-key"
+        value: "zai"
+    fallback:
+      - upstream:
+          url: "https://openrouter.ai/api/v1"
+          auth:
+            header: "Authorization"
+            value: "Bearer or"
+        transformer: "openai"
+        model_map: "z-ai/glm-4.6"
+      - model_map: "claude-sonnet-4-5"
+        triggers: ["5xx", "connect_error"]
+"#;
+        let path = "/tmp/_config_test_fallback_chain.yaml";
+        fs::write(path, yaml).expect("임시 파일 작성 실패");
+        let config = Config::load(path).expect("설정 로드 실패");
+        let _ = fs::remove_file(path);
+
+        let Fallback::Chain(targets) = &config.routes[0].fallback else {
+            panic!("체인으로 파싱되어야 함");
+        };
+        assert_eq!(targets.len(), 2);
+        assert!(config.routes[0].fallback.is_enabled());
+
+        let openrouter = targets[0].to_route().unwrap();
+        assert_eq!(openrouter.upstream.url, "https://openrouter.ai/api/v1");
+        assert_eq!(openrouter.model_map.as_deref(), Some("z-ai/glm-4.6"));
+        assert!(targets[0].triggered_by(UpstreamFailure::Status(429)));
+        assert!(targets[0].triggered_by(UpstreamFailure::Timeout));
+        assert!(!targets[0].triggered_by(UpstreamFailure::Status(400)));
+
+        assert!(targets[1].to_route().is_none());
+        assert!(targets[1].triggered_by(UpstreamFailure::Status(503)));
+        assert!(!targets[1].triggered_by(UpstreamFailure::Status(429)));
+
+        // 직렬화 후 다시 읽어도 체인 유지
+        let yaml = serde_yaml::to_string(&config.routes[0].fallback).unwrap();
+        let parsed: Fallback = serde_yaml::from_str(&yaml).unwrap();
+        assert!(matches!(parsed, Fallback::Chain(t) if t.len() == 2));
+    }
+
+    #[test]
+    fn test_fallback_trigger_matches() {
+        assert!(FallbackTrigger::Timeout.matches(UpstreamFailure::Status(504)));
+        assert!(FallbackTrigger::ServerError.matches(UpstreamFailure::Status(504)));
+        assert!(!FallbackTrigger::ClientError.matches(UpstreamFailure::Status(429)));
+        assert!(FallbackTrigger::ClientError.matches(UpstreamFailure::Status(401)));
+        assert!(!FallbackTrigger::ConnectError.matches(UpstreamFailure::Timeout));
+        assert!(FallbackTrigger::ConnectError.matches(UpstreamFailure::Local));
+
+        let empty: Result<Fallback, _> = serde_yaml::from_str("[]");
+        assert!(empty.is_err());
+    }
+
+    #[test]
+    fn test_config_load_balanced_upstreams() {
+        let yaml = r#"
+server:
+  host: "127.0.0.1"
+  port: 18081
+default:
+  url: "https://api.anthropic.com"
+routes:
+  - match: "glm-5"
+    transformer: "openai"
+    balance: cheapest
+    upstreams:
+      - url: "https://api.z.ai/api/anthropic"
+        auth:
+          header: "x-api-key"
+          value: "zai"
+        transformer: ""
+        weight: 3
+        cost: 1.5
+      - url: "https://openrouter.ai/api/v1"
+        auth:
+          header: "Authorization"
+          value: "Bearer or"
+        model_map: "z-ai/glm-5"
+        cost: 2
+        concurrency: 4
+"#;
+        let path = "/tmp/_config_test_balanced.yaml";
+        fs::write(path, yaml).expect("임시 파일 작성 실패");
+        let config = Config::load(path).expect("설정 로드 실패");
+        let _ = fs::remove_file(path);
+
+        let route = &config.routes[0];
+        assert!(route.upstream.is_unset());
+        assert_eq!(route.balance, BalanceStrategy::Cheapest);
+        assert_eq!(route.upstreams.len(), 2);
+        assert_eq!(route.upstreams[0].weight, 3);
+        assert_eq!(route.upstreams[1].weight, 1);
+        assert_eq!(route.upstreams[1].cost, Some(2.0));
+
+        let zai = route.member_route(0).unwrap();
+        assert_eq!(zai.upstream.url, "https://api.z.ai/api/anthropic");
+        assert_eq!(zai.transformer.as_deref(), Some(""));
+        assert!(!zai.fallback.is_enabled());
+        assert!(!zai.stream_recovery);
+
+        // stream_recovery면 이어받기 대상으로 폴백 설정 유지
+        let mut recovering = route.clone();
+        recovering.stream_recovery = true;
+        let member = recovering.member_route(0).unwrap();
+        assert!(member.stream_recovery);
+        assert!(member.fallback.is_enabled());
+
+        // 생략한 transformer는 라우트 값 상속, concurrency는 항목 값
+        let openrouter = route.member_route(1).unwrap();
+        assert_eq!(openrouter.transformer.as_deref(), Some("openai"));
+        assert_eq!(openrouter.model_map.as_deref(), Some("z-ai/glm-5"));
+        assert_eq!(openrouter.concurrency, Some(4));
+        assert!(route.member_route(2).is_none());
+    }
+
+    #[test]
+    fn test_config_load_upstreams_validation() {
+        let cases = [
+            ("  - match: \"glm\"\n", "upstream 또는 upstreams"),
+            (
+                "  - match: \"glm\"\n    upstream:\n      url: \"https://a\"\n    upstreams:\n      - url: \"https://b\"\n",
+                "함께 쓸 수 없습니다",
+            ),
+            (
+                "  - match: \"glm\"\n    upstreams:\n      - url: \"https://b\"\n        auth:\n          value: \"k1\"\n          pool: [\"k2\"]\n",
+                "upstreams[0]",
+            ),
+        ];
+        for (i, (routes, expected)) in cases.iter().enumerate() {
+            let yaml = format!(
+                "server:\n  host: \"127.0.0.1\"\n  port: 18081\ndefault:\n  url: \"https://api.anthropic.com\"\nroutes:\n{routes}"
+            );
+            let path = format!("/tmp/_config_test_upstreams_invalid_{i}.yaml");
+            fs::write(&path, yaml).expect("임시 파일 작성 실패");
+            let err = Config::load(&path).unwrap_err().to_string();
+            let _ = fs::remove_file(&path);
+            assert!(err.contains(expected), "{err}");
+        }
+    }
+
+    #[test]
+    fn test_config_load_circuit_breaker() {
+        let yaml = r#"
+server:
+  host: "127.0.0.1"
+  port: 18081
+default:
+  url: "https://api.anthropic.com"
+routes:
+  - match: "glm"
+    upstream:
+      url: "https://api.z.ai/api/anthropic"
+    circuit_breaker:
+      failure_threshold: 3
+      open_secs: 10
+  - match: "kimi"
+    upstream:
+      url: "https://api.kimi.com"
+"#;
+        let path = "/tmp/_config_test_circuit_breaker.yaml";
+        fs::write(path, yaml).expect("임시 파일 작성 실패");
+        let config = Config::load(path).expect("설정 로드 실패");
+        let _ = fs::remove_file(path);
+
+        let cb = config.routes[0].circuit_breaker.as_ref().unwrap();
+        assert!(cb.enabled);
+        assert_eq!(cb.failure_threshold, 3);
+        assert_eq!(cb.open_secs, 10);
+        assert_eq!(cb.window, 20);
+        assert!(config.routes[1].circuit_breaker.is_none());
+
+        let invalid = CircuitBreakerConfig { min_requests: 30, ..Default::default() };
+        assert!(invalid.validate().unwrap_err().contains("min_requests"));
+        let invalid = CircuitBreakerConfig { error_rate: 1.5, ..Default::default() };
+        assert!(invalid.validate().is_err());
+    }
+
+    #[test]
+    fn test_config_load_retry() {
+        let yaml = r#"
+server:
+  host: "127.0.0.1"
+  port: 18081
+default:
+  url: "https://api.anthropic.com"
+  retry:
+    max_attempts: 2
+routes:
+  - match: "glm"
+    upstream:
+      url: "https://api.z.ai/api/anthropic"
+    retry:
+      statuses: [503, 529]
+      timeout: false
+"#;
+        let path = "/tmp/_config_test_retry.yaml";
+        fs::write(path, yaml).expect("임시 파일 작성 실패");
+        let config = Config::load(path).expect("설정 로드 실패");
+        let _ = fs::remove_file(path);
+
+        let default_retry = config.default.retry.as_ref().unwrap();
+        assert_eq!(default_retry.max_attempts, 2);
+        assert!(default_retry.retries(UpstreamFailure::Status(502)));
+
+        let retry = config.routes[0].retry.as_ref().unwrap();
+        assert_eq!(retry.max_attempts, 3);
+        assert!(retry.retries(UpstreamFailure::Status(529)));
+        assert!(!retry.retries(UpstreamFailure::Status(500)));
+        assert!(!retry.retries(UpstreamFailure::Timeout));
+        assert!(retry.retries(UpstreamFailure::Connect));
+        assert!(!retry.retries(UpstreamFailure::Local));
+
+        let invalid = RetryConfig { max_attempts: 0, ..Default::default() };
+        assert!(invalid.validate().is_err());
+    }
+
+    #[test]
+    fn test_config_load_timeouts() {
+        let yaml = r#"
+server:
+  host: "127.0.0.1"
+  port: 18081
+default:
+  url: "https://api.anthropic.com"
+  timeouts:
+    connect_secs: 5
+    idle_secs: 120
+routes:
+  - match: "glm"
+    upstream:
+      url: "https://api.z.ai/api/anthropic"
+    timeouts:
+      first_byte_secs: 60
+      idle_secs: 30
+  - match: "kimi"
+    upstream:
+      url: "https://api.kimi.com"
+    timeouts:
+      connect_secs: 3
+"#;
+        let path = "/tmp/_config_test_timeouts.yaml";
+        fs::write(path, yaml).expect("임시 파일 작성 실패");
+        let config = Config::load(path).expect("설정 로드 실패");
+        let _ = fs::remove_file(path);
+
+        // 라우트 → default → 기본값 순으로 병합
+        let glm = config.timeouts_for(Some(&config.routes[0]));
+        assert_eq!(glm.connect(), Duration::from_secs(5));
+        assert_eq!(glm.first_byte(), Duration::from_secs(60));
+        assert_eq!(glm.idle(), Duration::from_secs(30));
+        assert_eq!(glm.queue(), Duration::from_secs(120));
+
+        let passthrough = config.timeouts_for(None);
+        assert_eq!(passthrough.first_byte(), Duration::from_secs(600));
+        assert_eq!(passthrough.idle(), Duration::from_secs(120));
+
+        assert_eq!(config.connect_timeouts(), vec![Duration::from_secs(3), Duration::from_secs(5)]);
+
+        let invalid = TimeoutConfig { idle_secs: Some(0), ..Default::default() };
+        assert!(invalid.validate().unwrap_err().contains("idle_secs"));
+    }
+
+    #[test]
+    fn test_unchanged_routes_and_atomic_save() {
+        let route = |pattern: &str| -> RouteConfig {
+            serde_yaml::from_str(&format!("match: \"{pattern}\"\nupstream:\n  url: \"https://api.z.ai\"\n")).unwrap()
+        };
+        let mut old = Config::default_config();
+        old.routes = vec![route("glm"), route("glm"), route("kimi")];
+        let mut new = old.clone();
+        new.routes = vec![route("kimi"), route("glm"), route("glm"), route("glm")];
+        // 같은 내용의 라우트는 앞에서부터 한 번씩만 짝지음
+        assert_eq!(new.unchanged_routes(&old), vec![Some(2), Some(0), Some(1), None]);
+
+        let path = "/tmp/_config_test_atomic_save.yaml";
+        new.save(path).expect("설정 저장 실패");
+        assert!(!std::path::Path::new(&format!("{path}.tmp")).exists());
+        let loaded = Config::load(path).expect("설정 로드 실패");
+        let _ = fs::remove_file(path);
+        assert_eq!(loaded.unchanged_routes(&new), vec![Some(0), Some(1), Some(2), Some(3)]);
+    }
+
+    #[test]
+    fn test_retry_delay() {
+        let policy = RetryConfig { jitter: false, ..Default::default() };
+        assert_eq!(policy.delay(1, None, 0), Some(Duration::from_millis(500)));
+        assert_eq!(policy.delay(3, None, 0), Some(Duration::from_millis(2000)));
+        // 상한 적용
+        assert_eq!(policy.delay(10, None, 0), Some(Duration::from_millis(8000)));
+        // Retry-After는 백오프보다 길면 우선, 상한(30초)을 넘으면 재시도 안 함
+        assert_eq!(policy.delay(1, Some(5), 0), Some(Duration::from_secs(5)));
+        assert_eq!(policy.delay(1, Some(31), 0), None);
+
+        // 지터: 대기의 절반 ~ 전체
+        let policy = RetryConfig::default();
+        for random in [0, 1, 249, 250, 251, u64::MAX] {
+            let delay = policy.delay(1, None, random).unwrap();
+            assert!(delay >= Duration::from_millis(250) && delay <= Duration::from_millis(500), "{delay:?}");
+        }
+    }
+
+    /// 잘못된 패턴은 로드 시 실패
+    #[test]
+    fn test_config_load_invalid_match_pattern() {
+        let yaml = r#"
+server:
+  host: "127.0.0.1"
+  port: 18081
+default:
+  url: "https://api.anthropic.com"
+routes:
+  - match: "regex:[a-"
+    upstream:
+      url: "http://localhost:11434"
+"#;
+        let path = "/tmp/_config_test_invalid_match.yaml";
+        fs::write(path, yaml).expect("임시 파일 작성 실패");
+        let err = Config::load(path).unwrap_err().to_string();
+        assert!(err.contains("regex:[a-"), "{err}");
+        let _ = fs::remove_file(path);
+    }
+
     /// find_route: 순서 우선순위 (첫 번째 매칭 반환)
     #[test]
     fn test_find_route_first_match_wins() {
@@ -450,13 +2160,19 @@ routes:
             server: ServerConfig {
                 host: "127.0.0.1".into(),
                 port: 18081,
+                admin_token: None,
             },
             default: DefaultConfig {
                 url: "https://api.anthropic.com".into(),
+                retry: None,
+                timeouts: None,
             },
+            transformers: vec![],
             routes: vec![
                 RouteConfig {
                     match_pattern: "kimi".into(),
+                    matcher: None,
+                    when: None,
                     upstream: UpstreamConfig {
                         url: "https://first.example.com".into(),
                         auth: AuthConfig {
@@ -467,17 +2183,36 @@ routes:
                             client_secret: None,
                             refresh_token: None,
                             token_url: None,
+                            access_key_id: None,
+                            secret_access_key: None,
+                            session_token: None,
+                            region: None,
+                            service: None,
+                            credentials_file: None,
+                            scope: None,
                             pool: None,
                         },
+                        vertex: None,
+                        path: None,
+                        query: BTreeMap::new(),
                     },
                     transformer: None,
                     model_map: None,
                     fallback: Fallback::Passthrough,
                     concurrency: None,
                     account_concurrency: None,
+                    upstreams: vec![],
+                    balance: Default::default(),
+                    circuit_breaker: None,
+                    retry: None,
+                    timeouts: None,
+                    stream_recovery: false,
+                    reasoning_effort: Default::default(),
                 },
                 RouteConfig {
                     match_pattern: "kimi".into(),
+                    matcher: None,
+                    when: None,
                     upstream: UpstreamConfig {
                         url: "https://second.example.com".into(),
                         auth: AuthConfig {
@@ -488,14 +2223,31 @@ routes:
                             client_secret: None,
                             refresh_token: None,
                             token_url: None,
+                            access_key_id: None,
+                            secret_access_key: None,
+                            session_token: None,
+                            region: None,
+                            service: None,
+                            credentials_file: None,
+                            scope: None,
                             pool: None,
                         },
+                        vertex: None,
+                        path: None,
+                        query: BTreeMap::new(),
                     },
                     transformer: None,
                     model_map: None,
                     fallback: Fallback::Passthrough,
                     concurrency: None,
                     account_concurrency: None,
+                    upstreams: vec![],
+                    balance: Default::default(),
+                    circuit_breaker: None,
+                    retry: None,
+                    timeouts: None,
+                    stream_recovery: false,
+                    reasoning_effort: Default::default(),
                 },
             ],
         };
@@ -510,12 +2262,18 @@ routes:
             server: ServerConfig {
                 host: "127.0.0.1".into(),
                 port: 18081,
+                admin_token: None,
             },
             default: DefaultConfig {
                 url: "https://api.anthropic.com".into(),
+                retry: None,
+                timeouts: None,
             },
+            transformers: vec![],
             routes: vec![RouteConfig {
                 match_pattern: "zai".into(),
+                matcher: None,
+                when: None,
                 upstream: UpstreamConfig {
                     url: "https://api.z.ai".into(),
                     auth: AuthConfig {
@@ -526,14 +2284,31 @@ routes:
                         client_secret: None,
                         refresh_token: None,
                         token_url: None,
+                        access_key_id: None,
+                        secret_access_key: None,
+                        session_token: None,
+                        region: None,
+                        service: None,
+                        credentials_file: None,
+                        scope: None,
                         pool: None,
                     },
+                    vertex: None,
+                    path: None,
+                    query: BTreeMap::new(),
                 },
                 transformer: None,
                 model_map: None,
                 fallback: Fallback::Passthrough,
                 concurrency: None,
                 account_concurrency: None,
+                upstreams: vec![],
+                balance: Default::default(),
+                circuit_breaker: None,
+                retry: None,
+                timeouts: None,
+                stream_recovery: false,
+                reasoning_effort: Default::default(),
             }],
         }
     }
@@ -61,6 +61,11 @@ fn log_file_path() -> PathBuf {
     claude_dir().join("model-router.log")
 }
 
+/// 실행 중인 서버가 열린 서킷 목록을 기록하는 파일
+pub fn circuit_state_path() -> PathBuf {
+    claude_dir().join("model-router.circuits.json")
+}
+
 // ── settings.json 읽기/쓰기 ──
 
 fn read_settings() -> Value {
@@ -104,6 +109,21 @@ fn is_process_running(pid: u32) -> bool {
         .unwrap_or(false)
 }
 
+/// 실행 중인 프록시에 설정 리로드 요청 (SIGHUP, 파일 변경 감시로도 몇 초 안에 반영됨)
+fn notify_reload() {
+    let Some(pid) = read_pid().filter(|&pid| is_process_running(pid)) else {
+        return;
+    };
+    let sent = Command::new("kill")
+        .args(["-HUP", &pid.to_string()])
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false);
+    if sent {
+        println!("실행 중인 프록시에 반영했습니다 (재시작 불필요, 진행 중 요청 유지)");
+    }
+}
+
 /// 현재 실행 파일의 절대 경로
 fn current_exe_path() -> PathBuf {
     std::env::current_exe().expect("실행 파일 경로를 찾을 수 없습니다")
@@ -218,6 +238,8 @@ fn start(config_path: &str) {
         .try_clone()
         .expect("로그 파일 복제 실패");
 
+    // 백그라운드 데몬으로 분리 실행하므로 wait()하지 않음
+    #[allow(clippy::zombie_processes)]
     let child = Command::new(exe)
         .args(["--config", config_abs.to_str().unwrap()])
         .stdout(log_file)
@@ -463,6 +485,8 @@ fn add_route(config_path: &str) {
 
     let route = RouteConfig {
         match_pattern: match_pattern.clone(),
+        matcher: None,
+        when: None,
         upstream: UpstreamConfig {
             url: upstream_url.clone(),
             auth: AuthConfig {
@@ -473,14 +497,31 @@ fn add_route(config_path: &str) {
                 client_secret: None,
                 refresh_token: None,
                 token_url: None,
+                access_key_id: None,
+                secret_access_key: None,
+                session_token: None,
+                region: None,
+                service: None,
+                credentials_file: None,
+                scope: None,
                 pool: None,
             },
+            vertex: None,
+            path: None,
+            query: Default::default(),
         },
         transformer: None,
         model_map: None,
         fallback,
         concurrency: None,
         account_concurrency: None,
+        upstreams: vec![],
+        balance: Default::default(),
+        circuit_breaker: None,
+        retry: None,
+        timeouts: None,
+        stream_recovery: false,
+        reasoning_effort: Default::default(),
     };
 
     config.routes.push(route);
@@ -489,6 +530,7 @@ fn add_route(config_path: &str) {
     println!("프로바이더 추가 완료");
     println!("  매칭 패턴: {}", match_pattern);
     println!("  업스트림: {}", upstream_url);
+    notify_reload();
 }
 
 fn remove_route(config_path: &str) {
@@ -513,7 +555,7 @@ fn remove_route(config_path: &str) {
                 "{}. match=\"{}\" → {}",
                 i + 1,
                 r.match_pattern,
-                r.upstream.url
+                r.upstream_summary()
             )
         })
         .collect();
@@ -528,6 +570,7 @@ fn remove_route(config_path: &str) {
     config.save(config_path).expect("설정 파일 저장 실패");
 
     println!("라우트 제거 완료: match=\"{}\"", removed.match_pattern);
+    notify_reload();
 }
 
 // ── status ──
@@ -591,11 +634,48 @@ fn status(config_path: &str) {
         _ => {}
     }
 
-    // 5. 라우트 목록
+    // 5. 서킷 브레이커 (실행 중일 때만, 중지 후 남은 파일은 무시)
+    if read_pid().is_some_and(is_process_running) {
+        let circuits = crate::breaker::read_state_file(&circuit_state_path());
+        if circuits.is_empty() {
+            println!("서킷: 모두 닫힘");
+        } else {
+            println!("서킷 ({}개 차단 중):", circuits.len());
+            for c in &circuits {
+                let now = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map(|d| d.as_secs())
+                    .unwrap_or(0);
+                let until = c
+                    .open_until
+                    .map(|t| format!(", {}초 후 시험 요청", t.saturating_sub(now)))
+                    .unwrap_or_default();
+                println!(
+                    "  match=\"{}\" {} → {} (연속 실패 {}, 오류율 {:.0}%{})",
+                    c.route,
+                    c.scope,
+                    c.state,
+                    c.consecutive_failures,
+                    c.error_rate * 100.0,
+                    until
+                );
+            }
+        }
+    }
+
+    // 6. 라우트 목록
     println!();
     if std::path::Path::new(config_path).exists() {
         match Config::load(config_path) {
             Ok(config) => {
+                if read_pid().is_some_and(is_process_running) {
+                    println!(
+                        "관리 API: http://{}:{}{}/routes",
+                        config.server.host,
+                        config.server.port,
+                        crate::admin::ADMIN_PREFIX
+                    );
+                }
                 if config.routes.is_empty() {
                     println!("라우트: 없음");
                 } else {
@@ -610,7 +690,7 @@ fn status(config_path: &str) {
                             "  {}. match=\"{}\" → {}{}",
                             i + 1,
                             r.match_pattern,
-                            r.upstream.url,
+                            r.upstream_summary(),
                             transformer_str
                         );
                     }
@@ -622,13 +702,14 @@ fn status(config_path: &str) {
         println!("설정 파일 없음: {}", config_path);
     }
 
-    // 5. 경로 정보
+    // 7. 경로 정보
     let config_abs = resolve_config_path(config_path);
     println!("\n경로:");
     println!("  settings.json: {}", settings_json_path().display());
     println!("  config.yaml:   {}", config_abs.display());
     println!("  PID 파일:      {}", pid_file_path().display());
     println!("  로그 파일:     {}", log_file_path().display());
+    println!("  서킷 상태:     {}", circuit_state_path().display());
 }
 
 // ── service install / uninstall ──
@@ -1,17 +1,29 @@
+mod admin;
+mod auth;
+mod breaker;
 mod config;
 mod configure;
 mod pool;
 mod proxy;
+mod reload;
 mod transformer;
 mod update;
+mod util;
 
 use clap::{Parser, Subcommand};
+use std::collections::HashMap;
+use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 
+use admin::AdminState;
+use auth::oauth::TokenManager;
+use breaker::CircuitBreaker;
 use config::Config;
-use pool::{KeyPool, AccountSemaphore};
+use pool::{KeyPool, AccountSemaphore, UpstreamPool};
 use hyper_rustls::HttpsConnectorBuilder;
 use hyper_util::client::legacy::Client;
+use hyper_util::client::legacy::connect::HttpConnector;
 use hyper_util::rt::TokioExecutor;
 use http_body_util::Full;
 use bytes::Bytes;
@@ -19,7 +31,7 @@ use axum::Router;
 use tower_http::trace::TraceLayer;
 
 /// 프록시 HTTP 클라이언트 타입
-pub type HttpClient = Client<hyper_rustls::HttpsConnector<hyper_util::client::legacy::connect::HttpConnector>, Full<Bytes>>;
+pub type HttpClient = Client<hyper_rustls::HttpsConnector<HttpConnector>, Full<Bytes>>;
 
 /// 애플리케이션 상태 (axum에서 공유)
 #[derive(Clone)]
@@ -28,6 +40,66 @@ pub struct AppState {
     pub client: HttpClient,
     pub key_pool: Arc<KeyPool>,
     pub account_semaphore: Arc<AccountSemaphore>,
+    pub token_manager: Arc<TokenManager>,
+    pub upstream_pool: Arc<UpstreamPool>,
+    pub breaker: Arc<CircuitBreaker>,
+    /// 연결 타임아웃별 HTTP 클라이언트
+    pub clients: Arc<HashMap<Duration, HttpClient>>,
+    /// 관리 API 상태 (리로드해도 유지)
+    pub admin: Arc<AdminState>,
+}
+
+impl AppState {
+    /// 연결 타임아웃에 맞는 HTTP 클라이언트 (없으면 기본 클라이언트)
+    pub fn client_for(&self, connect_timeout: Duration) -> &HttpClient {
+        self.clients.get(&connect_timeout).unwrap_or(&self.client)
+    }
+
+    /// 리로드된 Config로 새 AppState 생성
+    ///
+    /// 변경 없는 라우트의 키 풀/계정 세마포어/업스트림/서킷 상태를 이어받고,
+    /// 연결 타임아웃이 같은 HTTP 클라이언트와 토큰 캐시는 그대로 재사용한다.
+    pub fn reload(&self, config: Config) -> Self {
+        let previous = config.unchanged_routes(&self.config);
+        let clients: HashMap<Duration, HttpClient> = config
+            .connect_timeouts()
+            .into_iter()
+            .map(|timeout| {
+                let client = self.clients.get(&timeout).cloned().unwrap_or_else(|| build_client(timeout));
+                (timeout, client)
+            })
+            .collect();
+        let client = clients
+            .get(&config.timeouts_for(None).connect())
+            .cloned()
+            .unwrap_or_else(|| self.client.clone());
+
+        AppState {
+            client,
+            key_pool: Arc::new(self.key_pool.reload(&config, &previous)),
+            account_semaphore: Arc::new(self.account_semaphore.reload(&config, &previous)),
+            token_manager: self.token_manager.clone(),
+            upstream_pool: Arc::new(self.upstream_pool.reload(&config, &previous)),
+            breaker: Arc::new(self.breaker.reload(&config, &previous)),
+            clients: Arc::new(clients),
+            admin: self.admin.clone(),
+            config,
+        }
+    }
+}
+
+/// HTTPS 클라이언트 구축 (rustls — 순수 Rust TLS, 시스템 OpenSSL 불필요)
+fn build_client(connect_timeout: Duration) -> HttpClient {
+    let mut http = HttpConnector::new();
+    http.enforce_http(false);
+    http.set_connect_timeout(Some(connect_timeout));
+    let https = HttpsConnectorBuilder::new()
+        .with_native_roots()
+        .expect("시스템 루트 인증서 로드 실패")
+        .https_or_http()
+        .enable_all_versions()
+        .wrap_connector(http);
+    Client::builder(TokioExecutor::new()).build(https)
 }
 
 #[derive(Parser)]
@@ -115,27 +187,36 @@ async fn run_server(config_path: &str) {
     let config = Config::load(config_path).expect("설정 파일 로드 실패");
     tracing::info!(host = %config.server.host, port = config.server.port, "설정 로드 완료");
 
-    // 3. HTTPS 클라이언트 구축 (rustls — 순수 Rust TLS, 시스템 OpenSSL 불필요)
+    // 3. HTTPS 클라이언트 구축 (기본 + 라우트별 연결 타임아웃)
     rustls::crypto::ring::default_provider()
         .install_default()
         .expect("CryptoProvider 설치 실패");
 
-    let https = HttpsConnectorBuilder::new()
-        .with_native_roots()
-        .expect("시스템 루트 인증서 로드 실패")
-        .https_or_http()
-        .enable_all_versions()
-        .build();
-    let client: HttpClient = Client::builder(TokioExecutor::new()).build(https);
+    let client = build_client(config.timeouts_for(None).connect());
+    // 라우트별 연결 타임아웃마다 클라이언트 생성 (연결 타임아웃은 커넥터 단위 설정)
+    let clients: HashMap<Duration, HttpClient> = config
+        .connect_timeouts()
+        .into_iter()
+        .map(|timeout| (timeout, build_client(timeout)))
+        .collect();
 
     // 4. 키 풀 초기화 및 AppState 생성
     let key_pool = Arc::new(KeyPool::from_config(&config));
     let account_semaphore = Arc::new(AccountSemaphore::from_config(&config));
+    let token_manager = Arc::new(TokenManager::new(client.clone()));
+    let upstream_pool = Arc::new(UpstreamPool::from_config(&config));
+    let breaker = Arc::new(CircuitBreaker::from_config(&config).with_state_file(configure::circuit_state_path()));
     let addr = format!("{}:{}", config.server.host, config.server.port);
-    let state = AppState { config: config.clone(), client, key_pool, account_semaphore };
+    let admin = Arc::new(AdminState::new());
+    let state = AppState { config: config.clone(), client, key_pool, account_semaphore, token_manager, upstream_pool, breaker, clients: Arc::new(clients), admin };
+    let state = reload::SharedState::new(state, config_path);
+
+    // 설정 파일 변경 감시 + SIGHUP 시 리로드 (진행 중 요청은 기존 설정으로 완료)
+    reload::spawn(state.clone());
 
-    // 5. axum 라우터 구성
+    // 5. axum 라우터 구성 (관리 API는 예약 경로, 나머지는 프록시)
     let app = Router::new()
+        .nest(admin::ADMIN_PREFIX, admin::router(state.clone()))
         .fallback(proxy::proxy_handler)
         .layer(TraceLayer::new_for_http())
         .with_state(state);
@@ -143,5 +224,8 @@ async fn run_server(config_path: &str) {
     // 6. 서버 시작
     let listener = tokio::net::TcpListener::bind(&addr).await.expect("바인딩 실패");
     tracing::info!(addr = %addr, "프록시 서버 시작");
-    axum::serve(listener, app).await.expect("서버 실행 실패");
+    // 관리 API의 로컬 요청 판별을 위해 클라이언트 주소 전달
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
+        .await
+        .expect("서버 실행 실패");
 }
@@ -4,7 +4,8 @@ use std::sync::{Arc, Mutex};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::sync::Semaphore;
 
-use crate::config::Config;
+use crate::config::{BalanceStrategy, Config};
+use crate::util::splitmix64;
 
 /// 기본 쿨다운 시간 (Retry-After 헤더가 없을 때)
 const DEFAULT_COOLDOWN_SECS: u64 = 60;
@@ -12,7 +13,39 @@ const DEFAULT_COOLDOWN_SECS: u64 = 60;
 /// 라우트별 계정 단위 동시성 제어
 pub struct AccountSemaphore {
     /// 라우트별 세마포어 (None = 제한 없음)
-    semaphores: Vec<Option<Arc<Semaphore>>>,
+    semaphores: Vec<Option<Arc<AccountSlot>>>,
+}
+
+struct AccountSlot {
+    semaphore: Arc<Semaphore>,
+    limit: usize,
+    /// permit을 기다리는 요청 수
+    waiting: AtomicUsize,
+}
+
+/// 대기 카운터 자동 감소 가드 (대기열 타임아웃으로 취소돼도 감소)
+struct WaitingGuard<'a>(&'a AtomicUsize);
+
+impl Drop for WaitingGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// 관리 API용 계정 세마포어 상태
+pub struct QueueStats {
+    pub limit: usize,
+    pub available: usize,
+    pub waiting: usize,
+}
+
+/// 관리 API용 키/업스트림별 상태
+pub struct SlotStats {
+    pub in_flight: usize,
+    /// 남은 쿨다운 (초, 0이면 사용 가능)
+    pub cooldown_secs: u64,
+    /// 응답 지연 EWMA (업스트림만, 미측정이면 None)
+    pub latency_ms: Option<u64>,
 }
 
 /// 세마포어 자동 해제 가드
@@ -33,15 +66,29 @@ impl AccountSemaphore {
             .routes
             .iter()
             .map(|route| {
-                route
-                    .account_concurrency
-                    .map(|limit| Arc::new(Semaphore::new(limit)))
+                route.account_concurrency.map(|limit| {
+                    Arc::new(AccountSlot {
+                        semaphore: Arc::new(Semaphore::new(limit)),
+                        limit,
+                        waiting: AtomicUsize::new(0),
+                    })
+                })
             })
             .collect();
 
         AccountSemaphore { semaphores }
     }
 
+    /// 리로드된 Config로 새 AccountSemaphore 생성 (변경 없는 라우트는 기존 세마포어 공유)
+    ///
+    /// `previous`는 `Config::unchanged_routes` 결과. 공유한 세마포어는 리로드 전에 시작한
+    /// 요청의 permit도 함께 세므로 계정 동시성 제한이 리로드를 넘어 유지된다.
+    pub fn reload(&self, config: &Config, previous: &[Option<usize>]) -> Self {
+        let mut semaphores = Self::from_config(config).semaphores;
+        carry_over(&mut semaphores, &self.semaphores, previous);
+        AccountSemaphore { semaphores }
+    }
+
     /// 세마포어 획득 (비동기 대기)
     ///
     /// # 반환값
@@ -55,11 +102,14 @@ impl AccountSemaphore {
         route_idx: usize,
     ) -> Option<SemaphoreGuard> {
         match self.semaphores.get(route_idx)? {
-            Some(sem) => {
-                let permit = sem.clone().acquire_owned().await.ok()?;
+            Some(slot) => {
+                slot.waiting.fetch_add(1, Ordering::Relaxed);
+                let waiting = WaitingGuard(&slot.waiting);
+                let permit = slot.semaphore.clone().acquire_owned().await.ok()?;
+                drop(waiting);
                 tracing::info!(
                     route_idx,
-                    available_permits = sem.available_permits(),
+                    available_permits = slot.semaphore.available_permits(),
                     "계정 세마포어 획득"
                 );
                 Some(SemaphoreGuard::new(permit))
@@ -67,6 +117,16 @@ impl AccountSemaphore {
             None => None, // 제한 없음
         }
     }
+
+    /// 라우트의 계정 세마포어 상태 (제한 없으면 None)
+    pub fn stats(&self, route_idx: usize) -> Option<QueueStats> {
+        let slot = self.semaphores.get(route_idx)?.as_ref()?;
+        Some(QueueStats {
+            limit: slot.limit,
+            available: slot.semaphore.available_permits(),
+            waiting: slot.waiting.load(Ordering::Relaxed),
+        })
+    }
 }
 
 /// 라우트별 API 키 풀 — Least-Connections 방식 분배 + 세션 친화
@@ -78,11 +138,14 @@ impl AccountSemaphore {
 /// 세션 친화: 동일한 세션(인증 토큰 해시)에 대해 동일한 API 키를 재사용하여
 /// 프롬프트 캐시를 효과적으로 활용한다.
 pub struct KeyPool {
-    entries: Vec<Option<PoolEntry>>,
+    entries: Vec<Option<Arc<PoolEntry>>>,
     /// 세션별 키 고정 매핑: route_idx별 (session_hash → key_idx)
-    session_map: Vec<Option<Mutex<HashMap<u64, usize>>>>,
+    session_map: Vec<Option<Arc<SessionMap>>>,
 }
 
+/// 세션 해시 → 키 인덱스
+type SessionMap = Mutex<HashMap<u64, usize>>;
+
 struct PoolEntry {
     /// 키별 활성 연결 수
     active: Vec<AtomicUsize>,
@@ -94,6 +157,15 @@ struct PoolEntry {
     next_idx: AtomicUsize,
 }
 
+/// 리로드 시 변경 없는 라우트의 기존 항목을 새 라우트 위치로 옮김 (Arc 공유)
+fn carry_over<T: Clone>(fresh: &mut [Option<T>], old: &[Option<T>], previous: &[Option<usize>]) {
+    for (slot, prev) in fresh.iter_mut().zip(previous) {
+        if let Some(entry) = prev.and_then(|idx| old.get(idx)) {
+            *slot = entry.clone();
+        }
+    }
+}
+
 /// 현재 시각을 Unix epoch 초로 반환
 fn now_epoch_secs() -> u64 {
     SystemTime::now()
@@ -105,18 +177,18 @@ fn now_epoch_secs() -> u64 {
 impl KeyPool {
     /// Config로부터 KeyPool 생성
     pub fn from_config(config: &Config) -> Self {
-        let entries: Vec<Option<PoolEntry>> = config
+        let entries: Vec<Option<Arc<PoolEntry>>> = config
             .routes
             .iter()
             .map(|route| {
                 if route.upstream.auth.has_pool() {
                     let pool_size = route.upstream.auth.all_values().len();
-                    Some(PoolEntry {
+                    Some(Arc::new(PoolEntry {
                         active: (0..pool_size).map(|_| AtomicUsize::new(0)).collect(),
                         cooldown_until: (0..pool_size).map(|_| AtomicU64::new(0)).collect(),
                         concurrency: route.concurrency,
                         next_idx: AtomicUsize::new(0),
-                    })
+                    }))
                 } else {
                     None
                 }
@@ -125,12 +197,23 @@ impl KeyPool {
 
         let session_map = entries
             .iter()
-            .map(|e| e.as_ref().map(|_| Mutex::new(HashMap::new())))
+            .map(|e| e.as_ref().map(|_| Arc::new(Mutex::new(HashMap::new()))))
             .collect();
 
         KeyPool { entries, session_map }
     }
 
+    /// 리로드된 Config로 새 KeyPool 생성 (변경 없는 라우트는 기존 키 상태 공유)
+    ///
+    /// `previous`는 `Config::unchanged_routes` 결과. 공유한 라우트는 키별 진행 중 요청 수,
+    /// 쿨다운, 세션 매핑이 유지되고 리로드 전 요청의 PoolGuard 해제도 그대로 반영된다.
+    pub fn reload(&self, config: &Config, previous: &[Option<usize>]) -> Self {
+        let KeyPool { mut entries, mut session_map } = Self::from_config(config);
+        carry_over(&mut entries, &self.entries, previous);
+        carry_over(&mut session_map, &self.session_map, previous);
+        KeyPool { entries, session_map }
+    }
+
     /// Least-Connections 방식으로 키 획득
     ///
     /// concurrency 제한 내에서 활성 연결이 가장 적은 키의 인덱스를 반환.
@@ -254,6 +337,24 @@ impl KeyPool {
         }
     }
 
+    /// 라우트의 키별 상태 (키 풀이 없으면 None)
+    pub fn key_stats(&self, route_idx: usize) -> Option<Vec<SlotStats>> {
+        let entry = self.entries.get(route_idx)?.as_ref()?;
+        let now = now_epoch_secs();
+        Some(
+            entry
+                .active
+                .iter()
+                .zip(&entry.cooldown_until)
+                .map(|(active, until)| SlotStats {
+                    in_flight: active.load(Ordering::Relaxed),
+                    cooldown_secs: until.load(Ordering::Relaxed).saturating_sub(now),
+                    latency_ms: None,
+                })
+                .collect(),
+        )
+    }
+
     /// 키 해제 (활성 연결 카운터 감소)
     pub fn release(&self, route_idx: usize, key_idx: usize) {
         if let Some(Some(entry)) = self.entries.get(route_idx) {
@@ -268,20 +369,38 @@ impl KeyPool {
     /// `retry_after_secs`가 Some이면 해당 값 사용 (Retry-After 헤더),
     /// None이면 기본 60초 적용.
     pub fn set_cooldown(&self, route_idx: usize, key_idx: usize, retry_after_secs: Option<u64>) {
-        if let Some(Some(entry)) = self.entries.get(route_idx) {
-            if let Some(cd) = entry.cooldown_until.get(key_idx) {
-                let secs = retry_after_secs.unwrap_or(DEFAULT_COOLDOWN_SECS);
-                let until = now_epoch_secs() + secs;
-                cd.store(until, Ordering::Relaxed);
-                tracing::warn!(
-                    route = route_idx,
-                    key = key_idx,
-                    cooldown_secs = secs,
-                    "429 수신, 키 쿨다운 설정"
-                );
-            }
+        if let Some(secs) = self.store_cooldown(route_idx, key_idx, retry_after_secs) {
+            tracing::warn!(
+                route = route_idx,
+                key = key_idx,
+                cooldown_secs = secs,
+                "429 수신, 키 쿨다운 설정"
+            );
+        }
+    }
+
+    /// 관리 API 요청으로 키 쿨다운 설정 (0이면 해제, None이면 기본 60초)
+    pub fn set_manual_cooldown(&self, route_idx: usize, key_idx: usize, secs: Option<u64>) {
+        if let Some(secs) = self.store_cooldown(route_idx, key_idx, secs) {
+            tracing::info!(
+                route = route_idx,
+                key = key_idx,
+                cooldown_secs = secs,
+                "관리 API 요청으로 키 쿨다운 설정"
+            );
         }
     }
+
+    /// 쿨다운 종료 시각 기록 (적용한 초, 키가 없으면 None)
+    fn store_cooldown(&self, route_idx: usize, key_idx: usize, secs: Option<u64>) -> Option<u64> {
+        let Some(Some(entry)) = self.entries.get(route_idx) else {
+            return None;
+        };
+        let cd = entry.cooldown_until.get(key_idx)?;
+        let secs = secs.unwrap_or(DEFAULT_COOLDOWN_SECS);
+        cd.store(now_epoch_secs() + secs, Ordering::Relaxed);
+        Some(secs)
+    }
 }
 
 /// 키 풀 자동 해제 가드
@@ -315,6 +434,214 @@ impl Drop for PoolGuard {
     }
 }
 
+/// 지연 EWMA 가중치 (새 측정값 비율, 0.3)
+const LATENCY_EWMA_NUM: u64 = 3;
+const LATENCY_EWMA_DEN: u64 = 10;
+
+/// 라우트별 업스트림 부하 분산 — KeyPool과 같은 방식으로 대상별 상태 추적
+///
+/// `upstreams`가 설정된 라우트마다 대상별 진행 중 요청 수, 쿨다운, 응답 지연(EWMA)을
+/// 기록하고 라우트의 `balance` 전략에 따라 대상을 고른다.
+/// 쿨다운 중이거나 `concurrency` 제한에 도달한 대상은 건너뛴다.
+pub struct UpstreamPool {
+    entries: Vec<Option<Arc<BalanceEntry>>>,
+}
+
+struct BalanceEntry {
+    strategy: BalanceStrategy,
+    weights: Vec<u32>,
+    costs: Vec<Option<f64>>,
+    limits: Vec<Option<usize>>,
+    /// 대상별 진행 중 요청 수
+    active: Vec<AtomicUsize>,
+    /// 대상별 쿨다운 만료 시각 (Unix epoch 초, 0이면 쿨다운 없음)
+    cooldown_until: Vec<AtomicU64>,
+    /// 대상별 응답 지연 EWMA (ms, 0이면 미측정)
+    latency_ms: Vec<AtomicU64>,
+    /// 무작위 선택/동점 순환용 카운터
+    next_idx: AtomicU64,
+}
+
+impl UpstreamPool {
+    /// Config로부터 UpstreamPool 생성
+    pub fn from_config(config: &Config) -> Self {
+        let entries = config
+            .routes
+            .iter()
+            .map(|route| {
+                if route.upstreams.is_empty() {
+                    return None;
+                }
+                let n = route.upstreams.len();
+                Some(Arc::new(BalanceEntry {
+                    strategy: route.balance,
+                    weights: route.upstreams.iter().map(|u| u.weight).collect(),
+                    costs: route.upstreams.iter().map(|u| u.cost).collect(),
+                    limits: route.upstreams.iter().map(|u| u.concurrency).collect(),
+                    active: (0..n).map(|_| AtomicUsize::new(0)).collect(),
+                    cooldown_until: (0..n).map(|_| AtomicU64::new(0)).collect(),
+                    latency_ms: (0..n).map(|_| AtomicU64::new(0)).collect(),
+                    next_idx: AtomicU64::new(now_epoch_secs()),
+                }))
+            })
+            .collect();
+
+        UpstreamPool { entries }
+    }
+
+    /// 리로드된 Config로 새 UpstreamPool 생성 (변경 없는 라우트는 대상별 상태 공유)
+    pub fn reload(&self, config: &Config, previous: &[Option<usize>]) -> Self {
+        let mut entries = Self::from_config(config).entries;
+        carry_over(&mut entries, &self.entries, previous);
+        UpstreamPool { entries }
+    }
+
+    /// 전략에 따라 대상 획득 (`exclude`는 이번 요청에서 이미 실패한 대상)
+    ///
+    /// 모든 대상이 제외/쿨다운/제한 상태면 None 반환.
+    pub fn acquire(&self, route_idx: usize, exclude: &[usize]) -> Option<usize> {
+        let entry = self.entries.get(route_idx)?.as_ref()?;
+        let now = now_epoch_secs();
+        let seed = entry.next_idx.fetch_add(1, Ordering::Relaxed);
+        let n = entry.active.len();
+
+        // 오프셋부터 순환하여 동점 시 매번 다른 대상부터 탐색
+        let available: Vec<usize> = (0..n)
+            .map(|j| (seed as usize + j) % n)
+            .filter(|&i| !exclude.contains(&i))
+            .filter(|&i| entry.cooldown_until[i].load(Ordering::Relaxed) <= now)
+            .filter(|&i| {
+                let limit = entry.limits[i].unwrap_or(usize::MAX);
+                entry.active[i].load(Ordering::Relaxed) < limit
+            })
+            .collect();
+
+        let idx = match entry.strategy {
+            BalanceStrategy::Weighted => entry.pick_weighted(&available, seed),
+            BalanceStrategy::LeastLatency => available
+                .iter()
+                .copied()
+                .min_by_key(|&i| entry.latency_ms[i].load(Ordering::Relaxed)),
+            BalanceStrategy::LeastInFlight => available
+                .iter()
+                .copied()
+                .min_by_key(|&i| entry.active[i].load(Ordering::Relaxed)),
+            BalanceStrategy::Cheapest => {
+                let cost = |i: usize| entry.costs[i].unwrap_or(f64::INFINITY);
+                let cheapest = available.iter().map(|&i| cost(i)).fold(f64::INFINITY, f64::min);
+                let tied: Vec<usize> = available.iter().copied().filter(|&i| cost(i) == cheapest).collect();
+                entry.pick_weighted(&tied, seed)
+            }
+        }?;
+
+        entry.active[idx].fetch_add(1, Ordering::Relaxed);
+        Some(idx)
+    }
+
+    /// 라우트의 `upstreams` 대상별 상태 (`upstreams`가 없으면 None)
+    pub fn upstream_stats(&self, route_idx: usize) -> Option<Vec<SlotStats>> {
+        let entry = self.entries.get(route_idx)?.as_ref()?;
+        let now = now_epoch_secs();
+        Some(
+            (0..entry.active.len())
+                .map(|i| SlotStats {
+                    in_flight: entry.active[i].load(Ordering::Relaxed),
+                    cooldown_secs: entry.cooldown_until[i].load(Ordering::Relaxed).saturating_sub(now),
+                    latency_ms: Some(entry.latency_ms[i].load(Ordering::Relaxed)).filter(|&ms| ms > 0),
+                })
+                .collect(),
+        )
+    }
+
+    /// 대상 해제 (진행 중 요청 수 감소)
+    pub fn release(&self, route_idx: usize, upstream_idx: usize) {
+        if let Some(Some(entry)) = self.entries.get(route_idx) {
+            if let Some(c) = entry.active.get(upstream_idx) {
+                c.fetch_sub(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// 응답 헤더 수신까지 걸린 시간 기록 (EWMA)
+    pub fn record_latency(&self, route_idx: usize, upstream_idx: usize, elapsed: Duration) {
+        if let Some(Some(entry)) = self.entries.get(route_idx) {
+            if let Some(l) = entry.latency_ms.get(upstream_idx) {
+                // 0은 미측정 표시이므로 최소 1ms
+                let sample = (elapsed.as_millis() as u64).max(1);
+                let prev = l.load(Ordering::Relaxed);
+                let next = match prev {
+                    0 => sample,
+                    _ => (sample * LATENCY_EWMA_NUM + prev * (LATENCY_EWMA_DEN - LATENCY_EWMA_NUM)) / LATENCY_EWMA_DEN,
+                };
+                l.store(next, Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// 429 응답을 받은 대상에 쿨다운 설정 (None이면 기본 60초)
+    pub fn set_cooldown(&self, route_idx: usize, upstream_idx: usize, retry_after_secs: Option<u64>) {
+        if let Some(Some(entry)) = self.entries.get(route_idx) {
+            if let Some(cd) = entry.cooldown_until.get(upstream_idx) {
+                let secs = retry_after_secs.unwrap_or(DEFAULT_COOLDOWN_SECS);
+                cd.store(now_epoch_secs() + secs, Ordering::Relaxed);
+                tracing::warn!(
+                    route = route_idx,
+                    upstream = upstream_idx,
+                    cooldown_secs = secs,
+                    "429 수신, 업스트림 쿨다운 설정"
+                );
+            }
+        }
+    }
+}
+
+impl BalanceEntry {
+    /// 가중치 비례 무작위 선택 (가중치가 모두 0이면 첫 후보)
+    fn pick_weighted(&self, candidates: &[usize], seed: u64) -> Option<usize> {
+        let total: u64 = candidates.iter().map(|&i| u64::from(self.weights[i])).sum();
+        if total == 0 {
+            return candidates.first().copied();
+        }
+        let mut r = splitmix64(seed) % total;
+        for &i in candidates {
+            let w = u64::from(self.weights[i]);
+            if r < w {
+                return Some(i);
+            }
+            r -= w;
+        }
+        None
+    }
+}
+
+/// 업스트림 풀 자동 해제 가드 (PoolGuard와 동일하게 Body에 부착)
+pub struct UpstreamGuard {
+    pool: Arc<UpstreamPool>,
+    route_idx: usize,
+    upstream_idx: usize,
+}
+
+impl UpstreamGuard {
+    pub fn new(pool: Arc<UpstreamPool>, route_idx: usize, upstream_idx: usize) -> Self {
+        UpstreamGuard {
+            pool,
+            route_idx,
+            upstream_idx,
+        }
+    }
+}
+
+impl Drop for UpstreamGuard {
+    fn drop(&mut self) {
+        self.pool.release(self.route_idx, self.upstream_idx);
+        tracing::debug!(
+            route = self.route_idx,
+            upstream = self.upstream_idx,
+            "업스트림 연결 해제"
+        );
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -325,14 +652,20 @@ mod tests {
             server: ServerConfig {
                 host: "127.0.0.1".into(),
                 port: 18081,
+                admin_token: None,
             },
             default: DefaultConfig {
                 url: "https://api.anthropic.com".into(),
+                retry: None,
+                timeouts: None,
             },
+            transformers: vec![],
             routes: vec![
                 // 라우트 0: 풀 있음, concurrency 1
                 RouteConfig {
                     match_pattern: "glm-5".into(),
+                    matcher: None,
+                    when: None,
                     upstream: UpstreamConfig {
                         url: "https://open.bigmodel.cn".into(),
                         auth: AuthConfig {
@@ -343,21 +676,40 @@ mod tests {
                             client_secret: None,
                             refresh_token: None,
                             token_url: None,
+                            access_key_id: None,
+                            secret_access_key: None,
+                            session_token: None,
+                            region: None,
+                            service: None,
+                            credentials_file: None,
+                            scope: None,
                             pool: Some(vec![
                                 "Bearer key2".into(),
                                 "Bearer key3".into(),
                             ]),
                         },
+                        vertex: None,
+                        path: None,
+                        query: Default::default(),
                     },
                     transformer: None,
                     model_map: None,
                     fallback: Fallback::Passthrough,
                     concurrency: Some(1),
                     account_concurrency: None,
+                    upstreams: vec![],
+                    balance: Default::default(),
+                    circuit_breaker: None,
+                    retry: None,
+                    timeouts: None,
+                    stream_recovery: false,
+                    reasoning_effort: Default::default(),
                 },
                 // 라우트 1: 풀 없음
                 RouteConfig {
                     match_pattern: "kimi".into(),
+                    matcher: None,
+                    when: None,
                     upstream: UpstreamConfig {
                         url: "https://api.kimi.com".into(),
                         auth: AuthConfig {
@@ -368,14 +720,31 @@ mod tests {
                             client_secret: None,
                             refresh_token: None,
                             token_url: None,
+                            access_key_id: None,
+                            secret_access_key: None,
+                            session_token: None,
+                            region: None,
+                            service: None,
+                            credentials_file: None,
+                            scope: None,
                             pool: None,
                         },
+                        vertex: None,
+                        path: None,
+                        query: Default::default(),
                     },
                     transformer: None,
                     model_map: None,
                     fallback: Fallback::Passthrough,
                     concurrency: None,
                     account_concurrency: None,
+                    upstreams: vec![],
+                    balance: Default::default(),
+                    circuit_breaker: None,
+                    retry: None,
+                    timeouts: None,
+                    stream_recovery: false,
+                    reasoning_effort: Default::default(),
                 },
             ],
         }
@@ -635,12 +1004,18 @@ mod tests {
             server: ServerConfig {
                 host: "127.0.0.1".into(),
                 port: 18081,
+                admin_token: None,
             },
             default: DefaultConfig {
                 url: "https://api.anthropic.com".into(),
+                retry: None,
+                timeouts: None,
             },
+            transformers: vec![],
             routes: vec![RouteConfig {
                 match_pattern: "test".into(),
+                matcher: None,
+                when: None,
                 upstream: UpstreamConfig {
                     url: "https://test.com".into(),
                     auth: AuthConfig {
@@ -651,14 +1026,31 @@ mod tests {
                         client_secret: None,
                         refresh_token: None,
                         token_url: None,
+                        access_key_id: None,
+                        secret_access_key: None,
+                        session_token: None,
+                        region: None,
+                        service: None,
+                        credentials_file: None,
+                        scope: None,
                         pool: None,
                     },
+                    vertex: None,
+                    path: None,
+                    query: Default::default(),
                 },
                 transformer: None,
                 model_map: None,
                 fallback: Fallback::Passthrough,
                 concurrency: None,
                 account_concurrency: Some(2), // 동시 2개 제한
+                upstreams: vec![],
+                balance: Default::default(),
+                circuit_breaker: None,
+                retry: None,
+                timeouts: None,
+                stream_recovery: false,
+                reasoning_effort: Default::default(),
             }],
         };
 
@@ -685,18 +1077,64 @@ mod tests {
         assert!(permit3.is_some());
     }
 
+    /// 리로드: 변경 없는 라우트는 위치가 바뀌어도 진행 중 요청 수/세마포어 공유, 바뀐 라우트는 새로 시작
+    #[tokio::test]
+    async fn test_reload_preserves_unchanged_routes() {
+        let mut old = make_pool_config();
+        old.routes[0].account_concurrency = Some(2);
+        let pool = KeyPool::from_config(&old);
+        let sem = AccountSemaphore::from_config(&old);
+        let key = pool.acquire(0).unwrap();
+        let _permit = sem.acquire(0).await.unwrap();
+
+        // 맨 앞에 라우트 추가 → glm-5는 1번으로 이동
+        let mut new = old.clone();
+        let mut added = old.routes[1].clone();
+        added.match_pattern = "qwen".into();
+        new.routes.insert(0, added);
+        let previous = new.unchanged_routes(&old);
+        assert_eq!(previous, vec![None, Some(0), Some(1)]);
+
+        let reloaded = KeyPool::reload(&pool, &new, &previous);
+        let reloaded_sem = sem.reload(&new, &previous);
+        let entry = reloaded.entries[1].as_ref().unwrap();
+        assert_eq!(entry.active[key].load(Ordering::Relaxed), 1);
+        assert_eq!(reloaded_sem.stats(1).unwrap().available, 1);
+
+        // 리로드 전 요청의 해제가 새 풀에도 반영
+        pool.release(0, key);
+        assert_eq!(entry.active[key].load(Ordering::Relaxed), 0);
+
+        // 설정이 바뀐 라우트는 새 상태
+        let mut changed = old.clone();
+        changed.routes[0].concurrency = Some(2);
+        let previous = changed.unchanged_routes(&old);
+        assert_eq!(previous, vec![None, Some(1)]);
+        pool.acquire(0).unwrap();
+        let fresh = pool.reload(&changed, &previous);
+        let entry = fresh.entries[0].as_ref().unwrap();
+        assert!(entry.active.iter().all(|c| c.load(Ordering::Relaxed) == 0));
+        assert_eq!(fresh.acquire(0).map(|_| ()), Some(()));
+    }
+
     #[tokio::test]
     async fn test_account_semaphore_no_limit() {
         let config = Config {
             server: ServerConfig {
                 host: "127.0.0.1".into(),
                 port: 18081,
+                admin_token: None,
             },
             default: DefaultConfig {
                 url: "https://api.anthropic.com".into(),
+                retry: None,
+                timeouts: None,
             },
+            transformers: vec![],
             routes: vec![RouteConfig {
                 match_pattern: "test".into(),
+                matcher: None,
+                when: None,
                 upstream: UpstreamConfig {
                     url: "https://test.com".into(),
                     auth: AuthConfig {
@@ -707,14 +1145,31 @@ mod tests {
                         client_secret: None,
                         refresh_token: None,
                         token_url: None,
+                        access_key_id: None,
+                        secret_access_key: None,
+                        session_token: None,
+                        region: None,
+                        service: None,
+                        credentials_file: None,
+                        scope: None,
                         pool: None,
                     },
+                    vertex: None,
+                    path: None,
+                    query: Default::default(),
                 },
                 transformer: None,
                 model_map: None,
                 fallback: Fallback::Passthrough,
                 concurrency: None,
                 account_concurrency: None, // 제한 없음
+                upstreams: vec![],
+                balance: Default::default(),
+                circuit_breaker: None,
+                retry: None,
+                timeouts: None,
+                stream_recovery: false,
+                reasoning_effort: Default::default(),
             }],
         };
 
@@ -727,4 +1182,101 @@ mod tests {
         let p2 = sem.acquire(0).await;
         assert!(p2.is_none());
     }
+
+    /// 업스트림 3개(비용 3/1/1, 가중치 1/0/5)인 라우트 하나
+    fn make_balanced_config(balance: BalanceStrategy) -> Config {
+        let member = |url: &str, weight: u32, cost: f64, concurrency: Option<usize>| BalancedUpstream {
+            upstream: UpstreamConfig {
+                url: url.into(),
+                ..Default::default()
+            },
+            transformer: None,
+            model_map: None,
+            weight,
+            cost: Some(cost),
+            concurrency,
+        };
+        let mut config = Config::default_config();
+        config.routes.push(RouteConfig {
+            match_pattern: "glm-5".into(),
+            matcher: None,
+            when: None,
+            upstream: UpstreamConfig::default(),
+            transformer: None,
+            model_map: None,
+            fallback: Fallback::Disabled,
+            concurrency: None,
+            account_concurrency: None,
+            upstreams: vec![
+                member("https://a.example.com", 1, 3.0, None),
+                member("https://b.example.com", 0, 1.0, Some(1)),
+                member("https://c.example.com", 5, 1.0, None),
+            ],
+            balance,
+            circuit_breaker: None,
+            retry: None,
+            timeouts: None,
+            stream_recovery: false,
+            reasoning_effort: Default::default(),
+        });
+        config
+    }
+
+    #[test]
+    fn test_upstream_weighted_skips_zero_weight() {
+        let pool = UpstreamPool::from_config(&make_balanced_config(BalanceStrategy::Weighted));
+        let mut counts = [0usize; 3];
+        for _ in 0..600 {
+            let idx = pool.acquire(0, &[]).unwrap();
+            counts[idx] += 1;
+            pool.release(0, idx);
+        }
+        assert_eq!(counts[1], 0);
+        assert!(counts[2] > counts[0] * 2, "{counts:?}");
+
+        // 가중치가 있는 대상이 모두 제외되면 가중치 0 대상 사용
+        assert_eq!(pool.acquire(0, &[0, 2]), Some(1));
+        assert!(UpstreamPool::from_config(&make_pool_config()).acquire(0, &[]).is_none());
+    }
+
+    #[test]
+    fn test_upstream_cheapest_and_concurrency() {
+        let pool = UpstreamPool::from_config(&make_balanced_config(BalanceStrategy::Cheapest));
+        // 비용 1인 b(가중치 0), c(가중치 5) 중 가중치 비례 → c
+        assert_eq!(pool.acquire(0, &[]), Some(2));
+        assert_eq!(pool.acquire(0, &[2]), Some(1));
+        // b는 concurrency 1에 도달 → 다음으로 싼 a
+        assert_eq!(pool.acquire(0, &[2]), Some(0));
+        pool.release(0, 1);
+        assert_eq!(pool.acquire(0, &[2]), Some(1));
+    }
+
+    #[test]
+    fn test_upstream_least_in_flight() {
+        let pool = UpstreamPool::from_config(&make_balanced_config(BalanceStrategy::LeastInFlight));
+        let mut held: Vec<usize> = (0..3).map(|_| pool.acquire(0, &[]).unwrap()).collect();
+        held.sort();
+        assert_eq!(held, vec![0, 1, 2]);
+        pool.release(0, 2);
+        assert_eq!(pool.acquire(0, &[]), Some(2));
+    }
+
+    #[test]
+    fn test_upstream_least_latency_and_cooldown() {
+        let pool = Arc::new(UpstreamPool::from_config(&make_balanced_config(BalanceStrategy::LeastLatency)));
+        pool.record_latency(0, 0, Duration::from_millis(300));
+        pool.record_latency(0, 1, Duration::from_millis(100));
+        pool.record_latency(0, 2, Duration::from_millis(200));
+        assert_eq!(pool.acquire(0, &[]), Some(1));
+        pool.release(0, 1);
+
+        // EWMA: 100 → 0.3*1000 + 0.7*100 = 370 → c(200)가 가장 빠름
+        pool.record_latency(0, 1, Duration::from_millis(1000));
+        {
+            let _guard = UpstreamGuard::new(pool.clone(), 0, pool.acquire(0, &[]).unwrap());
+        }
+        // 쿨다운 중인 대상은 건너뜀, 가드 drop으로 해제된 c 재선택 가능
+        pool.set_cooldown(0, 2, Some(60));
+        assert_eq!(pool.acquire(0, &[]), Some(0));
+    }
 }
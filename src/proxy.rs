@@ -7,15 +7,22 @@ use http_body_util::{BodyExt, Full};
 use uuid::Uuid;
 
 use std::sync::Arc;
-use std::time::Duration;
-
-use crate::config::RouteConfig;
-use crate::pool::{PoolGuard, SemaphoreGuard};
-use crate::transformer::{self, StreamContext, Transformer};
+use std::time::{Duration, Instant};
+
+use crate::admin::RequestTracker;
+use crate::auth::sigv4::SigV4Signer;
+use crate::breaker::CircuitScope;
+use crate::config::{Fallback, RequestAttributes, RouteConfig, TimeoutConfig, UpstreamFailure, VertexConfig};
+use crate::pool::{PoolGuard, SemaphoreGuard, UpstreamGuard};
+use crate::transformer::error::error_type_for_status;
+use crate::transformer::stream::sse_event;
+use crate::transformer::{self, tokens, ApiError, StreamContext, Transformer};
+use crate::reload::SharedState;
+use crate::util::jitter_random;
 use crate::AppState;
 
-/// 세마포어 대기 최대 시간 (500분 = 30,000,000ms)
-const SEMAPHORE_TIMEOUT_MS: u64 = 30_000_000;
+/// Vertex AI에서 Anthropic 모델 호출 시 본문에 넣는 API 버전
+const VERTEX_ANTHROPIC_VERSION: &str = "vertex-2023-10-16";
 
 /// 인증 관련 헤더인지 확인
 fn is_auth_header(name: &str) -> bool {
@@ -81,6 +88,43 @@ fn extract_model(bytes: &[u8]) -> Result<String, StatusCode> {
         .ok_or(StatusCode::BAD_REQUEST)
 }
 
+/// 라우트 조건 평가용 요청 속성 추출
+fn request_attributes(headers: &axum::http::HeaderMap, bytes: &[u8], model: &str) -> RequestAttributes {
+    let body: serde_json::Value = serde_json::from_slice(bytes).unwrap_or_default();
+    let system = match &body["system"] {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Array(blocks) => blocks
+            .iter()
+            .filter_map(|b| b["text"].as_str())
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    };
+    RequestAttributes {
+        model: model.to_string(),
+        has_tools: body["tools"].as_array().is_some_and(|t| !t.is_empty()),
+        has_images: body["messages"].as_array().is_some_and(|msgs| msgs.iter().any(|m| has_image(&m["content"]))),
+        thinking: body["thinking"]["type"].as_str().is_some_and(|t| t != "disabled"),
+        input_tokens: tokens::estimate_request_tokens(&body),
+        system,
+        headers: headers
+            .iter()
+            .filter_map(|(k, v)| Some((k.as_str().to_string(), v.to_str().ok()?.to_string())))
+            .collect(),
+    }
+}
+
+/// content 블록 배열에 이미지가 있는지 (tool_result 안쪽 포함)
+fn has_image(content: &serde_json::Value) -> bool {
+    content.as_array().is_some_and(|blocks| {
+        blocks.iter().any(|b| match b["type"].as_str() {
+            Some("image") => true,
+            Some("tool_result") => has_image(&b["content"]),
+            _ => false,
+        })
+    })
+}
+
 /// JSON 본문에서 stream 필드 추출
 fn is_stream_request(bytes: &[u8]) -> bool {
     serde_json::from_slice::<serde_json::Value>(bytes)
@@ -99,20 +143,92 @@ fn replace_model(bytes: &Bytes, new_model: &str) -> Result<Bytes, StatusCode> {
 }
 
 /// 폴백 시 모델명 교체 적용
-fn apply_fallback_model(bytes: &Bytes, fallback: &crate::config::Fallback) -> Result<Bytes, StatusCode> {
+fn apply_fallback_model(bytes: &Bytes, fallback: &Fallback) -> Result<Bytes, StatusCode> {
     match fallback.model() {
         Some(model) => replace_model(bytes, model),
         None => Ok(bytes.clone()),
     }
 }
 
+/// Vertex AI Anthropic 요청으로 변환: model은 경로로 이동, anthropic_version 본문 필드 추가
+fn vertex_anthropic_request(
+    vertex: &VertexConfig,
+    model_map: Option<&str>,
+    bytes: &Bytes,
+) -> Result<(String, Bytes), StatusCode> {
+    let mut json: serde_json::Value =
+        serde_json::from_slice(bytes).map_err(|_| StatusCode::BAD_REQUEST)?;
+    let obj = json.as_object_mut().ok_or(StatusCode::BAD_REQUEST)?;
+    let model = obj.remove("model");
+    let model = model_map.or_else(|| model.as_ref().and_then(|m| m.as_str())).unwrap_or_default();
+    let method = if obj.get("stream").and_then(|s| s.as_bool()) == Some(true) {
+        "streamRawPredict"
+    } else {
+        "rawPredict"
+    };
+    let path = vertex.model_path(model, method);
+    obj.entry("anthropic_version")
+        .or_insert_with(|| VERTEX_ANTHROPIC_VERSION.into());
+    let body = serde_json::to_vec(&json).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok((path, Bytes::from(body)))
+}
+
+/// Anthropic 형식 에러 응답 생성
+fn error_response(status: StatusCode, error_type: &str, message: &str) -> Response<Body> {
+    let body = serde_json::json!({
+        "type": "error",
+        "error": {"type": error_type, "message": message}
+    });
+    let mut response = Response::new(Body::from(body.to_string()));
+    *response.status_mut() = status;
+    response.headers_mut().insert(
+        hyper::header::CONTENT_TYPE,
+        "application/json".parse().unwrap(),
+    );
+    response
+}
+
+/// 변환된 에러 응답에도 유지할 업스트림 헤더 (재시도 대기/키 쿨다운 판단, 클라이언트 표시용)
+fn is_rate_limit_header(name: &str) -> bool {
+    name == "retry-after" || name.starts_with("x-ratelimit-") || name.starts_with("anthropic-ratelimit-")
+}
+
+/// 업스트림 에러 → Anthropic 형식 에러 응답 (원본의 Retry-After/레이트 리밋 헤더 복사)
+fn upstream_error_response(upstream_headers: &axum::http::HeaderMap, err: &ApiError) -> Response<Body> {
+    let status = StatusCode::from_u16(err.status).unwrap_or(StatusCode::BAD_GATEWAY);
+    let mut response = error_response(status, err.error_type, &err.message);
+    for (name, value) in upstream_headers {
+        if is_rate_limit_header(name.as_str()) {
+            response.headers_mut().append(name, value.clone());
+        }
+    }
+    response
+}
+
 /// 모든 요청을 처리하는 프록시 핸들러
 /// - POST /v1/messages → 모델 기반 라우팅 (+ 트랜스포머 변환)
 /// - 그 외 → Anthropic API 패스스루
-pub async fn proxy_handler(
-    State(state): State<AppState>,
-    req: Request<Body>,
-) -> Result<Response<Body>, StatusCode> {
+/// - 프록시가 직접 만든 오류(타임아웃, 키/업스트림 소진, 서킷 차단 등)는 Anthropic 에러 형식으로 응답
+pub async fn proxy_handler(State(shared): State<SharedState>, req: Request<Body>) -> Response<Body> {
+    // 요청 처리 중 설정이 리로드돼도 이 요청은 시작 시점의 상태로 끝까지 처리
+    handle_request(shared.current(), req).await.unwrap_or_else(|status| {
+        error_response(status, error_type_for_status(status.as_u16()), error_message_for_status(status))
+    })
+}
+
+/// 프록시 오류 상태 코드별 클라이언트 표시 메시지
+fn error_message_for_status(status: StatusCode) -> &'static str {
+    match status {
+        StatusCode::BAD_REQUEST => "요청 본문을 해석할 수 없습니다",
+        StatusCode::TOO_MANY_REQUESTS => "사용 가능한 API 키나 업스트림이 없습니다",
+        StatusCode::BAD_GATEWAY => "업스트림에 연결할 수 없습니다",
+        StatusCode::SERVICE_UNAVAILABLE => "업스트림 서킷이 열려 있어 요청을 보내지 않았습니다",
+        StatusCode::GATEWAY_TIMEOUT => "업스트림 응답 시간이 초과되었습니다",
+        _ => "프록시 내부 오류가 발생했습니다",
+    }
+}
+
+async fn handle_request(state: AppState, req: Request<Body>) -> Result<Response<Body>, StatusCode> {
     let (parts, body) = req.into_parts();
 
     let is_messages = parts.method == Method::POST && parts.uri.path() == "/v1/messages";
@@ -126,21 +242,58 @@ pub async fn proxy_handler(
     }
 
     let model = extract_model(&bytes)?;
-    let route_match = state.config.find_route(&model);
+    let attrs = request_attributes(&parts.headers, &bytes, &model);
+    let route_match = state.config.find_route_for(&attrs);
 
     tracing::info!(model = %model, routed = route_match.is_some(), "라우팅 결정");
 
+    // 관리 API용 진행 중 요청 수 + 최근 요청 요약 (응답 스트림이 끝날 때 기록)
+    let tracker = state.admin.track(
+        &model,
+        route_match.map(|(_, r)| r.match_pattern.as_str()),
+        is_stream_request(&bytes),
+    );
+    let result = route_request(&state, &parts, bytes, &model, route_match).await;
+    attach_tracker(result, tracker)
+}
+
+/// 라우팅 결과에 따라 요청 처리 (라우트 없음 → 패스스루)
+async fn route_request(
+    state: &AppState,
+    parts: &axum::http::request::Parts,
+    bytes: Bytes,
+    model: &str,
+    route_match: Option<(usize, &RouteConfig)>,
+) -> Result<Response<Body>, StatusCode> {
     // 라우팅 대상이 아니면 패스스루
     let (route_idx, route) = match route_match {
         Some(pair) => pair,
         None => {
-            return forward(&state, &parts, bytes, None, None).await;
+            return forward(state, parts, bytes, None, None).await;
         }
     };
+    // model_map의 캡처 그룹 참조($1 등) 치환
+    let resolved = route.resolve_for(model);
+    let route = resolved.as_ref();
+
+    // 관리 API로 드레인 중인 라우트: 새 요청은 업스트림으로 보내지 않음 (진행 중 요청은 유지)
+    if state.admin.is_draining(&route.match_pattern) {
+        tracing::warn!(route_idx, "드레인 중인 라우트, 업스트림 건너뜀");
+        let last = Ok(error_response(
+            StatusCode::SERVICE_UNAVAILABLE,
+            error_type_for_status(503),
+            "라우트가 드레인 중입니다",
+        ));
+        if route.fallback.is_enabled() {
+            return run_fallback(state, parts, &bytes, route, UpstreamFailure::Status(503), last).await;
+        }
+        return last;
+    }
 
-    // 계정 세마포어 획득 (타임아웃 적용)
+    // 계정 세마포어 획득 (대기열 타임아웃 적용)
+    let queue_timeout = state.config.timeouts_for(Some(route)).queue();
     let account_permit = match tokio::time::timeout(
-        Duration::from_millis(SEMAPHORE_TIMEOUT_MS),
+        queue_timeout,
         state.account_semaphore.acquire(route_idx),
     )
     .await
@@ -156,21 +309,35 @@ pub async fn proxy_handler(
         Err(_) => {
             tracing::error!(
                 route_idx,
-                timeout_mins = 500,
+                timeout_secs = queue_timeout.as_secs(),
                 "계정 세마포어 대기 타임아웃"
             );
 
+            let last = Ok(error_response(
+                StatusCode::SERVICE_UNAVAILABLE,
+                error_type_for_status(503),
+                "계정 동시 요청 대기열 시간이 초과되었습니다",
+            ));
             if route.fallback.is_enabled() {
-                tracing::warn!("타임아웃 발생, Anthropic API로 폴백");
-                let fallback_bytes = apply_fallback_model(&bytes, &route.fallback)?;
-                // 폴백은 Anthropic API로 가므로 permit 없이 전달
-                return forward(&state, &parts, fallback_bytes, None, None).await;
+                tracing::warn!("타임아웃 발생, 폴백 실행");
+                // 폴백 대상은 이 라우트의 계정이 아니므로 permit 없이 전달
+                return run_fallback(state, parts, &bytes, route, UpstreamFailure::Timeout, last).await;
             }
-
-            return Err(StatusCode::SERVICE_UNAVAILABLE);
+            return last;
         }
     };
 
+    // 복수 업스트림 라우트: 전략에 따라 대상 선택
+    if !route.upstreams.is_empty() {
+        return forward_balanced(state, parts, &bytes, route_idx, route, model, account_permit).await;
+    }
+
+    // 라우트 서킷이 열려 있으면 업스트림을 건너뛰고 바로 폴백
+    if !state.breaker.allow(route_idx, CircuitScope::Route) {
+        tracing::warn!(route_idx, "라우트 서킷 열림, 업스트림 건너뜀");
+        return skip_open_circuit(state, parts, &bytes, route, account_permit).await;
+    }
+
     // 키 풀이 있는 라우트: 429 시 다른 키로 재시도하는 루프
     if route.upstream.auth.has_pool() {
         let mut tried_keys: Vec<usize> = Vec::new();
@@ -190,9 +357,19 @@ pub async fn proxy_handler(
                     let values = route.upstream.auth.all_values();
                     let selected = &values[key_idx];
                     let guard = PoolGuard::new(state.key_pool.clone(), route_idx, key_idx);
+                    if !state.breaker.allow(route_idx, CircuitScope::Key(key_idx)) {
+                        tracing::debug!(route_idx, key_idx, "키 서킷 열림, 다른 키 선택");
+                        drop(guard);
+                        tried_keys.push(key_idx);
+                        continue;
+                    }
                     tracing::debug!(route_idx, key_idx, tried = ?tried_keys, "키 풀에서 키 선택");
 
-                    match forward(&state, &parts, bytes.clone(), Some(route), Some(selected.as_str())).await {
+                    let result = forward(state, parts, bytes.clone(), Some(route), Some(selected.as_str())).await;
+                    let outcome = classify_result(&result);
+                    state.breaker.record(route_idx, CircuitScope::Key(key_idx), outcome);
+                    state.breaker.record(route_idx, CircuitScope::Route, outcome);
+                    match result {
                         Ok(resp) if resp.status() == StatusCode::TOO_MANY_REQUESTS => {
                             let retry_after = parse_retry_after(&resp);
                             state.key_pool.set_cooldown(route_idx, key_idx, retry_after);
@@ -206,23 +383,23 @@ pub async fn proxy_handler(
                         Ok(resp) if route.fallback.is_enabled() => {
                             tracing::warn!(
                                 status = %resp.status(),
-                                "외부 제공자 비성공 응답, Anthropic API로 폴백"
+                                "외부 제공자 비성공 응답, 폴백 실행"
                             );
                             drop(guard);
-                            let fallback_bytes = apply_fallback_model(&bytes, &route.fallback)?;
-                            let resp = forward(&state, &parts, fallback_bytes, None, None).await?;
-                            // 폴백은 Anthropic API이므로 account_permit만 전달 (guard는 이미 drop)
+                            let failure = UpstreamFailure::Status(resp.status().as_u16());
+                            let resp = run_fallback(state, parts, &bytes, route, failure, Ok(resp)).await?;
+                            // 폴백 대상은 이 라우트의 키가 아니므로 account_permit만 전달 (guard는 이미 drop)
                             return Ok(attach_permits(resp, account_permit, None));
                         }
                         Ok(resp) => {
                             return Ok(attach_permits(resp, account_permit, Some(guard)));
                         }
-                        Err(_) if route.fallback.is_enabled() => {
-                            tracing::warn!("외부 제공자 연결 실패, Anthropic API로 폴백");
+                        Err(e) if route.fallback.is_enabled() => {
+                            tracing::warn!("외부 제공자 연결 실패, 폴백 실행");
                             drop(guard);
-                            let fallback_bytes = apply_fallback_model(&bytes, &route.fallback)?;
-                            let resp = forward(&state, &parts, fallback_bytes, None, None).await?;
-                            // 폴백은 Anthropic API이므로 account_permit만 전달 (guard는 이미 drop)
+                            let failure = failure_from_error(e);
+                            let resp = run_fallback(state, parts, &bytes, route, failure, Err(e)).await?;
+                            // 폴백 대상은 이 라우트의 키가 아니므로 account_permit만 전달 (guard는 이미 drop)
                             return Ok(attach_permits(resp, account_permit, None));
                         }
                         Err(e) => {
@@ -238,10 +415,11 @@ pub async fn proxy_handler(
                         "사용 가능한 API 키 없음"
                     );
                     if route.fallback.is_enabled() {
-                        tracing::info!("Anthropic API로 폴백");
-                        let fallback_bytes = apply_fallback_model(&bytes, &route.fallback)?;
-                        let resp = forward(&state, &parts, fallback_bytes, None, None).await?;
-                        // 폴백은 Anthropic API이므로 account_permit만 전달
+                        tracing::info!("폴백 실행");
+                        let failure = UpstreamFailure::Status(429);
+                        let last = Err(StatusCode::TOO_MANY_REQUESTS);
+                        let resp = run_fallback(state, parts, &bytes, route, failure, last).await?;
+                        // 폴백 대상은 이 라우트의 키가 아니므로 account_permit만 전달
                         return Ok(attach_permits(resp, account_permit, None));
                     } else {
                         return Err(StatusCode::TOO_MANY_REQUESTS);
@@ -254,36 +432,197 @@ pub async fn proxy_handler(
     // 풀이 없는 라우트: 단일 키로 시도
     match route.fallback.is_enabled() {
         true => {
-            match forward(&state, &parts, bytes.clone(), Some(route), None).await {
+            let result = forward(state, parts, bytes.clone(), Some(route), None).await;
+            state.breaker.record(route_idx, CircuitScope::Route, classify_result(&result));
+            match result {
                 Ok(resp) if resp.status().is_success() => {
                     Ok(attach_permits(resp, account_permit, None))
                 }
                 Ok(resp) => {
                     tracing::warn!(
                         status = %resp.status(),
-                        "외부 제공자 비성공 응답, Anthropic API로 폴백"
+                        "외부 제공자 비성공 응답, 폴백 실행"
                     );
-                    let fallback_bytes = apply_fallback_model(&bytes, &route.fallback)?;
-                    let resp = forward(&state, &parts, fallback_bytes, None, None).await?;
-                    // 폴백은 Anthropic API이므로 account_permit만 전달
+                    let failure = UpstreamFailure::Status(resp.status().as_u16());
+                    let resp = run_fallback(state, parts, &bytes, route, failure, Ok(resp)).await?;
                     Ok(attach_permits(resp, account_permit, None))
                 }
-                Err(_) => {
-                    tracing::warn!("외부 제공자 연결 실패, Anthropic API로 폴백");
-                    let fallback_bytes = apply_fallback_model(&bytes, &route.fallback)?;
-                    let resp = forward(&state, &parts, fallback_bytes, None, None).await?;
-                    // 폴백은 Anthropic API이므로 account_permit만 전달
+                Err(e) => {
+                    tracing::warn!("외부 제공자 연결 실패, 폴백 실행");
+                    let resp = run_fallback(state, parts, &bytes, route, failure_from_error(e), Err(e)).await?;
                     Ok(attach_permits(resp, account_permit, None))
                 }
             }
         }
         false => {
-            let resp = forward(&state, &parts, bytes, Some(route), None).await?;
-            Ok(attach_permits(resp, account_permit, None))
+            let result = forward(state, parts, bytes, Some(route), None).await;
+            state.breaker.record(route_idx, CircuitScope::Route, classify_result(&result));
+            Ok(attach_permits(result?, account_permit, None))
         }
     }
 }
 
+/// 복수 업스트림 라우트 처리
+/// - 라우트의 `balance` 전략으로 대상을 골라 전송
+/// - 연결 실패/타임아웃/429/5xx면 아직 시도하지 않은 대상으로 재시도 (429는 대상 쿨다운)
+/// - 그 외 실패이거나 모든 대상이 실패/불가하면 라우트 폴백 실행
+async fn forward_balanced(
+    state: &AppState,
+    parts: &axum::http::request::Parts,
+    bytes: &Bytes,
+    route_idx: usize,
+    route: &RouteConfig,
+    model: &str,
+    account_permit: Option<SemaphoreGuard>,
+) -> Result<Response<Body>, StatusCode> {
+    let mut tried: Vec<usize> = Vec::new();
+    // 대상을 하나도 고르지 못하면 키 풀 소진과 같이 429로 취급
+    let mut failure = UpstreamFailure::Status(429);
+    let mut last = Err(StatusCode::TOO_MANY_REQUESTS);
+    let mut attempted = false;
+
+    while let Some(upstream_idx) = state.upstream_pool.acquire(route_idx, &tried) {
+        let guard = UpstreamGuard::new(state.upstream_pool.clone(), route_idx, upstream_idx);
+        let Some(member) = route.member_route(upstream_idx) else {
+            break;
+        };
+        if !state.breaker.allow(route_idx, CircuitScope::Upstream(upstream_idx)) {
+            tracing::debug!(route_idx, upstream_idx, "업스트림 서킷 열림, 다른 대상 선택");
+            tried.push(upstream_idx);
+            // 실제로 시도한 대상이 없으면 서킷 차단을 연결 실패로 취급
+            if !attempted {
+                failure = UpstreamFailure::Connect;
+                last = Err(StatusCode::SERVICE_UNAVAILABLE);
+            }
+            continue;
+        }
+        attempted = true;
+        let resolved = member.resolve_for(model);
+        tracing::debug!(route_idx, upstream_idx, url = %member.upstream.url, tried = ?tried, "업스트림 선택");
+
+        let started = Instant::now();
+        let result = forward(state, parts, bytes.clone(), Some(resolved.as_ref()), None).await;
+        if result.is_ok() {
+            state.upstream_pool.record_latency(route_idx, upstream_idx, started.elapsed());
+        }
+        let outcome = classify_result(&result);
+        state.breaker.record(route_idx, CircuitScope::Upstream(upstream_idx), outcome);
+
+        let Some(next) = outcome else {
+            let resp = result?;
+            let (resp_parts, body) = resp.into_parts();
+            let guarded = wrap_body_with_permits(body, account_permit, Some(guard));
+            return Ok(Response::from_parts(resp_parts, guarded));
+        };
+        if let Ok(resp) = &result {
+            if resp.status() == StatusCode::TOO_MANY_REQUESTS {
+                state.upstream_pool.set_cooldown(route_idx, upstream_idx, parse_retry_after(resp));
+            }
+        }
+        tracing::warn!(route_idx, upstream_idx, failure = ?next, "업스트림 실패");
+        tried.push(upstream_idx);
+        failure = next;
+        last = result;
+        if !is_retryable(failure) {
+            break;
+        }
+    }
+
+    tracing::warn!(model = %model, tried = tried.len(), "사용 가능한 업스트림 없음, 폴백 판정");
+    let resp = run_fallback(state, parts, bytes, route, failure, last).await?;
+    Ok(attach_permits(resp, account_permit, None))
+}
+
+/// 서킷이 열린 업스트림을 건너뛰고 바로 폴백 (폴백이 없으면 503)
+async fn skip_open_circuit(
+    state: &AppState,
+    parts: &axum::http::request::Parts,
+    bytes: &Bytes,
+    route: &RouteConfig,
+    account_permit: Option<SemaphoreGuard>,
+) -> Result<Response<Body>, StatusCode> {
+    let last = Err(StatusCode::SERVICE_UNAVAILABLE);
+    let resp = run_fallback(state, parts, bytes, route, UpstreamFailure::Connect, last).await?;
+    Ok(attach_permits(resp, account_permit, None))
+}
+
+/// 다른 업스트림으로 재시도할 만한 실패인지 (연결 실패, 타임아웃, 408/429/5xx)
+fn is_retryable(failure: UpstreamFailure) -> bool {
+    match failure {
+        UpstreamFailure::Connect | UpstreamFailure::Timeout => true,
+        UpstreamFailure::Status(s) => s == 408 || s == 429 || (500..600).contains(&s),
+        UpstreamFailure::Local => false,
+    }
+}
+
+/// forward 결과를 폴백 판정용 실패로 분류 (성공이면 None)
+fn classify_result(result: &Result<Response<Body>, StatusCode>) -> Option<UpstreamFailure> {
+    match result {
+        Ok(resp) if resp.status().is_success() => None,
+        Ok(resp) => Some(UpstreamFailure::Status(resp.status().as_u16())),
+        Err(e) => Some(failure_from_error(*e)),
+    }
+}
+
+/// forward 오류 → 실패 종류
+///
+/// 504는 타임아웃, 502는 연결 실패, 그 외(요청 파싱/빌드, 응답 변환, 토큰 발급 실패 등 500/400)는
+/// 업스트림 연결과 무관한 프록시 쪽 실패로 재시도와 서킷 집계에서 제외
+fn failure_from_error(status: StatusCode) -> UpstreamFailure {
+    match status {
+        StatusCode::GATEWAY_TIMEOUT => UpstreamFailure::Timeout,
+        StatusCode::BAD_GATEWAY => UpstreamFailure::Connect,
+        _ => UpstreamFailure::Local,
+    }
+}
+
+/// 라우트 폴백 실행
+/// - true / "모델명": Anthropic API로 1회 (실패 종류 무관)
+/// - 체인: 순서대로 시도하되 직전 실패가 트리거에 해당하지 않는 대상은 건너뜀, 모두 실패하면 마지막 결과 반환
+async fn run_fallback(
+    state: &AppState,
+    parts: &axum::http::request::Parts,
+    bytes: &Bytes,
+    route: &RouteConfig,
+    failure: UpstreamFailure,
+    last: Result<Response<Body>, StatusCode>,
+) -> Result<Response<Body>, StatusCode> {
+    let targets = match &route.fallback {
+        Fallback::Disabled => return last,
+        Fallback::Chain(targets) => targets,
+        fallback => {
+            let fallback_bytes = apply_fallback_model(bytes, fallback)?;
+            return forward(state, parts, fallback_bytes, None, None).await;
+        }
+    };
+
+    let (mut failure, mut last) = (failure, last);
+    for (hop, target) in targets.iter().enumerate() {
+        if !target.triggered_by(failure) {
+            tracing::info!(hop, failure = ?failure, "폴백 트리거 불일치, 대상 건너뜀");
+            continue;
+        }
+        // 트랜스포머가 없는 대상도 대상의 model_map으로 요청
+        let target_bytes = match target.model_map.as_deref() {
+            Some(model) => replace_model(bytes, model)?,
+            None => bytes.clone(),
+        };
+        let result = forward(state, parts, target_bytes, target.to_route().as_ref(), None).await;
+        match classify_result(&result) {
+            None => {
+                tracing::info!(hop, "폴백 대상 응답 성공");
+                return result;
+            }
+            Some(next) => {
+                tracing::warn!(hop, failure = ?next, "폴백 대상 실패");
+                failure = next;
+                last = result;
+            }
+        }
+    }
+    last
+}
+
 /// 응답 Body에 PoolGuard와 SemaphoreGuard를 부착하여 스트림 종료 시 자동 해제
 fn attach_permits(
     resp: Response<Body>,
@@ -299,11 +638,30 @@ fn attach_permits(
     Response::from_parts(parts, guarded)
 }
 
+/// 응답 상태를 요청 요약에 기록하고, 본문이 끝날 때 요약이 완료되도록 본문에 부착
+fn attach_tracker(
+    result: Result<Response<Body>, StatusCode>,
+    mut tracker: RequestTracker,
+) -> Result<Response<Body>, StatusCode> {
+    match result {
+        Ok(resp) => {
+            tracker.set_status(resp.status());
+            let (parts, body) = resp.into_parts();
+            Ok(Response::from_parts(parts, wrap_body_with_permits(body, None, Some(tracker))))
+        }
+        Err(status) => {
+            tracker.set_status(status);
+            Err(status)
+        }
+    }
+}
+
 /// Body를 permit들과 함께 감싸서 스트림 종료 시 자동 해제
-fn wrap_body_with_permits(
+/// (guard는 PoolGuard, UpstreamGuard 또는 RequestTracker)
+fn wrap_body_with_permits<G: Send + 'static>(
     body: Body,
     account_permit: Option<SemaphoreGuard>,
-    guard: Option<PoolGuard>,
+    guard: Option<G>,
 ) -> Body {
     let stream = async_stream::stream! {
         let _account_permit = account_permit;
@@ -328,12 +686,197 @@ fn wrap_body_with_permits(
     Body::from_stream(stream)
 }
 
-/// 업스트림으로 요청 포워딩
+/// 업스트림 요청 전송 (연결 타임아웃별 클라이언트 사용, 응답 헤더까지 first_byte 타임아웃)
+///
+/// 연결 실패는 502, 연결/첫 바이트 타임아웃은 504 (폴백의 `timeout` 트리거)
+async fn send_upstream(
+    state: &AppState,
+    req: Request<Full<Bytes>>,
+    timeouts: &TimeoutConfig,
+) -> Result<Response<hyper::body::Incoming>, StatusCode> {
+    let client = state.client_for(timeouts.connect());
+    match tokio::time::timeout(timeouts.first_byte(), client.request(req)).await {
+        Ok(Ok(resp)) => Ok(resp),
+        Ok(Err(e)) if is_timeout_error(&e) => {
+            tracing::error!(error = %e, connect_secs = timeouts.connect().as_secs(), "업스트림 연결 타임아웃");
+            Err(StatusCode::GATEWAY_TIMEOUT)
+        }
+        Ok(Err(e)) => {
+            tracing::error!(error = %e, "업스트림 요청 실패");
+            Err(StatusCode::BAD_GATEWAY)
+        }
+        Err(_) => {
+            tracing::error!(first_byte_secs = timeouts.first_byte().as_secs(), "업스트림 첫 바이트 타임아웃");
+            Err(StatusCode::GATEWAY_TIMEOUT)
+        }
+    }
+}
+
+/// 오류 원인 체인에 타임아웃(io::ErrorKind::TimedOut)이 있는지
+fn is_timeout_error(err: &(dyn std::error::Error + 'static)) -> bool {
+    let mut source = Some(err);
+    while let Some(e) = source {
+        if e.downcast_ref::<std::io::Error>().is_some_and(|io| io.kind() == std::io::ErrorKind::TimedOut) {
+            return true;
+        }
+        source = e.source();
+    }
+    false
+}
+
+/// 응답 본문 전체 수집 (청크 간 유휴 타임아웃 초과 시 504)
+async fn collect_body(incoming: hyper::body::Incoming, idle_timeout: Duration) -> Result<Bytes, StatusCode> {
+    let mut body = incoming;
+    let mut buf = BytesMut::new();
+    loop {
+        match tokio::time::timeout(idle_timeout, body.frame()).await {
+            Ok(Some(Ok(frame))) => {
+                if let Ok(data) = frame.into_data() {
+                    buf.extend_from_slice(&data);
+                }
+            }
+            Ok(Some(Err(e))) => {
+                tracing::error!(error = %e, "업스트림 응답 본문 읽기 오류");
+                return Err(StatusCode::BAD_GATEWAY);
+            }
+            Ok(None) => return Ok(buf.freeze()),
+            Err(_) => {
+                tracing::error!(idle_secs = idle_timeout.as_secs(), "업스트림 응답 본문 유휴 타임아웃");
+                return Err(StatusCode::GATEWAY_TIMEOUT);
+            }
+        }
+    }
+}
+
+/// 스트림 유휴 타임아웃 에러
+fn idle_timeout_error(idle_timeout: Duration) -> ApiError {
+    ApiError::new(504, format!("업스트림 스트림이 {}초 동안 응답하지 않습니다", idle_timeout.as_secs()))
+}
+
+/// 패스스루 응답 본문에 유휴 타임아웃 적용
+/// (SSE면 타임아웃/읽기 오류 시 Anthropic `error` 이벤트를 보내고 종료, 그 외는 본문 오류로 종료)
+fn idle_guarded_body(incoming: hyper::body::Incoming, idle_timeout: Duration, is_sse: bool) -> Body {
+    let stream = async_stream::stream! {
+        let mut body = incoming;
+        loop {
+            match tokio::time::timeout(idle_timeout, body.frame()).await {
+                Ok(Some(Ok(frame))) => {
+                    if let Ok(data) = frame.into_data() {
+                        yield Ok::<Bytes, std::io::Error>(data);
+                    }
+                }
+                Ok(Some(Err(e))) => {
+                    tracing::error!(error = %e, "업스트림 스트림 읽기 오류");
+                    if is_sse {
+                        let err = ApiError::new(502, format!("업스트림 스트림이 중단되었습니다: {e}"));
+                        yield Ok(Bytes::from(sse_event("error", &err.to_json())));
+                    } else {
+                        yield Err(std::io::Error::other(e));
+                    }
+                    break;
+                }
+                Ok(None) => break,
+                Err(_) => {
+                    tracing::error!(idle_secs = idle_timeout.as_secs(), "업스트림 스트림 유휴 타임아웃");
+                    let err = idle_timeout_error(idle_timeout);
+                    if is_sse {
+                        yield Ok(Bytes::from(sse_event("error", &err.to_json())));
+                    } else {
+                        yield Err(std::io::Error::new(std::io::ErrorKind::TimedOut, err.message));
+                    }
+                    break;
+                }
+            }
+        }
+    };
+    Body::from_stream(stream)
+}
+
+/// 업스트림으로 요청 포워딩 (라우트 또는 default의 retry 정책으로 일시적 실패 재시도)
+///
+/// 재시도는 상태 코드를 받은 시점에 판단하므로 클라이언트로 응답 바이트를 보내기 전에만 일어난다.
+async fn forward(
+    state: &AppState,
+    parts: &axum::http::request::Parts,
+    body_bytes: Bytes,
+    route: Option<&RouteConfig>,
+    auth_value_override: Option<&str>,
+) -> Result<Response<Body>, StatusCode> {
+    let policy = match route {
+        Some(r) => r.retry.as_ref(),
+        None => state.config.default.retry.as_ref(),
+    };
+    let Some(policy) = policy.filter(|p| p.max_attempts > 1) else {
+        return forward_authed(state, parts, body_bytes, route, auth_value_override).await;
+    };
+
+    let mut attempt = 1;
+    loop {
+        let result = forward_authed(state, parts, body_bytes.clone(), route, auth_value_override).await;
+        let Some(failure) = classify_result(&result) else {
+            return result;
+        };
+        if attempt >= policy.max_attempts || !policy.retries(failure) {
+            return result;
+        }
+        let retry_after = result.as_ref().ok().and_then(parse_retry_after);
+        let Some(delay) = policy.delay(attempt, retry_after, jitter_random()) else {
+            tracing::warn!(attempt, retry_after, "Retry-After가 재시도 상한보다 길어 재시도하지 않음");
+            return result;
+        };
+        tracing::warn!(attempt, failure = ?failure, delay_ms = delay.as_millis() as u64, "일시적 업스트림 실패, 재시도");
+        // 재시도 전에 실패한 응답 해제
+        drop(result);
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+}
+
+/// 인증 처리 후 포워딩 (토큰 발급 라우트는 토큰 주입 + 401 시 강제 갱신 후 1회 재시도)
+async fn forward_authed(
+    state: &AppState,
+    parts: &axum::http::request::Parts,
+    body_bytes: Bytes,
+    route: Option<&RouteConfig>,
+    auth_value_override: Option<&str>,
+) -> Result<Response<Body>, StatusCode> {
+    let Some(r) = route.filter(|r| r.upstream.auth.uses_token_manager()) else {
+        return forward_once(state, parts, body_bytes, route, auth_value_override).await;
+    };
+    let auth = &r.upstream.auth;
+
+    let token_timeout = state.config.timeouts_for(route).first_byte();
+    let token = oauth_header_value(state, auth, token_timeout).await?;
+    let resp = forward_once(state, parts, body_bytes.clone(), route, Some(&token)).await?;
+    if resp.status() != StatusCode::UNAUTHORIZED {
+        return Ok(resp);
+    }
+
+    tracing::warn!("업스트림 401, 액세스 토큰 강제 갱신 후 재시도");
+    state.token_manager.invalidate(auth).await;
+    let token = oauth_header_value(state, auth, token_timeout).await?;
+    forward_once(state, parts, body_bytes, route, Some(&token)).await
+}
+
+/// 액세스 토큰 조회 (발급 실패는 500으로 처리: 업스트림 재시도/서킷 집계 없이 폴백 대상)
+/// (`timeout`은 라우트의 first_byte 타임아웃: 토큰 엔드포인트가 멈춰도 요청이 무한정 대기하지 않음)
+async fn oauth_header_value(
+    state: &AppState,
+    auth: &crate::config::AuthConfig,
+    timeout: Duration,
+) -> Result<String, StatusCode> {
+    state.token_manager.header_value(auth, timeout).await.map_err(|e| {
+        tracing::error!(error = %e, "액세스 토큰 발급 실패");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })
+}
+
+/// 업스트림으로 요청 1회 포워딩
 /// - route가 Some이고 transformer가 있으면 프로토콜 변환
 /// - route가 Some이고 transformer가 없으면 라우팅만 (인증 헤더 교체)
 /// - route가 None이면 기본 Anthropic API로 패스스루
-/// - auth_value_override가 Some이면 풀에서 선택된 키 값 사용
-async fn forward(
+/// - auth_value_override가 Some이면 풀에서 선택된 키(또는 OAuth 토큰) 값 사용
+async fn forward_once(
     state: &AppState,
     parts: &axum::http::request::Parts,
     body_bytes: Bytes,
@@ -342,8 +885,10 @@ async fn forward(
 ) -> Result<Response<Body>, StatusCode> {
     // 트랜스포머 결정
     let transformer_opt: Option<Arc<dyn Transformer>> = route
-        .and_then(|r| r.transformer.as_deref())
-        .and_then(transformer::create_transformer)
+        .and_then(|r| {
+            let name = r.transformer.as_deref()?;
+            transformer::create_transformer(name, &state.config.transformers, r)
+        })
         .map(Arc::from);
 
     // 트랜스포머가 있으면 변환 분기
@@ -357,11 +902,23 @@ async fn forward(
         None => &state.config.default.url,
     };
 
-    let path_and_query = parts
+    let mut path_and_query = parts
         .uri
         .path_and_query()
         .map(PathAndQuery::as_str)
-        .unwrap_or("/");
+        .unwrap_or("/")
+        .to_string();
+
+    // Vertex AI의 Anthropic 모델: rawPredict 경로 + 본문 보정
+    let vertex_route = route.and_then(|r| Some((r, r.upstream.vertex.as_ref()?)));
+    let body_bytes = match vertex_route {
+        Some((r, vertex)) if parts.uri.path() == "/v1/messages" => {
+            let (path, body) = vertex_anthropic_request(vertex, r.model_map.as_deref(), &body_bytes)?;
+            path_and_query = path;
+            body
+        }
+        _ => body_bytes,
+    };
     let uri_string = format!("{}{}", base_url, path_and_query);
 
     let mut builder = hyper::Request::builder()
@@ -380,8 +937,21 @@ async fn forward(
         builder = builder.header(key, value);
     }
 
-    // 라우팅 시 새 인증 헤더 추가
-    if let Some(r) = route {
+    // 라우팅 시 새 인증 헤더 추가 (SigV4는 본문 서명, 인증 미설정 라우트는 생략)
+    if let Some(r) = route.filter(|r| r.upstream.auth.is_sigv4()) {
+        // 복사된 content-type만 서명 대상에 포함
+        let signed: Vec<(&str, &str)> = parts
+            .headers
+            .get(hyper::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| ("content-type", v))
+            .into_iter()
+            .collect();
+        let signer = SigV4Signer::from_auth(&r.upstream.auth);
+        for (name, value) in signer.sign(parts.method.as_str(), &uri_string, &signed, &body_bytes) {
+            builder = builder.header(name, value);
+        }
+    } else if let Some(r) = route.filter(|r| r.upstream.auth.has_credentials()) {
         let value = auth_value_override.unwrap_or_else(|| r.upstream.auth.header_value());
         builder = builder.header(r.upstream.auth.header_name(), value);
     }
@@ -390,14 +960,17 @@ async fn forward(
         .body(Full::new(body_bytes))
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
-    let resp = state.client.request(req).await.map_err(|e| {
-        tracing::error!(error = %e, "업스트림 요청 실패");
-        StatusCode::BAD_GATEWAY
-    })?;
+    let timeouts = state.config.timeouts_for(route);
+    let resp = send_upstream(state, req, &timeouts).await?;
 
-    // hyper Incoming → axum Body 변환 (SSE 스트리밍 자동 지원)
+    // hyper Incoming → axum Body 변환 (SSE 스트리밍 자동 지원, 청크 간 유휴 타임아웃 적용)
     let (resp_parts, incoming) = resp.into_parts();
-    let body = Body::new(incoming);
+    let is_sse = resp_parts
+        .headers
+        .get(hyper::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("text/event-stream"));
+    let body = idle_guarded_body(incoming, timeouts.idle(), is_sse);
     Ok(Response::from_parts(resp_parts, body))
 }
 
@@ -420,17 +993,21 @@ async fn forward_with_transform(
         .model_map
         .as_deref()
         .unwrap_or(&original_model);
+    // 업스트림이 usage를 주지 않을 때 사용할 입력 토큰 추정치
+    let estimated_input_tokens = tokens::estimate_request_tokens(&body_json);
 
-    // 요청 변환
-    let transformed = transformer
-        .transform_request(body_json, route.model_map.as_deref(), is_stream)
-        .map_err(|e| {
+    // 요청 변환 (실패 사유를 Anthropic 에러 형식으로 클라이언트에 전달)
+    let transformed = match transformer.transform_request(body_json, route.model_map.as_deref(), is_stream) {
+        Ok(t) => t,
+        Err(e) => {
             tracing::error!(error = %e, "요청 변환 실패");
-            StatusCode::BAD_REQUEST
-        })?;
+            return Ok(error_response(StatusCode::BAD_REQUEST, "invalid_request_error", &e.0));
+        }
+    };
 
-    // URI 구축
-    let uri_string = format!("{}{}", route.upstream.url, transformed.path);
+    // URI 구축 (라우트의 경로 템플릿/쿼리 파라미터 적용)
+    let path = route.upstream.request_path(&transformed.path, upstream_model);
+    let uri_string = format!("{}{}", route.upstream.url, path);
 
     let mut builder = hyper::Request::builder()
         .method(parts.method.clone())
@@ -451,18 +1028,25 @@ async fn forward_with_transform(
         builder = builder.header(key, value);
     }
 
-    // 인증 헤더 추가 (풀 오버라이드 적용)
-    let auth_value = auth_value_override.unwrap_or_else(|| route.upstream.auth.header_value());
-    builder = builder.header(route.upstream.auth.header_name(), auth_value);
+    let req_body = serde_json::to_vec(&transformed.body)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    // 인증 헤더 추가 (SigV4는 본문 서명, 풀 오버라이드 적용, 인증 미설정 라우트는 생략)
+    if route.upstream.auth.is_sigv4() {
+        let signer = SigV4Signer::from_auth(&route.upstream.auth);
+        for (name, value) in signer.sign("POST", &uri_string, &[("content-type", "application/json")], &req_body) {
+            builder = builder.header(name, value);
+        }
+    } else if route.upstream.auth.has_credentials() {
+        let auth_value = auth_value_override.unwrap_or_else(|| route.upstream.auth.header_value());
+        builder = builder.header(route.upstream.auth.header_name(), auth_value);
+    }
 
     // 추가 헤더
     for (name, value) in &transformed.extra_headers {
         builder = builder.header(name.as_str(), value.as_str());
     }
 
-    let req_body = serde_json::to_vec(&transformed.body)
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-
     let req = builder
         .body(Full::new(Bytes::from(req_body)))
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
@@ -474,33 +1058,41 @@ async fn forward_with_transform(
         "변환된 요청 전송"
     );
 
-    let resp = state.client.request(req).await.map_err(|e| {
-        tracing::error!(error = %e, "업스트림 요청 실패");
-        StatusCode::BAD_GATEWAY
-    })?;
+    let timeouts = state.config.timeouts_for(Some(route));
+    let resp = send_upstream(state, req, &timeouts).await?;
 
     let (resp_parts, incoming) = resp.into_parts();
 
+    // 업스트림 에러: 제공자 에러 본문 → Anthropic 에러 형식 (스트리밍 요청도 동일)
+    if !resp_parts.status.is_success() {
+        let resp_bytes = collect_body(incoming, timeouts.idle()).await?;
+        let err = transformer.transform_error(resp_parts.status.as_u16(), &resp_bytes);
+        tracing::warn!(
+            status = resp_parts.status.as_u16(),
+            error_type = err.error_type,
+            message = %err.message,
+            "업스트림 에러 응답"
+        );
+        return Ok(upstream_error_response(&resp_parts.headers, &err));
+    }
+
     if !is_stream {
         // 비스트리밍: 전체 수집 → 변환 → JSON 반환
-        let resp_bytes = incoming
-            .collect()
-            .await
-            .map_err(|_| StatusCode::BAD_GATEWAY)?
-            .to_bytes();
+        let resp_bytes = collect_body(incoming, timeouts.idle()).await?;
 
         let resp_json: serde_json::Value =
             serde_json::from_slice(&resp_bytes).map_err(|e| {
                 tracing::error!(error = %e, body = %String::from_utf8_lossy(&resp_bytes), "응답 JSON 파싱 실패");
-                StatusCode::BAD_GATEWAY
+                StatusCode::INTERNAL_SERVER_ERROR
             })?;
 
-        let anthropic_resp = transformer
+        let mut anthropic_resp = transformer
             .transform_response(resp_json, &original_model)
             .map_err(|e| {
                 tracing::error!(error = %e, "응답 변환 실패");
-                StatusCode::BAD_GATEWAY
+                StatusCode::INTERNAL_SERVER_ERROR
             })?;
+        fill_estimated_usage(&mut anthropic_resp, estimated_input_tokens);
 
         let resp_body = serde_json::to_vec(&anthropic_resp)
             .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
@@ -515,16 +1107,17 @@ async fn forward_with_transform(
     }
 
     // 스트리밍: SSE 변환 스트림
-    let ctx = StreamContext {
-        model: original_model.clone(),
-        message_id: format!("msg_{}", Uuid::new_v4().simple()),
-        input_tokens: 0,
-        output_tokens: 0,
-        block_index: 0,
-        started: false,
-    };
-
-    let body = transform_sse_stream(incoming, transformer, ctx);
+    let message_id = format!("msg_{}", Uuid::new_v4().simple());
+    let mut ctx = StreamContext::new(&original_model, &message_id);
+    ctx.input_tokens = estimated_input_tokens;
+
+    let recovery = route.stream_recovery.then(|| StreamRecovery {
+        state: state.clone(),
+        parts: recovery_parts(parts),
+        body: body_bytes.clone(),
+        route: route.clone(),
+    });
+    let body = transform_sse_stream(incoming, transformer, ctx, timeouts.idle(), recovery);
 
     let mut response = Response::new(body);
     *response.status_mut() = resp_parts.status;
@@ -540,11 +1133,34 @@ async fn forward_with_transform(
     Ok(response)
 }
 
-/// 업스트림 SSE 스트림을 Anthropic SSE 형식으로 변환
+/// 업스트림이 usage를 0으로 보고한 비스트리밍 응답에 로컬 추정치 채움
+fn fill_estimated_usage(resp: &mut serde_json::Value, estimated_input_tokens: u32) {
+    if resp["usage"]["input_tokens"].as_u64().unwrap_or(0) == 0 {
+        resp["usage"]["input_tokens"] = estimated_input_tokens.into();
+    }
+    if resp["usage"]["output_tokens"].as_u64().unwrap_or(0) == 0 {
+        let output: u32 = resp["content"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|block| match block["type"].as_str() {
+                Some("text") => tokens::estimate_tokens(block["text"].as_str().unwrap_or("")),
+                Some("thinking") => tokens::estimate_tokens(block["thinking"].as_str().unwrap_or("")),
+                Some("tool_use") => tokens::estimate_tokens(&block["input"].to_string()),
+                _ => 0,
+            })
+            .sum();
+        resp["usage"]["output_tokens"] = output.into();
+    }
+}
+
+/// 업스트림 스트림(SSE 또는 NDJSON)을 Anthropic SSE 형식으로 변환
 fn transform_sse_stream(
     incoming: hyper::body::Incoming,
     transformer: Arc<dyn Transformer>,
     mut ctx: StreamContext,
+    idle_timeout: Duration,
+    recovery: Option<StreamRecovery>,
 ) -> Body {
     let stream = async_stream::stream! {
         // 1. 스트림 시작 이벤트 전송
@@ -552,65 +1168,111 @@ fn transform_sse_stream(
             yield Ok::<Bytes, std::io::Error>(Bytes::from(event));
         }
 
-        // 2. incoming → 바이트 청크 → 줄 단위 SSE 파싱
+        // 2. incoming → 바이트 청크 → 프레임 단위 파싱 (프레이밍은 트랜스포머가 결정)
+        let framing = transformer.stream_framing();
         let mut buf = BytesMut::new();
         let mut body = incoming;
 
+        // 스트림 중간 실패 (이어받기 판정용 실패 종류 + 이어받기 불가 시 보낼 에러)
+        let mut interrupted = None;
         loop {
-            match body.frame().await {
+            let Ok(next) = tokio::time::timeout(idle_timeout, body.frame()).await else {
+                tracing::error!(idle_secs = idle_timeout.as_secs(), "업스트림 스트림 유휴 타임아웃");
+                interrupted = Some((UpstreamFailure::Timeout, idle_timeout_error(idle_timeout)));
+                break;
+            };
+            match next {
                 Some(Ok(frame)) => {
                     if let Ok(data) = frame.into_data() {
                         buf.extend_from_slice(&data);
 
-                        // 줄 단위 처리
-                        while let Some(pos) = buf.iter().position(|&b| b == b'\n') {
-                            let line = buf.split_to(pos + 1);
-                            let line_str = String::from_utf8_lossy(&line);
-                            let trimmed = line_str.trim();
-
-                            // SSE "data: " 접두사 처리
-                            if let Some(payload) = trimmed.strip_prefix("data: ").or_else(|| trimmed.strip_prefix("data:")) {
-                                let payload = payload.trim();
-                                if payload.is_empty() {
-                                    continue;
-                                }
-
-                                match transformer.transform_stream_chunk(payload, &mut ctx) {
-                                    Ok(events) => {
-                                        for event in events {
-                                            yield Ok(Bytes::from(event));
-                                        }
-                                    }
-                                    Err(e) => {
-                                        tracing::warn!(error = %e, "SSE 청크 변환 실패, 건너뜀");
+                        for payload in framing.drain(&mut buf, false) {
+                            match transformer.transform_stream_chunk(&payload, &mut ctx) {
+                                Ok(events) => {
+                                    for event in events {
+                                        yield Ok(Bytes::from(event));
                                     }
                                 }
+                                Err(e) => {
+                                    tracing::warn!(error = %e, "스트림 청크 변환 실패, 건너뜀");
+                                }
                             }
                         }
                     }
                 }
                 Some(Err(e)) => {
-                    tracing::error!(error = %e, "SSE 스트림 읽기 오류");
+                    tracing::error!(error = %e, "업스트림 스트림 읽기 오류");
+                    let err = ApiError::new(502, format!("업스트림 스트림이 중단되었습니다: {e}"));
+                    interrupted = Some((UpstreamFailure::Connect, err));
                     break;
                 }
                 None => break,
             }
         }
 
-        // 남은 버퍼 처리
-        if !buf.is_empty() {
-            let line_str = String::from_utf8_lossy(&buf);
-            let trimmed = line_str.trim();
-            if let Some(payload) = trimmed.strip_prefix("data: ").or_else(|| trimmed.strip_prefix("data:")) {
-                let payload = payload.trim();
-                if !payload.is_empty() {
-                    if let Ok(events) = transformer.transform_stream_chunk(payload, &mut ctx) {
+        match interrupted {
+            // 남은 버퍼 처리
+            None => {
+                for payload in framing.drain(&mut buf, true) {
+                    if let Ok(events) = transformer.transform_stream_chunk(&payload, &mut ctx) {
                         for event in events {
                             yield Ok(Bytes::from(event));
                         }
                     }
                 }
             }
+            // 폴백 대상으로 이어받기, 불가능하면 error 이벤트로 종료
+            Some((failure, err)) => {
+                let resumed = match &recovery {
+                    Some(recovery) => resume_stream(recovery, &ctx, failure).await,
+                    None => None,
+                };
+                match resumed {
+                    Some(resp) => {
+                        let base_output_tokens = ctx.reported_output_tokens();
+                        let mut buf = BytesMut::new();
+                        let mut body = resp.into_body();
+                        loop {
+                            let Ok(next) = tokio::time::timeout(idle_timeout, body.frame()).await else {
+                                tracing::error!(idle_secs = idle_timeout.as_secs(), "이어받기 스트림 유휴 타임아웃");
+                                for event in ctx.error(&idle_timeout_error(idle_timeout)) {
+                                    yield Ok(Bytes::from(event));
+                                }
+                                break;
+                            };
+                            let data = match next {
+                                Some(Ok(frame)) => match frame.into_data() {
+                                    Ok(data) => data,
+                                    Err(_) => continue,
+                                },
+                                Some(Err(e)) => {
+                                    tracing::error!(error = %e, "이어받기 스트림 읽기 오류");
+                                    let err = ApiError::new(502, format!("이어받기 스트림이 중단되었습니다: {e}"));
+                                    for event in ctx.error(&err) {
+                                        yield Ok(Bytes::from(event));
+                                    }
+                                    break;
+                                }
+                                None => break,
+                            };
+                            buf.extend_from_slice(&data);
+                            for payload in transformer::StreamFraming::Sse.drain(&mut buf, false) {
+                                let Ok(event) = serde_json::from_str::<serde_json::Value>(&payload) else {
+                                    continue;
+                                };
+                                for event in ctx.splice_event(&event, base_output_tokens) {
+                                    yield Ok(Bytes::from(event));
+                                }
+                            }
+                        }
+                    }
+                    None => {
+                        for event in ctx.error(&err) {
+                            yield Ok(Bytes::from(event));
+                        }
+                    }
+                }
+            }
         }
 
         // 3. 스트림 종료 이벤트 전송
@@ -621,3 +1283,598 @@ fn transform_sse_stream(
 
     Body::from_stream(stream)
 }
+
+/// 스트림 이어받기에 필요한 원본 요청 (스트림이 요청 처리보다 오래 살아 있으므로 소유)
+struct StreamRecovery {
+    state: AppState,
+    parts: axum::http::request::Parts,
+    body: Bytes,
+    route: RouteConfig,
+}
+
+/// 이어받기 요청용 헤더 복사본 (이어받기 응답을 SSE로 직접 파싱하므로 압축 요청 제외)
+fn recovery_parts(parts: &axum::http::request::Parts) -> axum::http::request::Parts {
+    let (mut cloned, _) = Request::new(()).into_parts();
+    cloned.method = parts.method.clone();
+    cloned.uri = parts.uri.clone();
+    cloned.version = parts.version;
+    cloned.headers = parts.headers.clone();
+    cloned.headers.remove(hyper::header::ACCEPT_ENCODING);
+    cloned
+}
+
+/// 스트림 중간 실패 시 라우트 폴백 대상으로 이어받기 요청 (성공 응답일 때만 반환)
+async fn resume_stream(
+    recovery: &StreamRecovery,
+    ctx: &StreamContext,
+    failure: UpstreamFailure,
+) -> Option<Response<Body>> {
+    let request: serde_json::Value = serde_json::from_slice(&recovery.body).ok()?;
+    let Some(continuation) = ctx.continuation_request(&request) else {
+        tracing::warn!("tool_use 블록 전송 후 중단되어 스트림 이어받기 불가");
+        return None;
+    };
+    let bytes = Bytes::from(serde_json::to_vec(&continuation).ok()?);
+    tracing::info!(failure = ?failure, prefill_chars = ctx.partial_text.len(), "스트림 중단, 폴백 대상으로 이어받기");
+
+    let result = run_fallback(
+        &recovery.state,
+        &recovery.parts,
+        &bytes,
+        &recovery.route,
+        failure,
+        Err(StatusCode::BAD_GATEWAY),
+    )
+    .await;
+    match result {
+        Ok(resp) if resp.status().is_success() => Some(resp),
+        Ok(resp) => {
+            tracing::warn!(status = resp.status().as_u16(), "스트림 이어받기 실패");
+            None
+        }
+        Err(status) => {
+            tracing::warn!(status = status.as_u16(), "스트림 이어받기 실패");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::admin::AdminState;
+    use crate::auth::oauth::TokenManager;
+    use crate::breaker::CircuitBreaker;
+    use crate::config::Config;
+    use crate::pool::{AccountSemaphore, KeyPool, UpstreamPool};
+    use crate::HttpClient;
+    use hyper_util::client::legacy::Client;
+    use hyper_util::rt::TokioExecutor;
+    use serde_json::{json, Value};
+    use std::collections::HashMap;
+    use std::future::Future;
+    use std::sync::Mutex;
+
+    /// 연결이 거부되는 주소 (호출되면 안 되는 default.url 등)
+    const UNREACHABLE: &str = "http://127.0.0.1:9";
+
+    /// 테스트 업스트림이 받은 요청
+    struct Hit {
+        path: String,
+        headers: axum::http::HeaderMap,
+        body: Value,
+    }
+
+    /// 테스트 업스트림 (주소와 받은 요청 기록)
+    struct Stub {
+        url: String,
+        hits: Arc<Mutex<Vec<Hit>>>,
+    }
+
+    impl Stub {
+        fn count(&self) -> usize {
+            self.hits.lock().unwrap().len()
+        }
+    }
+
+    /// 요청을 기록하고 `respond(요청 순번)`의 응답을 돌려주는 로컬 업스트림
+    async fn spawn_stub<F, Fut>(respond: F) -> Stub
+    where
+        F: Fn(usize) -> Fut + Clone + Send + Sync + 'static,
+        Fut: Future<Output = Response<Body>> + Send,
+    {
+        let hits = Arc::new(Mutex::new(Vec::new()));
+        let recorded = hits.clone();
+        let app = axum::Router::new().fallback(move |req: Request<Body>| {
+            let (hits, respond) = (recorded.clone(), respond.clone());
+            async move {
+                let (parts, body) = req.into_parts();
+                let bytes = axum::body::to_bytes(body, usize::MAX).await.unwrap();
+                let n = {
+                    let mut hits = hits.lock().unwrap();
+                    hits.push(Hit {
+                        path: parts.uri.to_string(),
+                        headers: parts.headers,
+                        body: serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+                    });
+                    hits.len() - 1
+                };
+                respond(n).await
+            }
+        });
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        Stub { url: format!("http://{addr}"), hits }
+    }
+
+    /// JSON 본문 응답
+    fn json_response(status: u16, body: Value) -> Response<Body> {
+        Response::builder()
+            .status(status)
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    }
+
+    /// Anthropic 형식 메시지 응답
+    fn message_response(text: &str) -> Response<Body> {
+        json_response(200, json!({
+            "id": "msg_1",
+            "type": "message",
+            "role": "assistant",
+            "model": "glm",
+            "content": [{"type": "text", "text": text}],
+            "stop_reason": "end_turn",
+            "usage": {"input_tokens": 1, "output_tokens": 1}
+        }))
+    }
+
+    fn make_client() -> HttpClient {
+        let _ = rustls::crypto::ring::default_provider().install_default();
+        let tls = rustls::ClientConfig::builder()
+            .with_root_certificates(rustls::RootCertStore::empty())
+            .with_no_client_auth();
+        let https = hyper_rustls::HttpsConnectorBuilder::new()
+            .with_tls_config(tls)
+            .https_or_http()
+            .enable_http1()
+            .build();
+        Client::builder(TokioExecutor::new()).build(https)
+    }
+
+    /// `routes` YAML로 AppState 생성 (`Config::load`로 읽어 검증/매처 초기화를 거침)
+    fn make_state(default_url: &str, routes: &str) -> AppState {
+        let yaml = format!("server:\n  host: \"127.0.0.1\"\n  port: 0\ndefault:\n  url: \"{default_url}\"\nroutes:{routes}");
+        let path = std::env::temp_dir().join(format!("_summon_proxy_test_{}.yaml", Uuid::new_v4()));
+        std::fs::write(&path, yaml).unwrap();
+        let config = Config::load(path.to_str().unwrap());
+        let _ = std::fs::remove_file(&path);
+        let config = config.unwrap();
+        let client = make_client();
+        AppState {
+            key_pool: Arc::new(KeyPool::from_config(&config)),
+            account_semaphore: Arc::new(AccountSemaphore::from_config(&config)),
+            token_manager: Arc::new(TokenManager::new(client.clone())),
+            upstream_pool: Arc::new(UpstreamPool::from_config(&config)),
+            breaker: Arc::new(CircuitBreaker::from_config(&config)),
+            clients: Arc::new(HashMap::new()),
+            admin: Arc::new(AdminState::new()),
+            client,
+            config,
+        }
+    }
+
+    /// POST /v1/messages 요청
+    fn messages_request(model: &str, stream: bool) -> Request<Body> {
+        let body = json!({
+            "model": model,
+            "max_tokens": 16,
+            "stream": stream,
+            "messages": [{"role": "user", "content": "hi"}]
+        });
+        Request::builder()
+            .method(Method::POST)
+            .uri("/v1/messages")
+            .header("content-type", "application/json")
+            .header("x-api-key", "client-key")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    }
+
+    /// 프록시 핸들러로 요청 처리
+    async fn send(state: &AppState, req: Request<Body>) -> Response<Body> {
+        proxy_handler(State(SharedState::new(state.clone(), "")), req).await
+    }
+
+    async fn body_json(resp: Response<Body>) -> Value {
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    /// 폴백 없는 라우트: 키 풀 소진(429)도 Anthropic 에러 형식으로 응답
+    #[tokio::test]
+    async fn test_exhausted_key_pool_returns_anthropic_error() {
+        let upstream = spawn_stub(|_| async { json_response(429, json!({"error": "busy"})) }).await;
+        let routes = format!(
+            r#"
+  - match: "glm"
+    upstream:
+      url: "{}"
+      auth: {{ value: "k1", pool: ["k2"] }}
+    fallback: false
+"#,
+            upstream.url
+        );
+        let state = make_state(UNREACHABLE, &routes);
+
+        let resp = send(&state, messages_request("glm", false)).await;
+        assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(resp.headers()["content-type"], "application/json");
+        let body = body_json(resp).await;
+        assert_eq!(body["type"], "error");
+        assert_eq!(body["error"]["type"], "rate_limit_error");
+        // 두 키를 한 번씩 시도
+        let hits = upstream.hits.lock().unwrap();
+        let mut keys: Vec<&str> = hits.iter().map(|h| h.headers["authorization"].to_str().unwrap()).collect();
+        keys.sort();
+        assert_eq!(keys, ["k1", "k2"]);
+        assert!(hits.iter().all(|h| h.path == "/v1/messages" && h.body["model"] == "glm"));
+    }
+
+    /// 폴백 없는 라우트: 서킷이 열리면 업스트림을 건너뛰고 Anthropic 형식 503
+    #[tokio::test]
+    async fn test_open_circuit_returns_anthropic_error() {
+        let upstream = spawn_stub(|_| async { json_response(500, json!({"error": "down"})) }).await;
+        let routes = format!(
+            r#"
+  - match: "glm"
+    upstream:
+      url: "{}"
+    fallback: false
+    circuit_breaker: {{ failure_threshold: 1 }}
+"#,
+            upstream.url
+        );
+        let state = make_state(UNREACHABLE, &routes);
+
+        let resp = send(&state, messages_request("glm", false)).await;
+        assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+        let resp = send(&state, messages_request("glm", false)).await;
+        assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let body = body_json(resp).await;
+        assert_eq!(body["type"], "error");
+        assert_eq!(body["error"]["type"], "overloaded_error");
+        assert_eq!(upstream.count(), 1);
+    }
+
+    /// 변환 라우트의 429도 Retry-After가 남아 재시도 대기/키 쿨다운에 쓰임
+    #[test]
+    fn test_upstream_error_response_keeps_retry_after() {
+        let mut headers = axum::http::HeaderMap::new();
+        headers.insert("retry-after", "7".parse().unwrap());
+        headers.insert("x-ratelimit-remaining-requests", "0".parse().unwrap());
+        headers.insert("set-cookie", "session=1".parse().unwrap());
+        headers.insert("content-length", "42".parse().unwrap());
+
+        let resp = upstream_error_response(&headers, &ApiError::new(429, "Rate limit reached"));
+        assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(parse_retry_after(&resp), Some(7));
+        assert_eq!(resp.headers()["x-ratelimit-remaining-requests"], "0");
+        assert!(resp.headers().get("set-cookie").is_none());
+        assert!(resp.headers().get("content-length").is_none());
+        assert_eq!(resp.headers()["content-type"], "application/json");
+    }
+
+    /// 폴백 체인: 순서대로 시도, 트리거가 맞지 않는 대상은 건너뜀, 대상마다 자체 인증/트랜스포머/model_map
+    #[tokio::test]
+    async fn test_fallback_chain_walks_targets_in_order() {
+        let primary = spawn_stub(|_| async { json_response(429, json!({"error": "busy"})) }).await;
+        let server_only = spawn_stub(|_| async { message_response("5xx only") }).await;
+        let openai = spawn_stub(|_| async { json_response(500, json!({"error": {"message": "down"}})) }).await;
+        let last = spawn_stub(|_| async { message_response("from last") }).await;
+        let routes = format!(
+            r#"
+  - match: "glm"
+    upstream:
+      url: "{}"
+    fallback:
+      - upstream: {{ url: "{}" }}
+        triggers: ["5xx"]
+      - upstream:
+          url: "{}"
+          auth: {{ value: "Bearer or-key" }}
+        transformer: "openai"
+        model_map: "z-ai/glm"
+      - upstream:
+          url: "{}"
+          auth: {{ header: "x-api-key", value: "last-key" }}
+        model_map: "glm-backup"
+        triggers: ["5xx"]
+"#,
+            primary.url, server_only.url, openai.url, last.url
+        );
+        let state = make_state(UNREACHABLE, &routes);
+
+        let resp = send(&state, messages_request("glm", false)).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(body_json(resp).await["content"][0]["text"], "from last");
+
+        // 429에는 5xx 전용 대상을 건너뜀
+        assert_eq!(primary.count(), 1);
+        assert_eq!(server_only.count(), 0);
+
+        let hits = openai.hits.lock().unwrap();
+        assert_eq!(hits.len(), 1);
+        assert!(hits[0].path.ends_with("/chat/completions"));
+        assert_eq!(hits[0].headers["authorization"], "Bearer or-key");
+        assert_eq!(hits[0].body["model"], "z-ai/glm");
+        assert!(hits[0].body["messages"].is_array());
+
+        let hits = last.hits.lock().unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].path, "/v1/messages");
+        assert_eq!(hits[0].headers["x-api-key"], "last-key");
+        assert!(hits[0].headers.get("authorization").is_none());
+        assert_eq!(hits[0].body["model"], "glm-backup");
+    }
+
+    /// 폴백 체인을 모두 실패하면 마지막으로 시도한 대상의 응답 반환
+    #[tokio::test]
+    async fn test_fallback_chain_returns_last_failure() {
+        let primary = spawn_stub(|_| async { json_response(503, json!({"error": "primary"})) }).await;
+        let first = spawn_stub(|_| async { json_response(502, json!({"error": "first"})) }).await;
+        let second = spawn_stub(|_| async { json_response(500, json!({"error": "second"})) }).await;
+        let rate_limited_only = spawn_stub(|_| async { message_response("429 only") }).await;
+        let routes = format!(
+            r#"
+  - match: "glm"
+    upstream:
+      url: "{}"
+    fallback:
+      - upstream: {{ url: "{}" }}
+      - upstream: {{ url: "{}" }}
+      - upstream: {{ url: "{}" }}
+        triggers: ["429"]
+"#,
+            primary.url, first.url, second.url, rate_limited_only.url
+        );
+        let state = make_state(UNREACHABLE, &routes);
+
+        let resp = send(&state, messages_request("glm", false)).await;
+        assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(body_json(resp).await["error"], "second");
+        assert_eq!((primary.count(), first.count(), second.count()), (1, 1, 1));
+        assert_eq!(rate_limited_only.count(), 0);
+    }
+
+    /// `retry` 설정 라우트 (폴백 없음, 백오프 1ms)
+    fn retry_route(url: &str, retry: &str) -> String {
+        format!(
+            r#"
+  - match: "glm"
+    upstream:
+      url: "{url}"
+    fallback: false
+    retry: {{ initial_backoff_ms: 1, max_backoff_ms: 1, {retry} }}
+"#
+        )
+    }
+
+    /// 529 뒤 200: 같은 업스트림에 재시도해 성공 응답 반환
+    #[tokio::test]
+    async fn test_retry_recovers_after_overloaded() {
+        let upstream = spawn_stub(|n| async move {
+            match n {
+                0 => json_response(529, json!({"error": "overloaded"})),
+                _ => message_response("ok"),
+            }
+        })
+        .await;
+        let state = make_state(UNREACHABLE, &retry_route(&upstream.url, "max_attempts: 3"));
+
+        let resp = send(&state, messages_request("glm", false)).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(body_json(resp).await["content"][0]["text"], "ok");
+        assert_eq!(upstream.count(), 2);
+    }
+
+    /// 최대 시도 횟수를 넘기지 않고, 설정한 상태 코드만 재시도
+    #[tokio::test]
+    async fn test_retry_attempts_and_statuses() {
+        let overloaded = spawn_stub(|_| async { json_response(503, json!({"error": "down"})) }).await;
+        let state = make_state(UNREACHABLE, &retry_route(&overloaded.url, "max_attempts: 3"));
+        let resp = send(&state, messages_request("glm", false)).await;
+        assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body_json(resp).await["error"], "down");
+        assert_eq!(overloaded.count(), 3);
+
+        let server_error = spawn_stub(|_| async { json_response(500, json!({"error": "bug"})) }).await;
+        let state = make_state(UNREACHABLE, &retry_route(&server_error.url, "max_attempts: 3, statuses: [529]"));
+        let resp = send(&state, messages_request("glm", false)).await;
+        assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(server_error.count(), 1);
+
+        // 429는 기본 재시도 대상이 아님 (키 풀/폴백이 처리)
+        let rate_limited = spawn_stub(|_| async { json_response(429, json!({"error": "busy"})) }).await;
+        let state = make_state(UNREACHABLE, &retry_route(&rate_limited.url, "max_attempts: 3"));
+        let resp = send(&state, messages_request("glm", false)).await;
+        assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(rate_limited.count(), 1);
+    }
+
+    /// Retry-After만큼 기다린 뒤 재시도, 상한보다 길면 재시도 없이 응답 반환
+    #[tokio::test]
+    async fn test_retry_honours_retry_after() {
+        let upstream = spawn_stub(|n| async move {
+            match n {
+                0 => {
+                    let mut resp = json_response(529, json!({"error": "overloaded"}));
+                    resp.headers_mut().insert("retry-after", "1".parse().unwrap());
+                    resp
+                }
+                _ => message_response("ok"),
+            }
+        })
+        .await;
+        let state = make_state(UNREACHABLE, &retry_route(&upstream.url, "max_attempts: 2"));
+        let started = Instant::now();
+        let resp = send(&state, messages_request("glm", false)).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert!(started.elapsed() >= Duration::from_secs(1));
+        assert_eq!(upstream.count(), 2);
+
+        let upstream = spawn_stub(|_| async {
+            let mut resp = json_response(529, json!({"error": "overloaded"}));
+            resp.headers_mut().insert("retry-after", "60".parse().unwrap());
+            resp
+        })
+        .await;
+        let state = make_state(UNREACHABLE, &retry_route(&upstream.url, "max_attempts: 3, max_retry_after_secs: 30"));
+        let resp = send(&state, messages_request("glm", false)).await;
+        assert_eq!(resp.status().as_u16(), 529);
+        assert_eq!(resp.headers()["retry-after"], "60");
+        assert_eq!(upstream.count(), 1);
+    }
+
+    /// 응답 바이트를 보내기 시작한 뒤 끊긴 스트림은 재시도하지 않고 `error` 이벤트로 종료
+    #[tokio::test]
+    async fn test_no_retry_after_stream_started() {
+        let upstream = spawn_stub(|_| async {
+            let chunks = async_stream::stream! {
+                yield Ok::<Bytes, std::io::Error>(Bytes::from("event: message_start\ndata: {\"type\":\"message_start\"}\n\n"));
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                yield Err(std::io::Error::other("connection reset"));
+            };
+            Response::builder()
+                .header("content-type", "text/event-stream")
+                .body(Body::from_stream(chunks))
+                .unwrap()
+        })
+        .await;
+        let state = make_state(UNREACHABLE, &retry_route(&upstream.url, "max_attempts: 3, statuses: [502, 529]"));
+
+        let resp = send(&state, messages_request("glm", true)).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        let text = String::from_utf8_lossy(&bytes);
+        assert!(text.starts_with("event: message_start"));
+        assert!(text.contains("event: error"));
+        assert_eq!(upstream.count(), 1);
+    }
+
+    /// 응답 헤더를 보내지 않고 멈춘 업스트림
+    async fn stalled_response() -> Response<Body> {
+        tokio::time::sleep(Duration::from_secs(30)).await;
+        message_response("too late")
+    }
+
+    /// 첫 SSE 이벤트 뒤 멈춘 스트림
+    fn stalled_stream(first_event: &'static str) -> Response<Body> {
+        let chunks = async_stream::stream! {
+            yield Ok::<Bytes, std::io::Error>(Bytes::from(first_event));
+            tokio::time::sleep(Duration::from_secs(30)).await;
+        };
+        Response::builder()
+            .header("content-type", "text/event-stream")
+            .body(Body::from_stream(chunks))
+            .unwrap()
+    }
+
+    /// 첫 바이트 타임아웃: 폴백이 없으면 Anthropic 형식 504
+    #[tokio::test]
+    async fn test_stalled_upstream_returns_gateway_timeout() {
+        let upstream = spawn_stub(|_| stalled_response()).await;
+        let routes = format!(
+            r#"
+  - match: "glm"
+    upstream:
+      url: "{}"
+    fallback: false
+    timeouts: {{ first_byte_secs: 1 }}
+"#,
+            upstream.url
+        );
+        let state = make_state(UNREACHABLE, &routes);
+
+        let started = Instant::now();
+        let resp = send(&state, messages_request("glm", false)).await;
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert_eq!(resp.status(), StatusCode::GATEWAY_TIMEOUT);
+        let body = body_json(resp).await;
+        assert_eq!(body["type"], "error");
+        assert_eq!(body["error"]["type"], "api_error");
+        assert_eq!(body["error"]["message"], "업스트림 응답 시간이 초과되었습니다");
+    }
+
+    /// 첫 바이트 타임아웃은 폴백 체인에서 `timeout` 실패로 판정
+    #[tokio::test]
+    async fn test_timeout_walks_fallback_chain() {
+        let primary = spawn_stub(|_| stalled_response()).await;
+        let server_only = spawn_stub(|_| async { message_response("5xx only") }).await;
+        let on_timeout = spawn_stub(|_| async { message_response("after timeout") }).await;
+        let routes = format!(
+            r#"
+  - match: "glm"
+    upstream:
+      url: "{}"
+    timeouts: {{ first_byte_secs: 1 }}
+    fallback:
+      - upstream: {{ url: "{}" }}
+        triggers: ["5xx"]
+      - upstream: {{ url: "{}" }}
+        triggers: ["timeout"]
+"#,
+            primary.url, server_only.url, on_timeout.url
+        );
+        let state = make_state(UNREACHABLE, &routes);
+
+        let resp = send(&state, messages_request("glm", false)).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(body_json(resp).await["content"][0]["text"], "after timeout");
+        assert_eq!((primary.count(), server_only.count(), on_timeout.count()), (1, 0, 1));
+    }
+
+    /// 시작된 스트림이 유휴 타임아웃을 넘기면 `error` 이벤트로 종료 (패스스루/변환 모두)
+    #[tokio::test]
+    async fn test_idle_stream_emits_error_event() {
+        let anthropic = spawn_stub(|_| async {
+            stalled_stream("event: message_start\ndata: {\"type\":\"message_start\"}\n\n")
+        })
+        .await;
+        let openai = spawn_stub(|_| async {
+            stalled_stream("data: {\"choices\":[{\"delta\":{\"content\":\"hi\"},\"finish_reason\":null}]}\n\n")
+        })
+        .await;
+        let routes = format!(
+            r#"
+  - match: "glm"
+    upstream:
+      url: "{}"
+    fallback: false
+    timeouts: {{ idle_secs: 1 }}
+  - match: "gpt"
+    upstream:
+      url: "{}"
+    transformer: "openai"
+    fallback: false
+    timeouts: {{ idle_secs: 1 }}
+"#,
+            anthropic.url, openai.url
+        );
+        let state = make_state(UNREACHABLE, &routes);
+
+        for model in ["glm", "gpt"] {
+            let resp = send(&state, messages_request(model, true)).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+            let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+            let text = String::from_utf8_lossy(&bytes);
+            assert!(text.starts_with("event: message_start"), "{model}: {text}");
+            let error = text.split("event: error\ndata: ").nth(1).unwrap_or_else(|| panic!("{model}: {text}"));
+            let error: Value = serde_json::from_str(error.trim()).unwrap();
+            assert_eq!(error["type"], "error");
+            assert!(error["error"]["message"].as_str().unwrap().contains("1초"), "{model}: {error}");
+        }
+    }
+}
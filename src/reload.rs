@@ -0,0 +1,134 @@
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
+
+use crate::config::Config;
+use crate::AppState;
+
+/// 설정 파일 변경 확인 주기
+const WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// 리로드 가능한 공유 상태
+///
+/// 요청마다 `current()`로 그 시점의 AppState를 복제해 사용하므로, 리로드는 새 요청부터
+/// 적용되고 진행 중인 요청(스트림 포함)은 기존 설정과 가드로 끝까지 처리된다.
+#[derive(Clone)]
+pub struct SharedState {
+    inner: Arc<RwLock<AppState>>,
+    config_path: Arc<str>,
+}
+
+impl SharedState {
+    pub fn new(state: AppState, config_path: &str) -> Self {
+        SharedState { inner: Arc::new(RwLock::new(state)), config_path: config_path.into() }
+    }
+
+    /// 현재 상태 스냅샷
+    pub fn current(&self) -> AppState {
+        match self.inner.read() {
+            Ok(state) => state.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+
+    fn replace(&self, state: AppState) {
+        match self.inner.write() {
+            Ok(mut current) => *current = state,
+            Err(poisoned) => *poisoned.into_inner() = state,
+        }
+    }
+}
+
+/// 설정 파일을 다시 읽어 상태 교체
+///
+/// 파일 읽기/파싱/검증과 상태 파일 저장은 블로킹 작업이므로 블로킹 스레드에서 수행한다.
+/// 새 설정이 로드/검증에 실패하면 기존 설정을 그대로 유지하고 오류를 반환한다.
+pub async fn reload(shared: &SharedState, trigger: &'static str) -> Result<(), String> {
+    let shared = shared.clone();
+    tokio::task::spawn_blocking(move || reload_blocking(&shared, trigger))
+        .await
+        .map_err(|e| format!("설정 리로드 작업 실패: {e}"))?
+}
+
+fn reload_blocking(shared: &SharedState, trigger: &str) -> Result<(), String> {
+    let config = match Config::load(&shared.config_path) {
+        Ok(config) => config,
+        Err(e) => {
+            tracing::error!(trigger, error = %e, "설정 리로드 실패, 기존 설정 유지");
+            return Err(e.to_string());
+        }
+    };
+
+    let current = shared.current();
+    if config.server.host != current.config.server.host || config.server.port != current.config.server.port {
+        tracing::warn!(
+            host = %config.server.host,
+            port = config.server.port,
+            "server 주소 변경은 재시작 후 적용됩니다"
+        );
+    }
+    let unchanged = config.unchanged_routes(&current.config).iter().filter(|r| r.is_some()).count();
+    let routes = config.routes.len();
+    shared.replace(current.reload(config));
+    tracing::info!(trigger, routes, unchanged, "설정 리로드 완료");
+    Ok(())
+}
+
+/// 설정 파일 변경 감시 + SIGHUP 리로드 태스크 시작
+pub fn spawn(shared: SharedState) {
+    let (hangup_tx, mut hangup_rx) = tokio::sync::mpsc::channel::<()>(1);
+    spawn_hangup_listener(hangup_tx);
+
+    tokio::spawn(async move {
+        let config_path = shared.config_path.clone();
+        let mut fingerprint = file_fingerprint(&config_path);
+        let mut interval = tokio::time::interval(WATCH_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            let trigger = tokio::select! {
+                _ = interval.tick() => {
+                    let current = file_fingerprint(&config_path);
+                    if current == fingerprint {
+                        continue;
+                    }
+                    fingerprint = current;
+                    "file_change"
+                }
+                Some(()) = hangup_rx.recv() => {
+                    fingerprint = file_fingerprint(&config_path);
+                    "sighup"
+                }
+            };
+            let _ = reload(&shared, trigger).await;
+        }
+    });
+}
+
+/// 변경 감지용 파일 수정 시각 + 크기 (읽을 수 없으면 None)
+fn file_fingerprint(path: &str) -> Option<(SystemTime, u64)> {
+    let meta = std::fs::metadata(path).ok()?;
+    Some((meta.modified().ok()?, meta.len()))
+}
+
+#[cfg(unix)]
+fn spawn_hangup_listener(tx: tokio::sync::mpsc::Sender<()>) {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut hangup = match signal(SignalKind::hangup()) {
+        Ok(hangup) => hangup,
+        Err(e) => {
+            tracing::warn!(error = %e, "SIGHUP 핸들러 등록 실패, 파일 변경 감시만 사용");
+            return;
+        }
+    };
+    tokio::spawn(async move {
+        while hangup.recv().await.is_some() {
+            tracing::info!("SIGHUP 수신, 설정 리로드");
+            // 이미 대기 중인 리로드가 있으면 합침
+            let _ = tx.try_send(());
+        }
+    });
+}
+
+/// SIGHUP이 없는 플랫폼은 파일 변경 감시만 사용
+#[cfg(not(unix))]
+fn spawn_hangup_listener(_tx: tokio::sync::mpsc::Sender<()>) {}
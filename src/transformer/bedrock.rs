@@ -0,0 +1,453 @@
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use super::openai::join_text;
+use super::{error, schema, ApiError, StreamContext, StreamFraming, TransformError, TransformedRequest, Transformer};
+use crate::util::uri_encode;
+
+/// AWS Bedrock Converse / ConverseStream 트랜스포머
+///
+/// 인증은 라우트의 `auth.type: "aws_sigv4"`로 요청마다 서명한다.
+pub struct BedrockTransformer;
+
+/// Converse stopReason → Anthropic stop_reason
+fn map_stop_reason(reason: &str) -> &str {
+    match reason {
+        "guardrail_intervened" | "content_filtered" => "refusal",
+        "model_context_window_exceeded" => "max_tokens",
+        other => other,
+    }
+}
+
+/// media_type → Converse 이미지 format
+fn image_format(media_type: &str) -> &str {
+    match media_type {
+        "image/jpeg" | "image/jpg" => "jpeg",
+        "image/gif" => "gif",
+        "image/webp" => "webp",
+        _ => "png",
+    }
+}
+
+/// Converse 문서 이름 제약 (영숫자, 공백, 하이픈, 괄호, 대괄호만 허용)
+fn document_name(title: Option<&str>) -> String {
+    let name: String = title
+        .unwrap_or("document")
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, ' ' | '-' | '(' | ')' | '[' | ']') {
+                c
+            } else {
+                ' '
+            }
+        })
+        .collect();
+    let name = name.trim();
+    if name.is_empty() { "document".to_string() } else { name.to_string() }
+}
+
+/// 텍스트 / image / document 블록 → Converse content 블록
+fn content_block(block: &Value) -> Result<Option<Value>, TransformError> {
+    let source = &block["source"];
+    let media_type = source["media_type"].as_str().unwrap_or("");
+    let converted = match (block["type"].as_str().unwrap_or("text"), source["type"].as_str()) {
+        ("text", _) => block["text"].as_str().map(|t| json!({"text": t})),
+        ("image", Some("base64")) => Some(json!({
+            "image": {"format": image_format(media_type), "source": {"bytes": source["data"]}}
+        })),
+        ("document", Some("base64")) if media_type == "application/pdf" => Some(json!({
+            "document": {
+                "format": "pdf",
+                "name": document_name(block["title"].as_str()),
+                "source": {"bytes": source["data"]},
+            }
+        })),
+        ("document", Some("text")) => Some(json!({"text": source["data"]})),
+        ("document", Some("content")) => Some(json!({"text": join_text(source["content"].as_array().map(Vec::as_slice).unwrap_or_default())})),
+        (kind @ ("image" | "document"), source_type) => {
+            return Err(TransformError(format!(
+                "Bedrock Converse는 {} 블록의 source 타입 {}을(를) 지원하지 않습니다",
+                kind,
+                source_type.unwrap_or("unknown"),
+            )))
+        }
+        _ => None,
+    };
+    Ok(converted)
+}
+
+/// tool_result → Converse toolResult
+fn tool_result(block: &Value) -> Result<Value, TransformError> {
+    let content = match &block["content"] {
+        Value::String(s) => vec![json!({"text": s})],
+        Value::Array(blocks) => {
+            let mut out = Vec::new();
+            for b in blocks {
+                out.extend(content_block(b)?);
+            }
+            out
+        }
+        _ => vec![],
+    };
+    // 빈 content는 ValidationException
+    let content = if content.is_empty() { vec![json!({"text": ""})] } else { content };
+    let status = if block["is_error"].as_bool() == Some(true) { "error" } else { "success" };
+    Ok(json!({"toolResult": {"toolUseId": block["tool_use_id"], "content": content, "status": status}}))
+}
+
+/// Anthropic 메시지 → Converse 메시지
+fn convert_message(msg: &Value) -> Result<Value, TransformError> {
+    let role = msg["role"].as_str().unwrap_or("user");
+    let mut content = Vec::new();
+    match &msg["content"] {
+        Value::String(s) => content.push(json!({"text": s})),
+        Value::Array(blocks) => {
+            for block in blocks {
+                match block["type"].as_str().unwrap_or("text") {
+                    "tool_use" => content.push(json!({
+                        "toolUse": {"toolUseId": block["id"], "name": block["name"], "input": block.get("input").unwrap_or(&json!({}))}
+                    })),
+                    "tool_result" => content.push(tool_result(block)?),
+                    // 서명이 있는 thinking만 재전송 가능 (Claude 확장 사고)
+                    "thinking" if block["signature"].as_str().is_some_and(|s| !s.is_empty()) => content.push(json!({
+                        "reasoningContent": {"reasoningText": {"text": block["thinking"], "signature": block["signature"]}}
+                    })),
+                    "redacted_thinking" => content.push(json!({
+                        "reasoningContent": {"redactedContent": block["data"]}
+                    })),
+                    _ => content.extend(content_block(block)?),
+                }
+            }
+        }
+        _ => {}
+    }
+    Ok(json!({"role": role, "content": content}))
+}
+
+/// Anthropic tools / tool_choice → Converse toolConfig
+fn tool_config(tools: &[Value], choice: Option<&Value>) -> Value {
+    let specs: Vec<Value> = tools
+        .iter()
+        .map(|t| {
+            json!({"toolSpec": {
+                "name": t["name"],
+                "description": t["description"].as_str().unwrap_or(""),
+                "inputSchema": {"json": t.get("input_schema").map(|s| schema::sanitize(s, &schema::OPENAI)).unwrap_or_else(|| json!({"type": "object"}))},
+            }})
+        })
+        .collect();
+    let mut config = json!({"tools": specs});
+    match choice.and_then(|c| c["type"].as_str()) {
+        Some("any") => config["toolChoice"] = json!({"any": {}}),
+        Some("tool") => config["toolChoice"] = json!({"tool": {"name": choice.unwrap()["name"]}}),
+        Some("auto") => config["toolChoice"] = json!({"auto": {}}),
+        _ => {}
+    }
+    config
+}
+
+/// Converse usage → (입력, 출력, 캐시 적중)
+fn usage_tokens(usage: &Value) -> (u64, u64, u64) {
+    (
+        usage["inputTokens"].as_u64().unwrap_or(0),
+        usage["outputTokens"].as_u64().unwrap_or(0),
+        usage["cacheReadInputTokens"].as_u64().unwrap_or(0),
+    )
+}
+
+impl Transformer for BedrockTransformer {
+    fn transform_request(
+        &self,
+        body: Value,
+        model_map: Option<&str>,
+        is_stream: bool,
+    ) -> Result<TransformedRequest, TransformError> {
+        let model = model_map.or_else(|| body["model"].as_str()).unwrap_or_default();
+
+        let mut messages = Vec::new();
+        for msg in body["messages"].as_array().into_iter().flatten() {
+            messages.push(convert_message(msg)?);
+        }
+        let mut req = json!({"messages": messages});
+
+        match &body["system"] {
+            Value::String(s) => req["system"] = json!([{"text": s}]),
+            Value::Array(blocks) => req["system"] = json!([{"text": join_text(blocks)}]),
+            _ => {}
+        }
+
+        let mut inference = serde_json::Map::new();
+        for (from, to) in [
+            ("max_tokens", "maxTokens"),
+            ("temperature", "temperature"),
+            ("top_p", "topP"),
+            ("stop_sequences", "stopSequences"),
+        ] {
+            if let Some(v) = body.get(from) {
+                inference.insert(to.into(), v.clone());
+            }
+        }
+        req["inferenceConfig"] = Value::Object(inference);
+
+        if let Some(tools) = body["tools"].as_array().filter(|t| !t.is_empty()) {
+            req["toolConfig"] = tool_config(tools, body.get("tool_choice"));
+        }
+
+        // 모델별 추가 필드 (Anthropic 모델만 thinking / top_k 지원)
+        let mut additional = serde_json::Map::new();
+        if model.contains("anthropic") || model.contains("claude") {
+            if let Some(thinking) = body.get("thinking") {
+                additional.insert("thinking".into(), thinking.clone());
+            }
+        }
+        if let Some(top_k) = body.get("top_k") {
+            additional.insert("top_k".into(), top_k.clone());
+        }
+        if !additional.is_empty() {
+            req["additionalModelRequestFields"] = Value::Object(additional);
+        }
+
+        let action = if is_stream { "converse-stream" } else { "converse" };
+        Ok(TransformedRequest {
+            path: format!("/model/{}/{action}", uri_encode(model)),
+            body: req,
+            extra_headers: vec![],
+        })
+    }
+
+    fn transform_response(&self, body: Value, model: &str) -> Result<Value, TransformError> {
+        let mut content = Vec::new();
+        for block in body["output"]["message"]["content"].as_array().into_iter().flatten() {
+            if let Some(text) = block["text"].as_str() {
+                content.push(json!({"type": "text", "text": text}));
+            } else if let Some(tool) = block.get("toolUse") {
+                content.push(json!({
+                    "type": "tool_use",
+                    "id": tool["toolUseId"],
+                    "name": tool["name"],
+                    "input": tool["input"],
+                }));
+            } else if let Some(reasoning) = block.get("reasoningContent") {
+                if let Some(text) = reasoning["reasoningText"]["text"].as_str() {
+                    content.push(json!({
+                        "type": "thinking",
+                        "thinking": text,
+                        "signature": reasoning["reasoningText"]["signature"].as_str().unwrap_or(""),
+                    }));
+                } else if let Some(data) = reasoning["redactedContent"].as_str() {
+                    content.push(json!({"type": "redacted_thinking", "data": data}));
+                }
+            }
+        }
+        if content.is_empty() {
+            content.push(json!({"type": "text", "text": ""}));
+        }
+
+        let (input_tokens, output_tokens, cached_tokens) = usage_tokens(&body["usage"]);
+        Ok(json!({
+            "id": format!("msg_{}", Uuid::new_v4().simple()),
+            "type": "message",
+            "role": "assistant",
+            "model": model,
+            "content": content,
+            "stop_reason": map_stop_reason(body["stopReason"].as_str().unwrap_or("end_turn")),
+            "stop_sequence": null,
+            "usage": {
+                "input_tokens": input_tokens,
+                "output_tokens": output_tokens,
+                "cache_read_input_tokens": cached_tokens,
+            }
+        }))
+    }
+
+    fn transform_error(&self, status: u16, body: &[u8]) -> ApiError {
+        error::from_bedrock(status, body)
+    }
+
+    /// event-stream 메시지 1개 (`eventstream::decode_message`가 만든 JSON)
+    fn transform_stream_chunk(
+        &self,
+        chunk: &str,
+        ctx: &mut StreamContext,
+    ) -> Result<Vec<String>, TransformError> {
+        let data: Value = serde_json::from_str(chunk)
+            .map_err(|e| TransformError(format!("event-stream 페이로드 파싱 실패: {e}")))?;
+
+        let mut events = Vec::new();
+        if data.get("exception").is_some() {
+            return Ok(ctx.error(&self.transform_error(500, chunk.as_bytes())));
+        }
+
+        if let Some(start) = data.get("contentBlockStart") {
+            if let Some(tool) = start["start"].get("toolUse") {
+                let index = start["contentBlockIndex"].as_u64().unwrap_or(0);
+                let id = tool["toolUseId"]
+                    .as_str()
+                    .map(String::from)
+                    .unwrap_or_else(|| format!("toolu_{}", Uuid::new_v4().simple()));
+                events.extend(ctx.open_tool_block(index, &id, tool["name"].as_str().unwrap_or("")));
+            }
+        } else if let Some(delta) = data.get("contentBlockDelta").map(|d| &d["delta"]) {
+            if let Some(text) = delta["text"].as_str() {
+                events.extend(ctx.text_delta(text));
+            } else if let Some(input) = delta["toolUse"]["input"].as_str() {
+                events.extend(ctx.tool_input_delta(input));
+            } else if let Some(reasoning) = delta.get("reasoningContent") {
+                if let Some(text) = reasoning["text"].as_str() {
+                    events.extend(ctx.thinking_delta(text));
+                }
+                if let Some(signature) = reasoning["signature"].as_str() {
+                    events.extend(ctx.signature_delta(signature));
+                }
+            }
+        } else if data.get("contentBlockStop").is_some() {
+            events.extend(ctx.close_block());
+        } else if let Some(stop) = data.get("messageStop") {
+            events.extend(ctx.stop(map_stop_reason(stop["stopReason"].as_str().unwrap_or("end_turn"))));
+        } else if let Some(metadata) = data.get("metadata") {
+            // metadata는 messageStop 뒤에 도착하는 마지막 이벤트
+            let (input, output, cached) = usage_tokens(&metadata["usage"]);
+            ctx.set_usage(Some(input), Some(output), Some(cached));
+            events.extend(ctx.finish(None));
+        }
+        Ok(events)
+    }
+
+    fn stream_framing(&self) -> StreamFraming {
+        StreamFraming::AwsEventStream
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transformer::eventstream::{decode_message, encode_message};
+    use bytes::BytesMut;
+
+    fn make_transformer() -> BedrockTransformer {
+        BedrockTransformer
+    }
+
+    /// 요청 변환: 경로 인코딩, system, inferenceConfig, toolConfig, thinking
+    #[test]
+    fn test_transform_request() {
+        let t = make_transformer();
+        let body = json!({
+            "model": "claude-sonnet-4",
+            "system": "간결하게",
+            "max_tokens": 1024,
+            "stop_sequences": ["END"],
+            "thinking": {"type": "enabled", "budget_tokens": 2000},
+            "tools": [{"name": "Bash", "description": "셸", "input_schema": {"type": "object"}}],
+            "tool_choice": {"type": "any"},
+            "messages": [
+                {"role": "user", "content": "ls 해줘"},
+                {"role": "assistant", "content": [
+                    {"type": "thinking", "thinking": "실행", "signature": "sig"},
+                    {"type": "tool_use", "id": "toolu_1", "name": "Bash", "input": {"command": "ls"}}
+                ]},
+                {"role": "user", "content": [
+                    {"type": "tool_result", "tool_use_id": "toolu_1", "content": "a.txt", "is_error": true}
+                ]}
+            ]
+        });
+        let result = t
+            .transform_request(body, Some("us.anthropic.claude-sonnet-4-20250514-v1:0"), true)
+            .unwrap();
+        let b = &result.body;
+
+        assert_eq!(
+            result.path,
+            "/model/us.anthropic.claude-sonnet-4-20250514-v1%3A0/converse-stream"
+        );
+        assert_eq!(b["system"][0]["text"], "간결하게");
+        assert_eq!(b["inferenceConfig"]["maxTokens"], 1024);
+        assert_eq!(b["inferenceConfig"]["stopSequences"][0], "END");
+        assert_eq!(b["toolConfig"]["tools"][0]["toolSpec"]["name"], "Bash");
+        assert!(b["toolConfig"]["toolChoice"].get("any").is_some());
+        assert_eq!(b["additionalModelRequestFields"]["thinking"]["budget_tokens"], 2000);
+
+        let msgs = b["messages"].as_array().unwrap();
+        assert_eq!(msgs[0]["content"][0]["text"], "ls 해줘");
+        assert_eq!(msgs[1]["content"][0]["reasoningContent"]["reasoningText"]["signature"], "sig");
+        assert_eq!(msgs[1]["content"][1]["toolUse"]["input"]["command"], "ls");
+        assert_eq!(msgs[2]["content"][0]["toolResult"]["status"], "error");
+    }
+
+    /// 비 Anthropic 모델에는 thinking을 보내지 않음
+    #[test]
+    fn test_transform_request_non_anthropic_model() {
+        let t = make_transformer();
+        let body = json!({
+            "model": "x",
+            "max_tokens": 10,
+            "thinking": {"type": "enabled", "budget_tokens": 2000},
+            "messages": [{"role": "user", "content": "hi"}]
+        });
+        let result = t.transform_request(body, Some("meta.llama3-70b-instruct-v1:0"), false).unwrap();
+        assert!(result.path.ends_with("/converse"));
+        assert!(result.body.get("additionalModelRequestFields").is_none());
+    }
+
+    /// 응답 변환: reasoning/텍스트/toolUse, usage
+    #[test]
+    fn test_transform_response() {
+        let t = make_transformer();
+        let body = json!({
+            "output": {"message": {"role": "assistant", "content": [
+                {"reasoningContent": {"reasoningText": {"text": "생각", "signature": "abc"}}},
+                {"text": "실행합니다"},
+                {"toolUse": {"toolUseId": "tooluse_1", "name": "Bash", "input": {"command": "ls"}}}
+            ]}},
+            "stopReason": "tool_use",
+            "usage": {"inputTokens": 20, "outputTokens": 10, "cacheReadInputTokens": 5}
+        });
+        let result = t.transform_response(body, "claude-sonnet-4").unwrap();
+        assert_eq!(result["content"][0]["signature"], "abc");
+        assert_eq!(result["content"][1]["text"], "실행합니다");
+        assert_eq!(result["content"][2]["id"], "tooluse_1");
+        assert_eq!(result["stop_reason"], "tool_use");
+        assert_eq!(result["usage"]["cache_read_input_tokens"], 5);
+    }
+
+    /// 바이너리 event-stream → Anthropic SSE
+    #[test]
+    fn test_stream_event_stream() {
+        let t = make_transformer();
+        let mut ctx = StreamContext::new("claude-sonnet-4", "msg_1");
+
+        let mut raw = Vec::new();
+        for (event, payload) in [
+            ("messageStart", r#"{"role":"assistant"}"#),
+            ("contentBlockDelta", r#"{"contentBlockIndex":0,"delta":{"text":"안녕"}}"#),
+            ("contentBlockStop", r#"{"contentBlockIndex":0}"#),
+            ("contentBlockStart", r#"{"contentBlockIndex":1,"start":{"toolUse":{"toolUseId":"tooluse_1","name":"Bash"}}}"#),
+            ("contentBlockDelta", r#"{"contentBlockIndex":1,"delta":{"toolUse":{"input":"{\"command\":\"ls\"}"}}}"#),
+            ("contentBlockStop", r#"{"contentBlockIndex":1}"#),
+            ("messageStop", r#"{"stopReason":"tool_use"}"#),
+            ("metadata", r#"{"usage":{"inputTokens":7,"outputTokens":3}}"#),
+        ] {
+            raw.extend(encode_message(
+                &[(":message-type", "event"), (":event-type", event), (":content-type", "application/json")],
+                payload.as_bytes(),
+            ));
+        }
+
+        let mut buf = BytesMut::from(&raw[..]);
+        let mut events = Vec::new();
+        while let Some(payload) = decode_message(&mut buf) {
+            events.extend(t.transform_stream_chunk(&payload, &mut ctx).unwrap());
+        }
+
+        assert!(events[0].contains(r#""type":"text""#));
+        assert!(events[1].contains("안녕"));
+        assert!(events[2].contains("content_block_stop"));
+        assert!(events[3].contains("tooluse_1"));
+        assert!(events[4].contains("input_json_delta"));
+        assert!(events[5].contains("content_block_stop"));
+        let last = events.last().unwrap();
+        assert!(last.contains(r#""stop_reason":"tool_use""#));
+        assert!(last.contains(r#""output_tokens":3"#));
+        assert_eq!(t.stream_end_events(&mut ctx).len(), 1);
+    }
+}
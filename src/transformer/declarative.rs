@@ -0,0 +1,279 @@
+use serde_json::{json, Value};
+
+use super::{ApiError, StreamContext, StreamFraming, TransformError, TransformedRequest, Transformer};
+use crate::config::{ResponseMapping, TransformerConfig};
+use crate::util::expand_path_template;
+
+/// 설정으로 정의한 트랜스포머 (`transformers:` 섹션)
+///
+/// 요청은 기반 트랜스포머로 변환한 뒤 경로/필드/헤더를 보정하고,
+/// 응답은 JSON Pointer 매핑이 있는 항목만 덮어쓴다.
+pub struct DeclarativeTransformer {
+    base: Box<dyn Transformer>,
+    def: TransformerConfig,
+}
+
+impl DeclarativeTransformer {
+    pub fn new(base: Box<dyn Transformer>, def: TransformerConfig) -> Self {
+        DeclarativeTransformer { base, def }
+    }
+
+    /// 제공자 종료 사유 → Anthropic stop_reason (사용자 매핑 우선)
+    fn map_finish_reason(&self, reason: &str) -> String {
+        if let Some(mapped) = self.def.stop_reasons.get(reason) {
+            return mapped.clone();
+        }
+        match reason {
+            "length" | "max_tokens" | "MAX_TOKENS" => "max_tokens",
+            "tool_calls" | "function_call" | "tool_use" => "tool_use",
+            "stop_sequence" => "stop_sequence",
+            _ => "end_turn",
+        }
+        .to_string()
+    }
+}
+
+/// 객체끼리는 없는 키만 재귀적으로 채움 (예: Ollama `options.num_ctx`)
+fn merge_defaults(target: &mut Value, defaults: &Value) {
+    let (Value::Object(target), Value::Object(defaults)) = (target, defaults) else {
+        return;
+    };
+    for (key, value) in defaults {
+        match target.get_mut(key) {
+            Some(existing) => merge_defaults(existing, value),
+            None => {
+                target.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
+/// JSON Pointer로 문자열 값 조회
+fn pointer_str<'a>(body: &'a Value, pointer: &Option<String>) -> Option<&'a str> {
+    body.pointer(pointer.as_deref()?)?.as_str()
+}
+
+/// JSON Pointer로 정수 값 조회
+fn pointer_u64(body: &Value, pointer: &Option<String>) -> Option<u64> {
+    body.pointer(pointer.as_deref()?)?.as_u64()
+}
+
+impl Transformer for DeclarativeTransformer {
+    fn transform_request(
+        &self,
+        body: Value,
+        model_map: Option<&str>,
+        is_stream: bool,
+    ) -> Result<TransformedRequest, TransformError> {
+        let model = model_map
+            .or_else(|| body["model"].as_str())
+            .unwrap_or_default()
+            .to_string();
+        let mut transformed = self.base.transform_request(body, model_map, is_stream)?;
+
+        // 경로 템플릿
+        let template = if is_stream {
+            self.def.stream_path.as_ref().or(self.def.path.as_ref())
+        } else {
+            self.def.path.as_ref()
+        };
+        if let Some(template) = template {
+            transformed.path = expand_path_template(template, &model);
+        }
+
+        if let Some(obj) = transformed.body.as_object_mut() {
+            for (from, to) in &self.def.rename {
+                if let Some(value) = obj.remove(from) {
+                    obj.insert(to.clone(), value);
+                }
+            }
+            for key in &self.def.remove {
+                obj.remove(key);
+            }
+        }
+        merge_defaults(&mut transformed.body, &Value::Object(self.def.defaults.clone()));
+
+        for (name, value) in &self.def.headers {
+            transformed.extra_headers.push((name.clone(), value.clone()));
+        }
+
+        Ok(transformed)
+    }
+
+    fn transform_response(&self, body: Value, model: &str) -> Result<Value, TransformError> {
+        let mapping: &ResponseMapping = &self.def.response;
+        let text = pointer_str(&body, &mapping.text).map(String::from);
+        let finish_reason = pointer_str(&body, &mapping.finish_reason).map(|r| self.map_finish_reason(r));
+        let input_tokens = pointer_u64(&body, &mapping.input_tokens);
+        let output_tokens = pointer_u64(&body, &mapping.output_tokens);
+
+        // 텍스트 매핑이 있으면 기반 변환 없이 직접 구성 (기반 형식과 응답 구조가 다른 제공자)
+        let mut resp = if let Some(text) = text {
+            json!({
+                "id": format!("msg_{}", uuid::Uuid::new_v4().simple()),
+                "type": "message",
+                "role": "assistant",
+                "model": model,
+                "content": [{"type": "text", "text": text}],
+                "stop_reason": "end_turn",
+                "stop_sequence": null,
+                "usage": {"input_tokens": 0, "output_tokens": 0}
+            })
+        } else {
+            self.base.transform_response(body, model)?
+        };
+
+        if let Some(reason) = finish_reason {
+            resp["stop_reason"] = json!(reason);
+        }
+        if let Some(n) = input_tokens {
+            resp["usage"]["input_tokens"] = json!(n);
+        }
+        if let Some(n) = output_tokens {
+            resp["usage"]["output_tokens"] = json!(n);
+        }
+        Ok(resp)
+    }
+
+    fn transform_stream_chunk(
+        &self,
+        chunk: &str,
+        ctx: &mut StreamContext,
+    ) -> Result<Vec<String>, TransformError> {
+        let mapping = &self.def.stream;
+        if mapping.is_empty() {
+            return self.base.transform_stream_chunk(chunk, ctx);
+        }
+        if chunk.trim() == "[DONE]" {
+            return Ok(vec![]);
+        }
+
+        let data: Value = serde_json::from_str(chunk)
+            .map_err(|e| TransformError(format!("SSE JSON 파싱 실패: {e}")))?;
+        if data.get("error").is_some_and(|e| !e.is_null()) {
+            return Ok(ctx.error(&self.transform_error(500, chunk.as_bytes())));
+        }
+
+        let mut events = Vec::new();
+        if let Some(text) = pointer_str(&data, &mapping.text) {
+            if !text.is_empty() {
+                events.extend(ctx.text_delta(text));
+            }
+        }
+        let input = pointer_u64(&data, &mapping.input_tokens);
+        let output = pointer_u64(&data, &mapping.output_tokens);
+        if input.is_some() || output.is_some() {
+            ctx.set_usage(input, output, None);
+        }
+        if let Some(reason) = pointer_str(&data, &mapping.finish_reason) {
+            let reason = self.map_finish_reason(reason);
+            events.extend(ctx.stop(&reason));
+        }
+        Ok(events)
+    }
+
+    fn transform_error(&self, status: u16, body: &[u8]) -> ApiError {
+        self.base.transform_error(status, body)
+    }
+
+    fn stream_framing(&self) -> StreamFraming {
+        self.base.stream_framing()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transformer::openai::OpenAITransformer;
+
+    fn make_def() -> TransformerConfig {
+        serde_yaml::from_str(
+            r#"
+name: "acme"
+path: "/v2/chat/{model}"
+rename:
+  max_tokens: "max_completion_tokens"
+remove: ["parallel_tool_calls"]
+defaults:
+  safe_mode: false
+  temperature: 0.1
+  stream_options:
+    include_usage: false
+    extra: 1
+headers:
+  X-Acme-Version: "2025-01"
+response:
+  text: "/output/text"
+  finish_reason: "/output/reason"
+  output_tokens: "/meta/tokens_out"
+stream:
+  text: "/delta"
+  finish_reason: "/done_reason"
+stop_reasons:
+  truncated: "max_tokens"
+"#,
+        )
+        .unwrap()
+    }
+
+    fn make_transformer() -> DeclarativeTransformer {
+        DeclarativeTransformer::new(Box::new(OpenAITransformer::default()), make_def())
+    }
+
+    /// 요청: 기반 변환 후 경로/필드/헤더 보정
+    #[test]
+    fn test_transform_request_overrides() {
+        let t = make_transformer();
+        let body = json!({
+            "model": "claude-sonnet",
+            "max_tokens": 100,
+            "temperature": 0.7,
+            "messages": [{"role": "user", "content": "hi"}]
+        });
+        let result = t.transform_request(body, Some("acme-large"), true).unwrap();
+
+        // stream_path 미지정 시 path 사용
+        assert_eq!(result.path, "/v2/chat/acme-large");
+        assert!(result.body.get("max_tokens").is_none());
+        assert_eq!(result.body["max_completion_tokens"], 100);
+        assert_eq!(result.body["safe_mode"], false);
+        // 이미 있는 필드는 기본값으로 덮어쓰지 않음
+        assert_eq!(result.body["temperature"], 0.7);
+        // 객체는 없는 키만 병합
+        assert_eq!(result.body["stream_options"]["include_usage"], true);
+        assert_eq!(result.body["stream_options"]["extra"], 1);
+        assert!(result
+            .extra_headers
+            .contains(&("X-Acme-Version".to_string(), "2025-01".to_string())));
+    }
+
+    /// 응답: JSON Pointer 매핑과 사용자 stop_reason 매핑
+    #[test]
+    fn test_transform_response_pointers() {
+        let t = make_transformer();
+        let body = json!({
+            "output": {"text": "안녕", "reason": "truncated"},
+            "meta": {"tokens_out": 12}
+        });
+        let result = t.transform_response(body, "claude-sonnet").unwrap();
+        assert_eq!(result["content"][0]["text"], "안녕");
+        assert_eq!(result["stop_reason"], "max_tokens");
+        assert_eq!(result["usage"]["output_tokens"], 12);
+    }
+
+    /// 스트림: 매핑된 텍스트 델타와 종료 사유
+    #[test]
+    fn test_stream_chunk_pointers() {
+        let t = make_transformer();
+        let mut ctx = StreamContext::new("claude-sonnet", "msg_1");
+
+        let events = t.transform_stream_chunk(r#"{"delta":"Hi"}"#, &mut ctx).unwrap();
+        assert_eq!(events.len(), 2);
+        assert!(events[1].contains("text_delta"));
+
+        let events = t.transform_stream_chunk(r#"{"delta":"","done_reason":"stop"}"#, &mut ctx).unwrap();
+        assert!(events[0].contains("content_block_stop"));
+        let end = t.stream_end_events(&mut ctx);
+        assert!(end[0].contains(r#""stop_reason":"end_turn""#));
+    }
+}
@@ -0,0 +1,174 @@
+use serde_json::{json, Value};
+
+/// Anthropic 형식으로 변환된 업스트림 에러
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApiError {
+    /// 클라이언트에 돌려줄 HTTP 상태 코드
+    pub status: u16,
+    /// Anthropic 에러 타입 (`rate_limit_error` 등)
+    pub error_type: &'static str,
+    pub message: String,
+}
+
+impl ApiError {
+    pub fn new(status: u16, message: impl Into<String>) -> Self {
+        ApiError {
+            status,
+            error_type: error_type_for_status(status),
+            message: message.into(),
+        }
+    }
+
+    /// Anthropic 에러 본문 `{"type":"error","error":{...}}`
+    pub fn to_json(&self) -> Value {
+        json!({
+            "type": "error",
+            "error": {"type": self.error_type, "message": &self.message}
+        })
+    }
+}
+
+/// HTTP 상태 코드 → Anthropic 에러 타입
+pub fn error_type_for_status(status: u16) -> &'static str {
+    match status {
+        400 | 422 => "invalid_request_error",
+        401 => "authentication_error",
+        403 => "permission_error",
+        404 => "not_found_error",
+        413 => "request_too_large",
+        429 => "rate_limit_error",
+        503 | 529 => "overloaded_error",
+        _ => "api_error",
+    }
+}
+
+/// 업스트림 에러 본문에서 메시지 추출 (JSON이 아니면 원문)
+pub fn error_message(body: &[u8]) -> String {
+    let text = String::from_utf8_lossy(body);
+    let parsed: Option<Value> = serde_json::from_slice(body).ok();
+    parsed
+        .as_ref()
+        .and_then(|v| {
+            v["error"]["message"]
+                .as_str()
+                .or_else(|| v["message"].as_str())
+                .or_else(|| v["error"].as_str())
+        })
+        .map(String::from)
+        .unwrap_or_else(|| {
+            let text = text.trim();
+            if text.is_empty() {
+                "업스트림 오류 (응답 본문 없음)".to_string()
+            } else {
+                text.to_string()
+            }
+        })
+}
+
+/// OpenAI 호환 에러 `{"error":{"message","type","code"}}`
+///
+/// 상태 코드 외에 `type`/`code`로 더 정확한 분류가 가능한 경우 보정
+pub fn from_openai(status: u16, body: &[u8]) -> ApiError {
+    let mut err = ApiError::new(status, error_message(body));
+    let parsed: Value = serde_json::from_slice(body).unwrap_or(Value::Null);
+    let kind = parsed["error"]["code"]
+        .as_str()
+        .or_else(|| parsed["error"]["type"].as_str())
+        .unwrap_or("");
+    err.error_type = match kind {
+        "rate_limit_exceeded" | "insufficient_quota" => "rate_limit_error",
+        "context_length_exceeded" | "invalid_request_error" => "invalid_request_error",
+        "invalid_api_key" => "authentication_error",
+        "model_not_found" => "not_found_error",
+        _ => err.error_type,
+    };
+    err
+}
+
+/// Gemini(Google API) 에러 `{"error":{"code","message","status"}}`
+pub fn from_gemini(status: u16, body: &[u8]) -> ApiError {
+    let parsed: Value = serde_json::from_slice(body).unwrap_or(Value::Null);
+    // 스트림 중간 에러는 HTTP 200 안에 실려 오므로 본문의 code 우선
+    let status = parsed["error"]["code"]
+        .as_u64()
+        .map(|c| c as u16)
+        .filter(|c| *c >= 400)
+        .unwrap_or(status);
+    let mut err = ApiError::new(status, error_message(body));
+    err.error_type = match parsed["error"]["status"].as_str().unwrap_or("") {
+        "INVALID_ARGUMENT" | "FAILED_PRECONDITION" | "OUT_OF_RANGE" => "invalid_request_error",
+        "UNAUTHENTICATED" => "authentication_error",
+        "PERMISSION_DENIED" => "permission_error",
+        "NOT_FOUND" => "not_found_error",
+        "RESOURCE_EXHAUSTED" => "rate_limit_error",
+        "UNAVAILABLE" => "overloaded_error",
+        "INTERNAL" | "DEADLINE_EXCEEDED" | "UNKNOWN" => "api_error",
+        _ => err.error_type,
+    };
+    err
+}
+
+/// AWS(Bedrock) 에러 `{"message": ...}` 또는 event-stream 예외 `{"exception":{"type","message"}}`
+///
+/// HTTP 응답의 에러 종류는 `x-amzn-ErrorType` 헤더에 있어 본문만으로는 상태 코드를 따름
+pub fn from_bedrock(status: u16, body: &[u8]) -> ApiError {
+    let parsed: Value = serde_json::from_slice(body).unwrap_or(Value::Null);
+    let exception = &parsed["exception"];
+    if exception.is_null() {
+        return ApiError::new(status, error_message(body));
+    }
+    let kind = exception["type"].as_str().unwrap_or("");
+    let status = match kind {
+        "throttlingException" => 429,
+        "validationException" => 400,
+        "accessDeniedException" => 403,
+        "resourceNotFoundException" => 404,
+        "serviceUnavailableException" => 503,
+        _ => status,
+    };
+    let message = exception["message"].as_str().unwrap_or(kind);
+    ApiError::new(status, format!("{kind}: {message}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_openai_rate_limit() {
+        let body = br#"{"error":{"message":"Rate limit reached","type":"requests","code":"rate_limit_exceeded"}}"#;
+        let err = from_openai(429, body);
+        assert_eq!(err.status, 429);
+        assert_eq!(err.error_type, "rate_limit_error");
+        assert_eq!(err.to_json()["error"]["message"], "Rate limit reached");
+
+        // JSON이 아닌 본문은 원문 메시지 + 상태 코드 기반 타입
+        let err = from_openai(502, b"Bad Gateway");
+        assert_eq!(err.error_type, "api_error");
+        assert_eq!(err.message, "Bad Gateway");
+    }
+
+    #[test]
+    fn test_from_bedrock_exception() {
+        let err = from_bedrock(500, br#"{"exception":{"type":"throttlingException","message":"Too many tokens"}}"#);
+        assert_eq!(err.status, 429);
+        assert_eq!(err.error_type, "rate_limit_error");
+
+        let err = from_bedrock(400, br#"{"message":"Malformed input request"}"#);
+        assert_eq!(err.error_type, "invalid_request_error");
+        assert_eq!(err.message, "Malformed input request");
+    }
+
+    #[test]
+    fn test_from_gemini_status() {
+        let body = br#"{"error":{"code":429,"message":"Quota exceeded","status":"RESOURCE_EXHAUSTED"}}"#;
+        let err = from_gemini(200, body);
+        assert_eq!(err.status, 429);
+        assert_eq!(err.error_type, "rate_limit_error");
+
+        let body = br#"{"error":{"code":400,"message":"bad schema","status":"INVALID_ARGUMENT"}}"#;
+        let err = from_gemini(400, body);
+        assert_eq!(err.to_json()["type"], "error");
+        assert_eq!(err.error_type, "invalid_request_error");
+    }
+}
@@ -0,0 +1,191 @@
+use bytes::{Buf, BytesMut};
+use serde_json::{json, Value};
+
+/// prelude(총 길이 4 + 헤더 길이 4 + prelude CRC 4) + 메시지 CRC 4
+const PRELUDE_LEN: usize = 12;
+const MIN_MESSAGE_LEN: usize = PRELUDE_LEN + 4;
+/// 비정상 길이 방어 (AWS 제한: 16MB)
+const MAX_MESSAGE_LEN: usize = 16 * 1024 * 1024;
+
+/// AWS event-stream (`application/vnd.amazon.eventstream`) 메시지 1개를 디코딩
+///
+/// 버퍼에 완성된 메시지가 없으면 None. 디코딩한 메시지는 버퍼에서 제거하고
+/// 트랜스포머가 다루기 쉽도록 JSON 문자열로 반환한다.
+/// - 이벤트: `{"<:event-type>": payload}` (예: `{"contentBlockDelta": {...}}`)
+/// - 예외: `{"exception": {"type": "<:exception-type>", "message": ...}}`
+pub fn decode_message(buf: &mut BytesMut) -> Option<String> {
+    if buf.len() < PRELUDE_LEN {
+        return None;
+    }
+    let total_len = u32::from_be_bytes([buf[0], buf[1], buf[2], buf[3]]) as usize;
+    let headers_len = u32::from_be_bytes([buf[4], buf[5], buf[6], buf[7]]) as usize;
+    let prelude_crc = u32::from_be_bytes([buf[8], buf[9], buf[10], buf[11]]);
+
+    if crc32(&buf[..8]) != prelude_crc
+        || !(MIN_MESSAGE_LEN..=MAX_MESSAGE_LEN).contains(&total_len)
+        || headers_len > total_len - MIN_MESSAGE_LEN
+    {
+        buf.clear();
+        return Some(frame_error("event-stream prelude가 손상되었습니다"));
+    }
+    if buf.len() < total_len {
+        return None;
+    }
+
+    let message = buf.split_to(total_len);
+    let message_crc = u32::from_be_bytes(message[total_len - 4..].try_into().unwrap_or_default());
+    if crc32(&message[..total_len - 4]) != message_crc {
+        return Some(frame_error("event-stream 메시지 CRC가 일치하지 않습니다"));
+    }
+
+    let headers = parse_headers(&message[PRELUDE_LEN..PRELUDE_LEN + headers_len]);
+    let payload = &message[PRELUDE_LEN + headers_len..total_len - 4];
+    let payload: Value = serde_json::from_slice(payload).unwrap_or(Value::Null);
+    let header = |name: &str| {
+        headers
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+            .unwrap_or("")
+    };
+
+    let out = match header(":message-type") {
+        "exception" | "error" => {
+            let kind = match header(":exception-type") {
+                "" => header(":error-code"),
+                t => t,
+            };
+            let message = payload["message"]
+                .as_str()
+                .or_else(|| payload["Message"].as_str())
+                .unwrap_or(header(":error-message"));
+            json!({"exception": {"type": kind, "message": message}})
+        }
+        _ => json!({ header(":event-type"): payload }),
+    };
+    Some(out.to_string())
+}
+
+fn frame_error(message: &str) -> String {
+    json!({"exception": {"type": "invalidFrameException", "message": message}}).to_string()
+}
+
+/// 헤더 파싱 (문자열 값만 보관, 나머지 타입은 건너뜀)
+fn parse_headers(mut data: &[u8]) -> Vec<(String, String)> {
+    let mut headers = Vec::new();
+    while data.remaining() > 2 {
+        let name_len = data.get_u8() as usize;
+        if data.remaining() < name_len + 1 {
+            break;
+        }
+        let name = String::from_utf8_lossy(&data[..name_len]).into_owned();
+        data.advance(name_len);
+        let value_len = match data.get_u8() {
+            0 | 1 => 0,
+            2 => 1,
+            3 => 2,
+            4 => 4,
+            5 | 8 => 8,
+            9 => 16,
+            // 6: bytes, 7: string (2바이트 길이 접두)
+            6 | 7 if data.remaining() >= 2 => {
+                let len = data.get_u16() as usize;
+                if data.remaining() < len {
+                    break;
+                }
+                headers.push((name, String::from_utf8_lossy(&data[..len]).into_owned()));
+                data.advance(len);
+                continue;
+            }
+            _ => break,
+        };
+        if data.remaining() < value_len {
+            break;
+        }
+        data.advance(value_len);
+    }
+    headers
+}
+
+/// CRC-32 (IEEE 802.3)
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+/// 테스트용 event-stream 메시지 인코딩 (문자열 헤더만)
+#[cfg(test)]
+pub fn encode_message(headers: &[(&str, &str)], payload: &[u8]) -> Vec<u8> {
+    let mut header_bytes = Vec::new();
+    for (name, value) in headers {
+        header_bytes.push(name.len() as u8);
+        header_bytes.extend_from_slice(name.as_bytes());
+        header_bytes.push(7);
+        header_bytes.extend_from_slice(&(value.len() as u16).to_be_bytes());
+        header_bytes.extend_from_slice(value.as_bytes());
+    }
+    let total_len = (MIN_MESSAGE_LEN + header_bytes.len() + payload.len()) as u32;
+    let mut out = Vec::new();
+    out.extend_from_slice(&total_len.to_be_bytes());
+    out.extend_from_slice(&(header_bytes.len() as u32).to_be_bytes());
+    let prelude_crc = crc32(&out);
+    out.extend_from_slice(&prelude_crc.to_be_bytes());
+    out.extend_from_slice(&header_bytes);
+    out.extend_from_slice(payload);
+    let message_crc = crc32(&out);
+    out.extend_from_slice(&message_crc.to_be_bytes());
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crc32_known_value() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    /// 분할 수신된 메시지는 완성될 때까지 대기, 이벤트/예외 구분
+    #[test]
+    fn test_decode_partial_and_exception() {
+        let event = encode_message(
+            &[(":message-type", "event"), (":event-type", "contentBlockDelta")],
+            br#"{"contentBlockIndex":0,"delta":{"text":"hi"}}"#,
+        );
+        let exception = encode_message(
+            &[(":message-type", "exception"), (":exception-type", "throttlingException")],
+            br#"{"message":"Too many requests"}"#,
+        );
+
+        let mut buf = BytesMut::from(&event[..10]);
+        assert!(decode_message(&mut buf).is_none());
+        buf.extend_from_slice(&event[10..]);
+        buf.extend_from_slice(&exception);
+
+        let first: Value = serde_json::from_str(&decode_message(&mut buf).unwrap()).unwrap();
+        assert_eq!(first["contentBlockDelta"]["delta"]["text"], "hi");
+
+        let second: Value = serde_json::from_str(&decode_message(&mut buf).unwrap()).unwrap();
+        assert_eq!(second["exception"]["type"], "throttlingException");
+        assert_eq!(second["exception"]["message"], "Too many requests");
+        assert!(buf.is_empty());
+    }
+
+    /// 손상된 prelude → 예외 페이로드 + 버퍼 비움
+    #[test]
+    fn test_decode_corrupt_prelude() {
+        let mut message = encode_message(&[(":event-type", "messageStop")], b"{}");
+        message[8] ^= 0xFF;
+        let mut buf = BytesMut::from(&message[..]);
+        let out = decode_message(&mut buf).unwrap();
+        assert!(out.contains("invalidFrameException"));
+        assert!(buf.is_empty());
+    }
+}
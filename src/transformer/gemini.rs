@@ -1,9 +1,21 @@
 use serde_json::{json, Value};
+use std::collections::HashMap;
 use uuid::Uuid;
 
-use super::{StreamContext, TransformError, TransformedRequest, Transformer};
+use super::{error, schema, thinking_budget, ApiError, StreamContext, TransformError, TransformedRequest, Transformer};
+use crate::config::VertexConfig;
 
-pub struct GeminiTransformer;
+/// Gemini generateContent 트랜스포머 (Google AI Studio 또는 Vertex AI)
+pub struct GeminiTransformer {
+    /// Some이면 Vertex AI 경로 사용
+    vertex: Option<VertexConfig>,
+}
+
+impl GeminiTransformer {
+    pub fn new(vertex: Option<VertexConfig>) -> Self {
+        GeminiTransformer { vertex }
+    }
+}
 
 /// Anthropic role → Gemini role
 fn map_role(role: &str) -> &str {
@@ -13,21 +25,216 @@ fn map_role(role: &str) -> &str {
     }
 }
 
-/// Anthropic content → Gemini parts
-fn to_parts(content: &Value) -> Value {
+/// Gemini finishReason → Anthropic stop_reason
+fn map_finish_reason(reason: &str) -> &str {
+    match reason {
+        "STOP" => "end_turn",
+        "MAX_TOKENS" => "max_tokens",
+        other => other,
+    }
+}
+
+/// tool_use id → 함수 이름 매핑 (functionResponse에는 이름이 필요)
+pub(super) fn tool_names(messages: &[Value]) -> HashMap<String, String> {
+    messages
+        .iter()
+        .filter_map(|m| m["content"].as_array())
+        .flatten()
+        .filter(|b| b["type"] == "tool_use")
+        .filter_map(|b| Some((b["id"].as_str()?.to_string(), b["name"].as_str()?.to_string())))
+        .collect()
+}
+
+/// tool_result의 content (문자열 또는 블록 배열) → 문자열
+fn tool_result_text(content: &Value) -> String {
     match content {
-        Value::String(s) => json!([{"text": s}]),
-        Value::Array(arr) => {
-            let parts: Vec<Value> = arr
-                .iter()
-                .filter_map(|block| {
-                    block["text"].as_str().map(|t| json!({"text": t}))
-                })
-                .collect();
-            json!(parts)
+        Value::String(s) => s.clone(),
+        Value::Array(arr) => arr
+            .iter()
+            .filter_map(|b| b["text"].as_str())
+            .collect::<Vec<_>>()
+            .join("\n"),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/// URL 확장자로 MIME 타입 추정 (fileData에는 mimeType이 필수)
+fn guess_mime_type(url: &str) -> &'static str {
+    let path = url.split(['?', '#']).next().unwrap_or(url).to_ascii_lowercase();
+    match path.rsplit('.').next() {
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        Some("pdf") => "application/pdf",
+        _ => "application/octet-stream",
+    }
+}
+
+/// image / document 블록 → Gemini part
+/// - base64 → `inlineData` (PDF 포함)
+/// - url → `fileData`
+/// - 텍스트 문서 → `text`
+fn media_part(block: &Value) -> Result<Value, TransformError> {
+    let source = &block["source"];
+    match source["type"].as_str() {
+        Some("base64") => Ok(json!({
+            "inlineData": {"mimeType": source["media_type"], "data": source["data"]}
+        })),
+        Some("url") => {
+            let url = source["url"].as_str().unwrap_or("");
+            let mime = source["media_type"].as_str().unwrap_or_else(|| guess_mime_type(url));
+            Ok(json!({"fileData": {"mimeType": mime, "fileUri": url}}))
+        }
+        Some("text") if block["type"] == "document" => Ok(json!({"text": source["data"]})),
+        Some("content") if block["type"] == "document" => {
+            Ok(json!({"text": tool_result_text(&source["content"])}))
+        }
+        other => Err(TransformError(format!(
+            "Gemini는 {} 블록의 source 타입 {}을(를) 지원하지 않습니다",
+            block["type"].as_str().unwrap_or("unknown"),
+            other.unwrap_or("unknown"),
+        ))),
+    }
+}
+
+/// Anthropic content → Gemini parts
+fn to_parts(content: &Value, names: &HashMap<String, String>) -> Result<Value, TransformError> {
+    let arr = match content {
+        Value::String(s) => return Ok(json!([{"text": s}])),
+        Value::Array(arr) => arr,
+        _ => return Ok(json!([{"text": ""}])),
+    };
+
+    let mut parts = Vec::new();
+    for block in arr {
+        match block["type"].as_str() {
+            Some("tool_use") => {
+                let mut part = json!({
+                    "functionCall": {
+                        "name": block["name"],
+                        "args": block.get("input").cloned().unwrap_or_else(|| json!({})),
+                    }
+                });
+                if let Some(signature) = block["id"].as_str().and_then(thought_signature) {
+                    part["thoughtSignature"] = json!(signature);
+                }
+                parts.push(part);
+            }
+            Some("tool_result") => {
+                let id = block["tool_use_id"].as_str().unwrap_or("");
+                let name = names.get(id).map(String::as_str).unwrap_or(id);
+                let key = if block["is_error"].as_bool() == Some(true) {
+                    "error"
+                } else {
+                    "content"
+                };
+                parts.push(json!({
+                    "functionResponse": {
+                        "name": name,
+                        "response": {key: tool_result_text(&block["content"])},
+                    }
+                }));
+                // functionResponse는 텍스트만 담으므로 결과 안의 이미지는 별도 part로 추가
+                for nested in block["content"].as_array().into_iter().flatten() {
+                    if nested["type"] == "image" || nested["type"] == "document" {
+                        parts.push(media_part(nested)?);
+                    }
+                }
+            }
+            Some("image" | "document") => parts.push(media_part(block)?),
+            _ => {
+                if let Some(t) = block["text"].as_str() {
+                    parts.push(json!({"text": t}));
+                }
+            }
         }
-        _ => json!([{"text": ""}]),
     }
+    Ok(json!(parts))
+}
+
+/// Anthropic tools → Gemini functionDeclarations
+fn convert_tools(tools: &[Value]) -> Value {
+    let declarations: Vec<Value> = tools
+        .iter()
+        .map(|tool| {
+            let mut decl = json!({"name": tool["name"]});
+            if let Some(desc) = tool.get("description") {
+                decl["description"] = desc.clone();
+            }
+            // Gemini는 properties가 빈 OBJECT 스키마를 거부하므로 생략
+            if let Some(params) = tool.get("input_schema").map(|s| schema::sanitize(s, &schema::GEMINI)) {
+                if params["properties"].as_object().is_some_and(|p| !p.is_empty()) {
+                    decl["parameters"] = params;
+                }
+            }
+            decl
+        })
+        .collect();
+    json!([{"functionDeclarations": declarations}])
+}
+
+/// Anthropic tool_choice → Gemini toolConfig
+fn convert_tool_choice(choice: &Value) -> Option<Value> {
+    let config = match choice["type"].as_str()? {
+        "auto" => json!({"mode": "AUTO"}),
+        "any" => json!({"mode": "ANY"}),
+        "none" => json!({"mode": "NONE"}),
+        "tool" => json!({"mode": "ANY", "allowedFunctionNames": [choice["name"]]}),
+        _ => return None,
+    };
+    Some(json!({"functionCallingConfig": config}))
+}
+
+/// Gemini usageMetadata → (입력, 출력, 캐시 적중) 토큰 수
+///
+/// promptTokenCount는 캐시 적중분을 포함하고, candidatesTokenCount는 사고 토큰을 제외한다.
+fn usage_tokens(usage: &Value) -> (Option<u64>, Option<u64>, Option<u64>) {
+    let cached = usage["cachedContentTokenCount"].as_u64();
+    let input = usage["promptTokenCount"]
+        .as_u64()
+        .map(|p| p.saturating_sub(cached.unwrap_or(0)));
+    let output = usage["candidatesTokenCount"]
+        .as_u64()
+        .map(|c| c + usage["thoughtsTokenCount"].as_u64().unwrap_or(0));
+    (input, output, cached)
+}
+
+/// 사고 요약 part인지 (`thought: true`)
+fn is_thought(part: &Value) -> bool {
+    part["thought"].as_bool() == Some(true)
+}
+
+/// tool_use id에서 thoughtSignature가 시작되는 구분자
+const SIGNATURE_SEPARATOR: &str = "__ts_";
+
+/// functionCall part → tool_use id (없으면 생성, thoughtSignature가 있으면 id 뒤에 붙임)
+///
+/// 사고 모델은 functionCall의 thoughtSignature를 다음 턴에 그대로 돌려받아야 한다.
+/// tool_use 블록에는 임의 필드를 둘 곳이 없으므로 id 허용 문자로 바꿔(base64url) id에 담는다.
+fn tool_use_id(part: &Value) -> String {
+    let id = part["functionCall"]["id"]
+        .as_str()
+        .map(String::from)
+        .unwrap_or_else(|| format!("toolu_{}", Uuid::new_v4().simple()));
+    match part["thoughtSignature"].as_str().filter(|s| !s.is_empty()) {
+        Some(signature) => {
+            let encoded = signature.replace('+', "-").replace('/', "_");
+            format!("{id}{SIGNATURE_SEPARATOR}{}", encoded.trim_end_matches('='))
+        }
+        None => id,
+    }
+}
+
+/// tool_use id에 담긴 thoughtSignature 복원 (base64url → base64)
+fn thought_signature(tool_use_id: &str) -> Option<String> {
+    let (_, encoded) = tool_use_id.split_once(SIGNATURE_SEPARATOR)?;
+    let mut signature = encoded.replace('-', "+").replace('_', "/");
+    while signature.len() % 4 != 0 {
+        signature.push('=');
+    }
+    Some(signature)
 }
 
 impl Transformer for GeminiTransformer {
@@ -47,32 +254,43 @@ impl Transformer for GeminiTransformer {
             .or_else(|| obj.get("model").and_then(|v| v.as_str()).map(String::from))
             .unwrap_or_else(|| "gemini-2.0-flash".to_string());
 
-        // 경로 결정
-        let path = if is_stream {
-            format!("/v1beta/models/{}:streamGenerateContent?alt=sse", model)
-        } else {
-            format!("/v1beta/models/{}:generateContent", model)
+        // 경로 결정 (Vertex AI는 프로젝트/리전 경로)
+        let method = if is_stream { "streamGenerateContent?alt=sse" } else { "generateContent" };
+        let path = match &self.vertex {
+            Some(vertex) => vertex.model_path(&model, method),
+            None => format!("/v1beta/models/{}:{}", model, method),
         };
 
         // messages → contents
-        let contents: Vec<Value> = obj
+        let messages = obj
             .get("messages")
             .and_then(|m| m.as_array())
-            .map(|msgs| {
-                msgs.iter()
-                    .filter(|m| m["role"].as_str() != Some("system"))
-                    .map(|m| {
-                        json!({
-                            "role": map_role(m["role"].as_str().unwrap_or("user")),
-                            "parts": to_parts(&m["content"]),
-                        })
-                    })
-                    .collect()
-            })
+            .map(Vec::as_slice)
             .unwrap_or_default();
+        let names = tool_names(messages);
+        let contents: Vec<Value> = messages
+            .iter()
+            .filter(|m| m["role"].as_str() != Some("system"))
+            .map(|m| {
+                Ok(json!({
+                    "role": map_role(m["role"].as_str().unwrap_or("user")),
+                    "parts": to_parts(&m["content"], &names)?,
+                }))
+            })
+            .collect::<Result<_, TransformError>>()?;
 
         let mut gemini_body = json!({"contents": contents});
 
+        // tools → functionDeclarations
+        if let Some(tools) = obj.get("tools").and_then(|t| t.as_array()) {
+            if !tools.is_empty() {
+                gemini_body["tools"] = convert_tools(tools);
+            }
+        }
+        if let Some(tool_config) = obj.get("tool_choice").and_then(convert_tool_choice) {
+            gemini_body["toolConfig"] = tool_config;
+        }
+
         // system → systemInstruction
         if let Some(system) = obj.get("system") {
             let system_text = match system {
@@ -105,7 +323,19 @@ impl Transformer for GeminiTransformer {
         if let Some(stop) = obj.get("stop_sequences") {
             gen_config["stopSequences"] = stop.clone();
         }
-        if gen_config.as_object().map_or(false, |o| !o.is_empty()) {
+        // thinking.budget_tokens → thinkingConfig (사고 요약을 thought part로 받음)
+        if let Some(thinking) = obj.get("thinking") {
+            match thinking_budget(thinking) {
+                Some(budget) => {
+                    gen_config["thinkingConfig"] = json!({"thinkingBudget": budget, "includeThoughts": true});
+                }
+                None if thinking["type"] == "disabled" => {
+                    gen_config["thinkingConfig"] = json!({"thinkingBudget": 0});
+                }
+                None => {}
+            }
+        }
+        if gen_config.as_object().is_some_and(|o| !o.is_empty()) {
             gemini_body["generationConfig"] = gen_config;
         }
 
@@ -121,60 +351,76 @@ impl Transformer for GeminiTransformer {
         body: Value,
         model: &str,
     ) -> Result<Value, TransformError> {
-        let text = body["candidates"]
-            .get(0)
-            .and_then(|c| c["content"]["parts"].get(0))
-            .and_then(|p| p["text"].as_str())
-            .unwrap_or("");
+        let parts = body["candidates"][0]["content"]["parts"]
+            .as_array()
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+
+        // thought part는 thinking 블록, 텍스트 part는 하나로 합치고, functionCall part는 tool_use 블록으로
+        let thoughts: String = parts
+            .iter()
+            .filter(|p| is_thought(p))
+            .filter_map(|p| p["text"].as_str())
+            .collect();
+        let text: String = parts
+            .iter()
+            .filter(|p| !is_thought(p))
+            .filter_map(|p| p["text"].as_str())
+            .collect();
+        let tool_uses: Vec<Value> = parts
+            .iter()
+            .filter_map(|p| Some((p, p.get("functionCall")?)))
+            .map(|(part, call)| {
+                json!({
+                    "type": "tool_use",
+                    "id": tool_use_id(part),
+                    "name": call["name"],
+                    "input": call.get("args").cloned().unwrap_or_else(|| json!({})),
+                })
+            })
+            .collect();
 
         let finish = body["candidates"]
             .get(0)
             .and_then(|c| c["finishReason"].as_str())
             .unwrap_or("STOP");
 
-        let stop_reason = match finish {
-            "STOP" => "end_turn",
-            "MAX_TOKENS" => "max_tokens",
-            other => other,
+        // Gemini는 함수 호출 시에도 STOP을 반환하므로 tool_use 여부로 판단
+        let stop_reason = if tool_uses.is_empty() {
+            map_finish_reason(finish)
+        } else {
+            "tool_use"
         };
 
-        let input_tokens = body["usageMetadata"]["promptTokenCount"]
-            .as_u64()
-            .unwrap_or(0);
-        let output_tokens = body["usageMetadata"]["candidatesTokenCount"]
-            .as_u64()
-            .unwrap_or(0);
+        let mut content = Vec::new();
+        if !thoughts.is_empty() {
+            content.push(json!({"type": "thinking", "thinking": thoughts, "signature": ""}));
+        }
+        if !text.is_empty() || tool_uses.is_empty() {
+            content.push(json!({"type": "text", "text": text}));
+        }
+        content.extend(tool_uses);
+
+        let (input_tokens, output_tokens, cached_tokens) = usage_tokens(&body["usageMetadata"]);
 
         Ok(json!({
             "id": format!("msg_{}", Uuid::new_v4().simple()),
             "type": "message",
             "role": "assistant",
             "model": model,
-            "content": [{"type": "text", "text": text}],
+            "content": content,
             "stop_reason": stop_reason,
             "stop_sequence": null,
             "usage": {
-                "input_tokens": input_tokens,
-                "output_tokens": output_tokens,
+                "input_tokens": input_tokens.unwrap_or(0),
+                "output_tokens": output_tokens.unwrap_or(0),
+                "cache_read_input_tokens": cached_tokens.unwrap_or(0),
             }
         }))
     }
 
-    fn stream_start_events(&self, ctx: &StreamContext) -> Vec<String> {
-        let msg_start = json!({
-            "type": "message_start",
-            "message": {
-                "id": &ctx.message_id,
-                "type": "message",
-                "role": "assistant",
-                "model": &ctx.model,
-                "content": [],
-                "stop_reason": null,
-                "stop_sequence": null,
-                "usage": {"input_tokens": 0, "output_tokens": 0}
-            }
-        });
-        vec![format!("event: message_start\ndata: {}\n\n", msg_start)]
+    fn transform_error(&self, status: u16, body: &[u8]) -> ApiError {
+        error::from_gemini(status, body)
     }
 
     fn transform_stream_chunk(
@@ -185,104 +431,78 @@ impl Transformer for GeminiTransformer {
         let data: Value = serde_json::from_str(chunk)
             .map_err(|e| TransformError(format!("SSE JSON 파싱 실패: {e}")))?;
 
+        // 스트림 중간 에러 청크
+        if data.get("error").is_some_and(|e| !e.is_null()) {
+            return Ok(ctx.error(&self.transform_error(500, chunk.as_bytes())));
+        }
+
         let mut events = Vec::new();
 
-        // 텍스트 추출
-        let text = data["candidates"]
-            .get(0)
-            .and_then(|c| c["content"]["parts"].get(0))
-            .and_then(|p| p["text"].as_str())
-            .unwrap_or("");
+        let parts = data["candidates"][0]["content"]["parts"]
+            .as_array()
+            .map(Vec::as_slice)
+            .unwrap_or_default();
 
         // usage 추적
         if let Some(usage) = data.get("usageMetadata") {
-            if let Some(pt) = usage["promptTokenCount"].as_u64() {
-                ctx.input_tokens = pt as u32;
-            }
-            if let Some(ct) = usage["candidatesTokenCount"].as_u64() {
-                ctx.output_tokens = ct as u32;
-            }
+            let (input, output, cached) = usage_tokens(usage);
+            ctx.set_usage(input, output, cached);
         }
 
-        // 첫 번째 청크: content_block_start
-        if !ctx.started {
-            ctx.started = true;
-            let block_start = json!({
-                "type": "content_block_start",
-                "index": ctx.block_index,
-                "content_block": {"type": "text", "text": ""}
-            });
-            events.push(format!(
-                "event: content_block_start\ndata: {}\n\n",
-                block_start
-            ));
-        }
+        for part in parts {
+            // functionCall은 한 번에 완성된 args로 오므로 블록을 열고 바로 닫음
+            if let Some(call) = part.get("functionCall") {
+                let id = tool_use_id(part);
+                let name = call["name"].as_str().unwrap_or("");
+                let call_index = ctx.block_index as u64;
+                events.extend(ctx.open_tool_block(call_index, &id, name));
+                let args = call.get("args").cloned().unwrap_or_else(|| json!({}));
+                events.extend(ctx.tool_input_delta(&args.to_string()));
+                events.extend(ctx.close_block());
+                continue;
+            }
 
-        // 텍스트 델타
-        if !text.is_empty() {
-            let delta = json!({
-                "type": "content_block_delta",
-                "index": ctx.block_index,
-                "delta": {"type": "text_delta", "text": text}
-            });
-            events.push(format!("event: content_block_delta\ndata: {}\n\n", delta));
+            // 텍스트 델타 (thought part는 thinking 블록으로)
+            let text = part["text"].as_str().unwrap_or("");
+            if text.is_empty() {
+                continue;
+            }
+            if is_thought(part) {
+                events.extend(ctx.thinking_delta(text));
+            } else {
+                events.extend(ctx.text_delta(text));
+            }
         }
 
         // finishReason 확인 → 종료 이벤트
+        // Gemini는 함수 호출 시에도 STOP을 반환하므로 tool_use 여부로 판단
         let finish = data["candidates"]
             .get(0)
             .and_then(|c| c["finishReason"].as_str());
 
         if let Some(reason) = finish {
             let stop_reason = match reason {
-                "STOP" => "end_turn",
-                "MAX_TOKENS" => "max_tokens",
-                other => other,
+                "STOP" => None,
+                other => Some(map_finish_reason(other)),
             };
-
-            let block_stop = json!({
-                "type": "content_block_stop",
-                "index": ctx.block_index,
-            });
-            events.push(format!(
-                "event: content_block_stop\ndata: {}\n\n",
-                block_stop
-            ));
-
-            let msg_delta = json!({
-                "type": "message_delta",
-                "delta": {"stop_reason": stop_reason, "stop_sequence": null},
-                "usage": {"output_tokens": ctx.output_tokens}
-            });
-            events.push(format!("event: message_delta\ndata: {}\n\n", msg_delta));
+            events.extend(ctx.finish(stop_reason));
         }
 
         Ok(events)
     }
-
-    fn stream_end_events(&self, _ctx: &mut StreamContext) -> Vec<String> {
-        let msg_stop = json!({"type": "message_stop"});
-        vec![format!("event: message_stop\ndata: {}\n\n", msg_stop)]
-    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::transformer::stream::BlockKind;
 
     fn make_transformer() -> GeminiTransformer {
-        GeminiTransformer
+        GeminiTransformer::new(None)
     }
 
     fn make_ctx() -> StreamContext {
-        StreamContext {
-            model: "gemini-2.0-flash".into(),
-            message_id: "msg_test456".into(),
-            input_tokens: 0,
-            output_tokens: 0,
-            block_index: 0,
-            started: false,
-        }
+        StreamContext::new("gemini-2.0-flash", "msg_test456")
     }
 
     /// 요청 변환: contents 구조, systemInstruction, URL 경로
@@ -336,6 +556,22 @@ mod tests {
         assert!(result.path.contains("streamGenerateContent?alt=sse"));
     }
 
+    /// 요청 변환: Vertex AI 경로
+    #[test]
+    fn test_transform_request_vertex_path() {
+        let t = GeminiTransformer::new(Some(VertexConfig {
+            project: "my-proj".into(),
+            location: "us-central1".into(),
+            publisher: "google".into(),
+        }));
+        let body = json!({"model": "gemini-2.5-pro", "max_tokens": 10, "messages": []});
+        let result = t.transform_request(body, None, true).unwrap();
+        assert_eq!(
+            result.path,
+            "/v1/projects/my-proj/locations/us-central1/publishers/google/models/gemini-2.5-pro:streamGenerateContent?alt=sse"
+        );
+    }
+
     /// 요청 변환: model_map 적용
     #[test]
     fn test_transform_request_model_map() {
@@ -345,6 +581,31 @@ mod tests {
         assert!(result.path.contains("gemini-2.0-flash"));
     }
 
+    /// 요청 변환: image/document → inlineData, URL → fileData
+    #[test]
+    fn test_transform_request_images_and_documents() {
+        let t = make_transformer();
+        let body = json!({
+            "model": "gemini-2.0-flash",
+            "max_tokens": 10,
+            "messages": [{"role": "user", "content": [
+                {"type": "text", "text": "이 화면 봐줘"},
+                {"type": "image", "source": {"type": "base64", "media_type": "image/png", "data": "iVBOR"}},
+                {"type": "image", "source": {"type": "url", "url": "https://example.com/a.webp?x=1"}},
+                {"type": "document", "source": {"type": "base64", "media_type": "application/pdf", "data": "JVBER"}}
+            ]}]
+        });
+        let result = t.transform_request(body, None, false).unwrap();
+        let parts = result.body["contents"][0]["parts"].as_array().unwrap();
+
+        assert_eq!(parts.len(), 4);
+        assert_eq!(parts[1]["inlineData"]["mimeType"], "image/png");
+        assert_eq!(parts[1]["inlineData"]["data"], "iVBOR");
+        assert_eq!(parts[2]["fileData"]["mimeType"], "image/webp");
+        assert_eq!(parts[2]["fileData"]["fileUri"], "https://example.com/a.webp?x=1");
+        assert_eq!(parts[3]["inlineData"]["mimeType"], "application/pdf");
+    }
+
     /// 응답 변환: Gemini → Anthropic
     #[test]
     fn test_transform_response() {
@@ -370,6 +631,25 @@ mod tests {
         assert_eq!(result["usage"]["output_tokens"], 5);
     }
 
+    /// 응답 변환: 캐시 적중 토큰은 입력에서 분리, 사고 토큰은 출력에 합산
+    #[test]
+    fn test_transform_response_usage_cache_and_thoughts() {
+        let t = make_transformer();
+        let body = json!({
+            "candidates": [{"content": {"parts": [{"text": "ok"}]}, "finishReason": "STOP"}],
+            "usageMetadata": {
+                "promptTokenCount": 1000,
+                "cachedContentTokenCount": 800,
+                "candidatesTokenCount": 5,
+                "thoughtsTokenCount": 40
+            }
+        });
+        let result = t.transform_response(body, "gemini-2.5-pro").unwrap();
+        assert_eq!(result["usage"]["input_tokens"], 200);
+        assert_eq!(result["usage"]["output_tokens"], 45);
+        assert_eq!(result["usage"]["cache_read_input_tokens"], 800);
+    }
+
     /// 응답 변환: MAX_TOKENS → max_tokens
     #[test]
     fn test_transform_response_max_tokens() {
@@ -393,7 +673,7 @@ mod tests {
         let chunk = r#"{"candidates":[{"content":{"parts":[{"text":"안녕"}]}}],"usageMetadata":{"promptTokenCount":5}}"#;
         let events = t.transform_stream_chunk(chunk, &mut ctx).unwrap();
 
-        assert!(ctx.started);
+        assert_eq!(ctx.open_block, Some(BlockKind::Text));
         assert_eq!(events.len(), 2); // block_start + delta
         assert!(events[0].contains("content_block_start"));
         assert!(events[1].contains("text_delta"));
@@ -406,7 +686,7 @@ mod tests {
     fn test_stream_chunk_finish() {
         let t = make_transformer();
         let mut ctx = make_ctx();
-        ctx.started = true;
+        ctx.text_delta("");
         let chunk = r#"{"candidates":[{"content":{"parts":[{"text":""}]},"finishReason":"STOP"}],"usageMetadata":{"promptTokenCount":10,"candidatesTokenCount":8}}"#;
         let events = t.transform_stream_chunk(chunk, &mut ctx).unwrap();
 
@@ -417,6 +697,173 @@ mod tests {
         assert_eq!(ctx.output_tokens, 8);
     }
 
+    /// 요청 변환: tools → functionDeclarations, tool_use/tool_result → functionCall/functionResponse
+    #[test]
+    fn test_transform_request_function_calling() {
+        let t = make_transformer();
+        let body = json!({
+            "model": "gemini-2.0-flash",
+            "max_tokens": 10,
+            "tools": [{
+                "name": "Bash",
+                "description": "셸 명령 실행",
+                "input_schema": {
+                    "$schema": "http://json-schema.org/draft-07/schema#",
+                    "type": "object",
+                    "additionalProperties": false,
+                    "properties": {"command": {"type": "string"}}
+                }
+            }],
+            "tool_choice": {"type": "tool", "name": "Bash"},
+            "messages": [
+                {"role": "user", "content": "ls 실행해줘"},
+                {"role": "assistant", "content": [
+                    {"type": "tool_use", "id": "toolu_1", "name": "Bash", "input": {"command": "ls"}}
+                ]},
+                {"role": "user", "content": [
+                    {"type": "tool_result", "tool_use_id": "toolu_1", "content": "a.txt"}
+                ]}
+            ]
+        });
+        let result = t.transform_request(body, None, false).unwrap();
+
+        let decl = &result.body["tools"][0]["functionDeclarations"][0];
+        assert_eq!(decl["name"], "Bash");
+        assert_eq!(decl["parameters"]["properties"]["command"]["type"], "string");
+        assert!(decl["parameters"].get("$schema").is_none());
+        assert!(decl["parameters"].get("additionalProperties").is_none());
+        assert_eq!(result.body["toolConfig"]["functionCallingConfig"]["mode"], "ANY");
+        assert_eq!(result.body["toolConfig"]["functionCallingConfig"]["allowedFunctionNames"][0], "Bash");
+
+        let contents = result.body["contents"].as_array().unwrap();
+        assert_eq!(contents[1]["parts"][0]["functionCall"]["name"], "Bash");
+        assert_eq!(contents[1]["parts"][0]["functionCall"]["args"]["command"], "ls");
+        // tool_use id → 함수 이름으로 역추적
+        assert_eq!(contents[2]["parts"][0]["functionResponse"]["name"], "Bash");
+        assert_eq!(contents[2]["parts"][0]["functionResponse"]["response"]["content"], "a.txt");
+    }
+
+    /// 응답 변환: functionCall → tool_use 블록
+    #[test]
+    fn test_transform_response_function_call() {
+        let t = make_transformer();
+        let body = json!({
+            "candidates": [{
+                "content": {"parts": [
+                    {"text": "읽어볼게요."},
+                    {"functionCall": {"name": "Read", "args": {"path": "a.rs"}}}
+                ]},
+                "finishReason": "STOP"
+            }],
+            "usageMetadata": {"promptTokenCount": 10, "candidatesTokenCount": 5}
+        });
+        let result = t.transform_response(body, "gemini-2.0-flash").unwrap();
+
+        let content = result["content"].as_array().unwrap();
+        assert_eq!(content.len(), 2);
+        assert_eq!(content[0]["text"], "읽어볼게요.");
+        assert_eq!(content[1]["type"], "tool_use");
+        assert_eq!(content[1]["name"], "Read");
+        assert_eq!(content[1]["input"]["path"], "a.rs");
+        assert!(content[1]["id"].as_str().unwrap().starts_with("toolu_"));
+        assert_eq!(result["stop_reason"], "tool_use");
+    }
+
+    /// SSE 변환: functionCall part → tool_use 블록 (시작 + input_json_delta + 종료)
+    #[test]
+    fn test_stream_chunk_function_call() {
+        let t = make_transformer();
+        let mut ctx = make_ctx();
+
+        let text = r#"{"candidates":[{"content":{"parts":[{"text":"확인"}]}}]}"#;
+        t.transform_stream_chunk(text, &mut ctx).unwrap();
+
+        let call = r#"{"candidates":[{"content":{"parts":[{"functionCall":{"name":"Bash","args":{"command":"ls"}}}]},"finishReason":"STOP"}]}"#;
+        let events = t.transform_stream_chunk(call, &mut ctx).unwrap();
+
+        assert!(events[0].contains("content_block_stop"));
+        assert!(events[1].contains("\"tool_use\""));
+        assert!(events[1].contains(r#""index":1"#));
+        assert!(events[2].contains("input_json_delta"));
+        assert!(events[3].contains("content_block_stop"));
+        assert!(events[4].contains(r#""stop_reason":"tool_use""#));
+        assert_eq!(events.len(), 5);
+    }
+
+    /// thoughtSignature: 응답/스트림의 functionCall → tool_use id → 다음 요청의 functionCall part로 복원
+    #[test]
+    fn test_thought_signature_round_trip() {
+        let t = make_transformer();
+        let signature = "CpQBAb4+9vs/AB+c1w==";
+        let body = json!({
+            "candidates": [{
+                "content": {"parts": [
+                    {"functionCall": {"name": "Read", "args": {"path": "a.rs"}}, "thoughtSignature": signature}
+                ]},
+                "finishReason": "STOP"
+            }]
+        });
+        let result = t.transform_response(body, "gemini-2.5-pro").unwrap();
+        let id = result["content"][0]["id"].as_str().unwrap().to_string();
+        assert!(id.starts_with("toolu_"));
+        assert!(id.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-'));
+
+        let mut ctx = make_ctx();
+        let chunk = json!({"candidates": [{"content": {"parts": [
+            {"functionCall": {"name": "Bash", "args": {}}, "thoughtSignature": signature}
+        ]}}]});
+        let events = t.transform_stream_chunk(&chunk.to_string(), &mut ctx).unwrap();
+        assert!(events[0].contains("__ts_CpQBAb4-9vs_AB-c1w\""));
+
+        let request = json!({
+            "model": "gemini-2.5-pro",
+            "messages": [
+                {"role": "user", "content": "a.rs 읽어줘"},
+                {"role": "assistant", "content": [{"type": "tool_use", "id": id, "name": "Read", "input": {"path": "a.rs"}}]},
+                {"role": "user", "content": [{"type": "tool_result", "tool_use_id": id, "content": "fn main() {}"}]},
+                {"role": "assistant", "content": [{"type": "tool_use", "id": "toolu_plain", "name": "Bash", "input": {}}]}
+            ]
+        });
+        let result = t.transform_request(request, None, false).unwrap();
+        let contents = result.body["contents"].as_array().unwrap();
+        assert_eq!(contents[1]["parts"][0]["thoughtSignature"], signature);
+        assert_eq!(contents[2]["parts"][0]["functionResponse"]["name"], "Read");
+        assert!(contents[3]["parts"][0].get("thoughtSignature").is_none());
+    }
+
+    /// 요청 변환: thinking → thinkingConfig
+    #[test]
+    fn test_transform_request_thinking_config() {
+        let t = make_transformer();
+        let body = json!({
+            "model": "gemini-2.5-pro",
+            "max_tokens": 10,
+            "thinking": {"type": "enabled", "budget_tokens": 2048},
+            "messages": []
+        });
+        let result = t.transform_request(body, None, false).unwrap();
+        let config = &result.body["generationConfig"]["thinkingConfig"];
+        assert_eq!(config["thinkingBudget"], 2048);
+        assert_eq!(config["includeThoughts"], true);
+    }
+
+    /// SSE 변환: thought part → thinking 블록, 이후 텍스트는 새 블록
+    #[test]
+    fn test_stream_chunk_thought() {
+        let t = make_transformer();
+        let mut ctx = make_ctx();
+
+        let chunk = r#"{"candidates":[{"content":{"parts":[{"text":"고민","thought":true},{"text":"답"}]}}]}"#;
+        let events = t.transform_stream_chunk(chunk, &mut ctx).unwrap();
+
+        assert_eq!(events.len(), 5);
+        assert!(events[0].contains(r#""type":"thinking""#));
+        assert!(events[1].contains("thinking_delta"));
+        assert!(events[2].contains("content_block_stop"));
+        assert!(events[3].contains(r#""type":"text""#));
+        assert!(events[4].contains(r#""index":1"#));
+    }
+
     /// 스트림 시작/종료 이벤트
     #[test]
     fn test_stream_start_end_events() {
@@ -428,6 +875,8 @@ mod tests {
         assert!(start[0].contains("message_start"));
         assert!(start[0].contains("msg_test456"));
 
+        // finish 이후 종료: message_stop만 전송
+        ctx.finish(Some("end_turn"));
         let end = t.stream_end_events(&mut ctx);
         assert_eq!(end.len(), 1);
         assert!(end[0].contains("message_stop"));
@@ -1,8 +1,23 @@
+pub mod bedrock;
+pub mod declarative;
+pub mod error;
+pub mod eventstream;
 pub mod gemini;
+pub mod ollama;
 pub mod openai;
+pub mod responses;
+pub mod schema;
+pub mod stream;
+pub mod tokens;
 
+use bytes::BytesMut;
 use std::fmt;
 
+use crate::config::{RouteConfig, TransformerConfig};
+
+pub use error::ApiError;
+pub use stream::StreamContext;
+
 /// 변환 오류
 #[derive(Debug)]
 pub struct TransformError(pub String);
@@ -22,14 +37,58 @@ pub struct TransformedRequest {
     pub extra_headers: Vec<(String, String)>,
 }
 
-/// SSE 스트림 상태 추적
-pub struct StreamContext {
-    pub model: String,
-    pub message_id: String,
-    pub input_tokens: u32,
-    pub output_tokens: u32,
-    pub block_index: u32,
-    pub started: bool,
+/// 업스트림 스트리밍 응답의 프레이밍
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StreamFraming {
+    /// Server-Sent Events (`data: {...}` 줄)
+    Sse,
+    /// 줄마다 JSON 객체 하나 (Ollama 등)
+    Ndjson,
+    /// AWS event-stream 바이너리 메시지 (Bedrock ConverseStream)
+    AwsEventStream,
+}
+
+impl StreamFraming {
+    /// 버퍼에서 완성된 프레임을 꺼내 페이로드 목록으로 반환
+    ///
+    /// `eof`가 true이면 줄 기반 프레이밍은 개행 없이 남은 마지막 줄도 처리
+    pub fn drain(&self, buf: &mut BytesMut, eof: bool) -> Vec<String> {
+        let mut payloads = Vec::new();
+        if *self == StreamFraming::AwsEventStream {
+            while let Some(payload) = eventstream::decode_message(buf) {
+                payloads.push(payload);
+            }
+            return payloads;
+        }
+
+        while let Some(pos) = buf.iter().position(|&b| b == b'\n') {
+            let line = buf.split_to(pos + 1);
+            payloads.extend(self.payload(&String::from_utf8_lossy(&line)).map(String::from));
+        }
+        if eof && !buf.is_empty() {
+            let line = buf.split();
+            payloads.extend(self.payload(&String::from_utf8_lossy(&line)).map(String::from));
+        }
+        payloads
+    }
+
+    /// 한 줄에서 변환할 페이로드 추출 (이벤트 이름/주석/빈 줄은 None)
+    pub fn payload<'a>(&self, line: &'a str) -> Option<&'a str> {
+        let line = line.trim();
+        let payload = match self {
+            StreamFraming::Sse => line.strip_prefix("data:")?.trim(),
+            StreamFraming::Ndjson | StreamFraming::AwsEventStream => line,
+        };
+        (!payload.is_empty()).then_some(payload)
+    }
+}
+
+/// Anthropic `thinking` 파라미터의 budget_tokens (비활성 시 None)
+pub fn thinking_budget(thinking: &serde_json::Value) -> Option<u64> {
+    if thinking["type"].as_str() != Some("enabled") {
+        return None;
+    }
+    Some(thinking["budget_tokens"].as_u64().unwrap_or(1024))
 }
 
 /// 프로토콜 변환 트레이트
@@ -56,18 +115,78 @@ pub trait Transformer: Send + Sync {
         ctx: &mut StreamContext,
     ) -> Result<Vec<String>, TransformError>;
 
+    /// 업스트림 에러 응답(4xx/5xx 또는 스트림 중간 에러 청크) → Anthropic 에러
+    fn transform_error(&self, status: u16, body: &[u8]) -> ApiError {
+        ApiError::new(status, error::error_message(body))
+    }
+
+    /// 업스트림 스트림 프레이밍 (기본: SSE)
+    fn stream_framing(&self) -> StreamFraming {
+        StreamFraming::Sse
+    }
+
     /// 스트림 시작 이벤트
-    fn stream_start_events(&self, ctx: &StreamContext) -> Vec<String>;
+    fn stream_start_events(&self, ctx: &StreamContext) -> Vec<String> {
+        ctx.message_start()
+    }
 
-    /// 스트림 종료 이벤트
-    fn stream_end_events(&self, ctx: &mut StreamContext) -> Vec<String>;
+    /// 스트림 종료 이벤트 (열린 블록/누락된 message_delta 보충 후 message_stop)
+    fn stream_end_events(&self, ctx: &mut StreamContext) -> Vec<String> {
+        ctx.message_stop()
+    }
 }
 
-/// 트랜스포머 팩토리
-pub fn create_transformer(name: &str) -> Option<Box<dyn Transformer>> {
+/// 내장 트랜스포머
+fn builtin_transformer(name: &str, route: &RouteConfig) -> Option<Box<dyn Transformer>> {
     match name {
-        "openai" => Some(Box::new(openai::OpenAITransformer)),
-        "gemini" => Some(Box::new(gemini::GeminiTransformer)),
+        "openai" => Some(Box::new(openai::OpenAITransformer::new(route.reasoning_effort.clone()))),
+        "gemini" => Some(Box::new(gemini::GeminiTransformer::new(route.upstream.vertex.clone()))),
+        "responses" => Some(Box::new(responses::ResponsesTransformer::new(route.reasoning_effort.clone()))),
+        "ollama" => Some(Box::new(ollama::OllamaTransformer)),
+        "bedrock" => Some(Box::new(bedrock::BedrockTransformer)),
         _ => None,
     }
 }
+
+/// 트랜스포머 팩토리
+///
+/// 설정의 `transformers:` 정의를 먼저 찾고, 없으면 내장 트랜스포머를 사용.
+/// `route`의 Vertex AI 설정(Gemini 경로 구성)과 reasoning_effort(추론 강도 전달)를 반영
+pub fn create_transformer(
+    name: &str,
+    defs: &[TransformerConfig],
+    route: &RouteConfig,
+) -> Option<Box<dyn Transformer>> {
+    if let Some(def) = defs.iter().find(|d| d.name == name) {
+        let base = builtin_transformer(&def.base, route)?;
+        return Some(Box::new(declarative::DeclarativeTransformer::new(base, def.clone())));
+    }
+    builtin_transformer(name, route)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// SSE는 data: 줄만, NDJSON은 비어 있지 않은 모든 줄
+    #[test]
+    fn test_stream_framing_payload() {
+        assert_eq!(StreamFraming::Sse.payload("data: {\"a\":1}\n"), Some("{\"a\":1}"));
+        assert_eq!(StreamFraming::Sse.payload("data:[DONE]"), Some("[DONE]"));
+        assert_eq!(StreamFraming::Sse.payload("event: message_start"), None);
+        assert_eq!(StreamFraming::Sse.payload(": keep-alive"), None);
+
+        assert_eq!(StreamFraming::Ndjson.payload("{\"done\":true}\r\n"), Some("{\"done\":true}"));
+        assert_eq!(StreamFraming::Ndjson.payload("  \n"), None);
+    }
+
+    /// drain: 완성된 줄만 꺼내고 eof 시 남은 줄 처리
+    #[test]
+    fn test_stream_framing_drain() {
+        let mut buf = BytesMut::from("data: {\"a\":1}\n\nevent: x\ndata: {\"b\"");
+        assert_eq!(StreamFraming::Sse.drain(&mut buf, false), vec!["{\"a\":1}"]);
+        buf.extend_from_slice(b":2}");
+        assert_eq!(StreamFraming::Sse.drain(&mut buf, true), vec!["{\"b\":2}"]);
+        assert!(buf.is_empty());
+    }
+}
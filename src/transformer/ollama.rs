@@ -0,0 +1,367 @@
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use super::gemini::tool_names;
+use super::openai::{join_text, tool_result_text};
+use super::{schema, StreamContext, StreamFraming, TransformError, TransformedRequest, Transformer};
+
+/// Ollama 네이티브 API (`/api/chat`) 트랜스포머
+///
+/// 스트리밍 응답은 SSE가 아닌 NDJSON (한 줄에 JSON 객체 하나)
+pub struct OllamaTransformer;
+
+/// Ollama done_reason → Anthropic stop_reason (None이면 tool_use 여부로 결정)
+fn map_done_reason(reason: &str) -> Option<&'static str> {
+    match reason {
+        "length" => Some("max_tokens"),
+        _ => None,
+    }
+}
+
+/// image / document 블록 → (images 항목, 텍스트)
+/// Ollama는 base64 이미지만 받으며 문서는 텍스트만 가능
+fn media(block: &Value) -> Result<(Option<String>, Option<String>), TransformError> {
+    let source = &block["source"];
+    match (block["type"].as_str(), source["type"].as_str()) {
+        (Some("image"), Some("base64")) => Ok((source["data"].as_str().map(String::from), None)),
+        (Some("document"), Some("text")) => Ok((None, source["data"].as_str().map(String::from))),
+        (Some("document"), Some("content")) => Ok((None, Some(tool_result_text(&source["content"])))),
+        (kind, source_type) => Err(TransformError(format!(
+            "Ollama는 {} 블록의 source 타입 {}을(를) 지원하지 않습니다",
+            kind.unwrap_or("unknown"),
+            source_type.unwrap_or("unknown"),
+        ))),
+    }
+}
+
+/// Anthropic 메시지 1개 → Ollama 메시지들
+/// - assistant tool_use → tool_calls (arguments는 객체)
+/// - user tool_result → role: tool 메시지 (tool_name 필요)
+fn convert_message(
+    msg: &Value,
+    names: &std::collections::HashMap<String, String>,
+) -> Result<Vec<Value>, TransformError> {
+    let role = msg["role"].as_str().unwrap_or("user");
+    let blocks = match &msg["content"] {
+        Value::Array(arr) => arr,
+        other => return Ok(vec![json!({"role": role, "content": other})]),
+    };
+
+    if role == "assistant" {
+        let mut out = json!({"role": "assistant", "content": join_text(blocks)});
+        let thinking: Vec<&str> = blocks
+            .iter()
+            .filter(|b| b["type"] == "thinking")
+            .filter_map(|b| b["thinking"].as_str())
+            .collect();
+        if !thinking.is_empty() {
+            out["thinking"] = json!(thinking.join("\n"));
+        }
+        let tool_calls: Vec<Value> = blocks
+            .iter()
+            .filter(|b| b["type"] == "tool_use")
+            .map(|b| json!({"function": {"name": b["name"], "arguments": b.get("input").unwrap_or(&json!({}))}}))
+            .collect();
+        if !tool_calls.is_empty() {
+            out["tool_calls"] = json!(tool_calls);
+        }
+        return Ok(vec![out]);
+    }
+
+    let mut out = Vec::new();
+    let mut texts = Vec::new();
+    let mut images = Vec::new();
+    for block in blocks {
+        match block["type"].as_str().unwrap_or("text") {
+            "tool_result" => {
+                let mut text = tool_result_text(&block["content"]);
+                if block["is_error"].as_bool() == Some(true) {
+                    text = format!("[error] {text}");
+                }
+                let name = block["tool_use_id"]
+                    .as_str()
+                    .and_then(|id| names.get(id))
+                    .map(String::as_str)
+                    .unwrap_or("");
+                out.push(json!({"role": "tool", "content": text, "tool_name": name}));
+                for nested in block["content"].as_array().into_iter().flatten() {
+                    if nested["type"] == "image" {
+                        let (image, _) = media(nested)?;
+                        images.extend(image);
+                    }
+                }
+            }
+            "text" => texts.extend(block["text"].as_str().map(String::from)),
+            "image" | "document" => {
+                let (image, text) = media(block)?;
+                images.extend(image);
+                texts.extend(text);
+            }
+            _ => {}
+        }
+    }
+
+    if !texts.is_empty() || !images.is_empty() || out.is_empty() {
+        let mut user = json!({"role": role, "content": texts.join("\n")});
+        if !images.is_empty() {
+            user["images"] = json!(images);
+        }
+        out.push(user);
+    }
+    Ok(out)
+}
+
+/// Anthropic tool 정의 → Ollama function 정의
+fn convert_tool(tool: &Value) -> Value {
+    json!({
+        "type": "function",
+        "function": {
+            "name": tool["name"],
+            "description": tool["description"].as_str().unwrap_or(""),
+            "parameters": tool
+                .get("input_schema")
+                .map(|s| schema::sanitize(s, &schema::OPENAI))
+                .unwrap_or_else(|| json!({"type": "object"})),
+        }
+    })
+}
+
+impl Transformer for OllamaTransformer {
+    fn transform_request(
+        &self,
+        body: Value,
+        model_map: Option<&str>,
+        is_stream: bool,
+    ) -> Result<TransformedRequest, TransformError> {
+        let messages = body["messages"].as_array().map(Vec::as_slice).unwrap_or_default();
+        let names = tool_names(messages);
+
+        let mut converted = Vec::new();
+        match &body["system"] {
+            Value::String(s) => converted.push(json!({"role": "system", "content": s})),
+            Value::Array(blocks) => converted.push(json!({"role": "system", "content": join_text(blocks)})),
+            _ => {}
+        }
+        for msg in messages {
+            converted.extend(convert_message(msg, &names)?);
+        }
+
+        let model = model_map.or_else(|| body["model"].as_str()).unwrap_or_default();
+        // Ollama는 stream 기본값이 true이므로 항상 명시
+        let mut req = json!({
+            "model": model,
+            "messages": converted,
+            "stream": is_stream,
+        });
+
+        // 샘플링 파라미터 → options
+        let mut options = serde_json::Map::new();
+        for (from, to) in [
+            ("max_tokens", "num_predict"),
+            ("temperature", "temperature"),
+            ("top_p", "top_p"),
+            ("top_k", "top_k"),
+            ("stop_sequences", "stop"),
+        ] {
+            if let Some(v) = body.get(from) {
+                options.insert(to.into(), v.clone());
+            }
+        }
+        req["options"] = Value::Object(options);
+
+        if let Some(tools) = body["tools"].as_array().filter(|t| !t.is_empty()) {
+            req["tools"] = Value::Array(tools.iter().map(convert_tool).collect());
+        }
+
+        // thinking → think (추론 모델만 적용, 그 외 모델은 Ollama가 무시)
+        if let Some(kind) = body["thinking"]["type"].as_str() {
+            req["think"] = json!(kind == "enabled");
+        }
+
+        Ok(TransformedRequest {
+            path: "/api/chat".to_string(),
+            body: req,
+            extra_headers: vec![],
+        })
+    }
+
+    fn transform_response(&self, body: Value, model: &str) -> Result<Value, TransformError> {
+        let message = &body["message"];
+        let mut content = Vec::new();
+        if let Some(thinking) = message["thinking"].as_str().filter(|t| !t.is_empty()) {
+            content.push(json!({"type": "thinking", "thinking": thinking, "signature": ""}));
+        }
+        let text = message["content"].as_str().unwrap_or("");
+        let tool_calls = message["tool_calls"].as_array();
+        if !text.is_empty() || tool_calls.is_none_or(|c| c.is_empty()) {
+            content.push(json!({"type": "text", "text": text}));
+        }
+        for call in tool_calls.into_iter().flatten() {
+            content.push(json!({
+                "type": "tool_use",
+                "id": call["id"].as_str().map(String::from).unwrap_or_else(|| format!("toolu_{}", Uuid::new_v4().simple())),
+                "name": call["function"]["name"],
+                "input": call["function"]["arguments"],
+            }));
+        }
+
+        let tool_used = tool_calls.is_some_and(|c| !c.is_empty());
+        let stop_reason = map_done_reason(body["done_reason"].as_str().unwrap_or(""))
+            .unwrap_or(if tool_used { "tool_use" } else { "end_turn" });
+
+        Ok(json!({
+            "id": format!("msg_{}", Uuid::new_v4().simple()),
+            "type": "message",
+            "role": "assistant",
+            "model": model,
+            "content": content,
+            "stop_reason": stop_reason,
+            "stop_sequence": null,
+            "usage": {
+                "input_tokens": body["prompt_eval_count"].as_u64().unwrap_or(0),
+                "output_tokens": body["eval_count"].as_u64().unwrap_or(0),
+            }
+        }))
+    }
+
+    fn transform_stream_chunk(
+        &self,
+        chunk: &str,
+        ctx: &mut StreamContext,
+    ) -> Result<Vec<String>, TransformError> {
+        let data: Value = serde_json::from_str(chunk)
+            .map_err(|e| TransformError(format!("NDJSON 파싱 실패: {e}")))?;
+
+        if data.get("error").is_some_and(|e| !e.is_null()) {
+            return Ok(ctx.error(&self.transform_error(500, chunk.as_bytes())));
+        }
+
+        let mut events = Vec::new();
+        let message = &data["message"];
+        if let Some(thinking) = message["thinking"].as_str().filter(|t| !t.is_empty()) {
+            events.extend(ctx.thinking_delta(thinking));
+        }
+        if let Some(text) = message["content"].as_str().filter(|t| !t.is_empty()) {
+            events.extend(ctx.text_delta(text));
+        }
+
+        // tool_calls는 한 청크에 완성된 형태로 도착
+        for call in message["tool_calls"].as_array().into_iter().flatten() {
+            let id = call["id"]
+                .as_str()
+                .map(String::from)
+                .unwrap_or_else(|| format!("toolu_{}", Uuid::new_v4().simple()));
+            let call_id = u64::from(ctx.block_index);
+            events.extend(ctx.open_tool_block(call_id, &id, call["function"]["name"].as_str().unwrap_or("")));
+            events.extend(ctx.tool_input_delta(&call["function"]["arguments"].to_string()));
+            events.extend(ctx.close_block());
+        }
+
+        if data["done"].as_bool() == Some(true) {
+            ctx.set_usage(data["prompt_eval_count"].as_u64(), data["eval_count"].as_u64(), None);
+            events.extend(ctx.finish(map_done_reason(data["done_reason"].as_str().unwrap_or(""))));
+        }
+
+        Ok(events)
+    }
+
+    fn stream_framing(&self) -> StreamFraming {
+        StreamFraming::Ndjson
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_transformer() -> OllamaTransformer {
+        OllamaTransformer
+    }
+
+    /// 요청 변환: system/샘플링 옵션/tool 왕복/이미지
+    #[test]
+    fn test_transform_request() {
+        let t = make_transformer();
+        let body = json!({
+            "model": "claude-sonnet",
+            "system": "간결하게",
+            "max_tokens": 512,
+            "temperature": 0.2,
+            "thinking": {"type": "enabled", "budget_tokens": 2048},
+            "tools": [{"name": "Bash", "input_schema": {"type": "object"}}],
+            "messages": [
+                {"role": "user", "content": [
+                    {"type": "text", "text": "이 그림은?"},
+                    {"type": "image", "source": {"type": "base64", "media_type": "image/png", "data": "iVBOR"}}
+                ]},
+                {"role": "assistant", "content": [
+                    {"type": "tool_use", "id": "toolu_1", "name": "Bash", "input": {"command": "ls"}}
+                ]},
+                {"role": "user", "content": [
+                    {"type": "tool_result", "tool_use_id": "toolu_1", "content": "a.txt"}
+                ]}
+            ]
+        });
+        let result = t.transform_request(body, Some("qwen3:32b"), false).unwrap();
+        let b = &result.body;
+
+        assert_eq!(result.path, "/api/chat");
+        assert_eq!(b["model"], "qwen3:32b");
+        assert_eq!(b["stream"], false);
+        assert_eq!(b["think"], true);
+        assert_eq!(b["options"]["num_predict"], 512);
+        assert_eq!(b["options"]["temperature"], 0.2);
+        assert_eq!(b["tools"][0]["function"]["name"], "Bash");
+
+        let msgs = b["messages"].as_array().unwrap();
+        assert_eq!(msgs[0]["role"], "system");
+        assert_eq!(msgs[1]["images"][0], "iVBOR");
+        assert_eq!(msgs[2]["tool_calls"][0]["function"]["arguments"]["command"], "ls");
+        assert_eq!(msgs[3]["role"], "tool");
+        assert_eq!(msgs[3]["tool_name"], "Bash");
+    }
+
+    /// 응답 변환: thinking + tool_calls, 토큰 수
+    #[test]
+    fn test_transform_response() {
+        let t = make_transformer();
+        let body = json!({
+            "message": {
+                "role": "assistant",
+                "content": "",
+                "thinking": "ls 필요",
+                "tool_calls": [{"function": {"name": "Bash", "arguments": {"command": "ls"}}}]
+            },
+            "done": true,
+            "done_reason": "stop",
+            "prompt_eval_count": 30,
+            "eval_count": 8
+        });
+        let result = t.transform_response(body, "claude-sonnet").unwrap();
+        assert_eq!(result["content"][0]["type"], "thinking");
+        assert_eq!(result["content"][1]["type"], "tool_use");
+        assert_eq!(result["content"][1]["input"]["command"], "ls");
+        assert_eq!(result["stop_reason"], "tool_use");
+        assert_eq!(result["usage"]["input_tokens"], 30);
+    }
+
+    /// NDJSON 스트림: 텍스트 델타 → done
+    #[test]
+    fn test_stream_chunks() {
+        let t = make_transformer();
+        let mut ctx = StreamContext::new("claude-sonnet", "msg_1");
+
+        let events = t
+            .transform_stream_chunk(r#"{"message":{"role":"assistant","content":"안녕"},"done":false}"#, &mut ctx)
+            .unwrap();
+        assert_eq!(events.len(), 2);
+
+        let done = r#"{"message":{"role":"assistant","content":""},"done":true,"done_reason":"length","prompt_eval_count":12,"eval_count":40}"#;
+        let events = t.transform_stream_chunk(done, &mut ctx).unwrap();
+        assert!(events[0].contains("content_block_stop"));
+        assert!(events[1].contains(r#""stop_reason":"max_tokens""#));
+        assert!(events[1].contains(r#""output_tokens":40"#));
+
+        assert_eq!(t.stream_framing(), StreamFraming::Ndjson);
+    }
+}
@@ -1,16 +1,232 @@
 use serde_json::{json, Value};
 use uuid::Uuid;
 
-use super::{StreamContext, TransformError, TransformedRequest, Transformer};
+use super::{error, schema, thinking_budget, ApiError, StreamContext, TransformError, TransformedRequest, Transformer};
+use crate::config::ReasoningEffort;
 
-pub struct OpenAITransformer;
+#[derive(Default)]
+pub struct OpenAITransformer {
+    /// thinking 요청에 reasoning_effort를 보낼지 (라우트 설정)
+    reasoning_effort: ReasoningEffort,
+}
+
+impl OpenAITransformer {
+    pub fn new(reasoning_effort: ReasoningEffort) -> Self {
+        OpenAITransformer { reasoning_effort }
+    }
+}
+
+/// OpenAI finish_reason → Anthropic stop_reason
+fn map_finish_reason(reason: &str) -> &str {
+    match reason {
+        "stop" => "end_turn",
+        "length" => "max_tokens",
+        "tool_calls" | "function_call" => "tool_use",
+        other => other,
+    }
+}
+
+/// 추론 텍스트 (DeepSeek/Kimi/GLM: reasoning_content, OpenRouter 등: reasoning)
+fn reasoning_text(value: &Value) -> Option<&str> {
+    value["reasoning_content"]
+        .as_str()
+        .or_else(|| value["reasoning"].as_str())
+        .filter(|s| !s.is_empty())
+}
+
+/// OpenAI usage → (입력, 출력, 캐시 적중) 토큰 수
+///
+/// prompt_tokens는 캐시 적중분을 포함하므로 Anthropic input_tokens에서는 제외한다.
+/// 캐시 적중 수: OpenAI `prompt_tokens_details.cached_tokens`, DeepSeek `prompt_cache_hit_tokens`
+fn usage_tokens(usage: &Value) -> (u64, u64, u64) {
+    let prompt = usage["prompt_tokens"].as_u64().unwrap_or(0);
+    let completion = usage["completion_tokens"].as_u64().unwrap_or(0);
+    let cached = usage["prompt_tokens_details"]["cached_tokens"]
+        .as_u64()
+        .or_else(|| usage["prompt_cache_hit_tokens"].as_u64())
+        .unwrap_or(0);
+    (prompt.saturating_sub(cached), completion, cached)
+}
+
+/// content 블록 배열에서 텍스트만 이어붙임
+pub(super) fn join_text(blocks: &[Value]) -> String {
+    blocks
+        .iter()
+        .filter(|b| b["type"].as_str().unwrap_or("text") == "text")
+        .filter_map(|b| b["text"].as_str())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// tool_result의 content (문자열 또는 블록 배열) → 문자열
+pub(super) fn tool_result_text(content: &Value) -> String {
+    match content {
+        Value::String(s) => s.clone(),
+        Value::Array(arr) => join_text(arr),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/// image / document 블록 → OpenAI content part
+/// - image: base64 → data URI, url → 그대로 `image_url`
+/// - document: PDF base64 → `file` part, 텍스트 문서 → `text` part
+fn media_part(block: &Value) -> Result<Value, TransformError> {
+    let source = &block["source"];
+    let media_type = source["media_type"].as_str().unwrap_or("");
+    match (block["type"].as_str(), source["type"].as_str()) {
+        (Some("image"), Some("base64")) => Ok(json!({
+            "type": "image_url",
+            "image_url": {"url": format!("data:{};base64,{}", media_type, source["data"].as_str().unwrap_or(""))}
+        })),
+        (Some("image"), Some("url")) => Ok(json!({
+            "type": "image_url",
+            "image_url": {"url": source["url"]}
+        })),
+        (Some("document"), Some("base64")) if media_type == "application/pdf" => Ok(json!({
+            "type": "file",
+            "file": {
+                "filename": block["title"].as_str().unwrap_or("document.pdf"),
+                "file_data": format!("data:application/pdf;base64,{}", source["data"].as_str().unwrap_or("")),
+            }
+        })),
+        (Some("document"), Some("text")) => Ok(json!({"type": "text", "text": source["data"]})),
+        (Some("document"), Some("content")) => Ok(json!({
+            "type": "text",
+            "text": tool_result_text(&source["content"]),
+        })),
+        (kind, source_type) => Err(TransformError(format!(
+            "OpenAI 호환 제공자는 {} 블록의 source 타입 {}을(를) 지원하지 않습니다",
+            kind.unwrap_or("unknown"),
+            source_type.unwrap_or("unknown"),
+        ))),
+    }
+}
+
+/// 블록 배열 → OpenAI content (텍스트만 있으면 문자열, 이미지/문서가 있으면 part 배열)
+fn user_content(blocks: &[&Value]) -> Result<Value, TransformError> {
+    let mut parts = Vec::new();
+    for block in blocks {
+        match block["type"].as_str().unwrap_or("text") {
+            "text" => {
+                if let Some(text) = block["text"].as_str() {
+                    parts.push(json!({"type": "text", "text": text}));
+                }
+            }
+            "image" | "document" => parts.push(media_part(block)?),
+            _ => {}
+        }
+    }
+
+    if parts.iter().all(|p| p["type"] == "text") {
+        let text: Vec<&str> = parts.iter().filter_map(|p| p["text"].as_str()).collect();
+        return Ok(json!(text.join("\n")));
+    }
+    Ok(Value::Array(parts))
+}
+
+/// Anthropic 메시지 1개 → OpenAI 메시지 (tool_result는 별도 role: tool 메시지로 분리)
+fn convert_message(msg: &Value) -> Result<Vec<Value>, TransformError> {
+    let role = msg["role"].as_str().unwrap_or("user");
+    let blocks = match &msg["content"] {
+        Value::Array(arr) => arr,
+        _ => return Ok(vec![msg.clone()]),
+    };
+
+    if role == "assistant" {
+        let tool_calls: Vec<Value> = blocks
+            .iter()
+            .filter(|b| b["type"] == "tool_use")
+            .map(|b| {
+                json!({
+                    "id": b["id"],
+                    "type": "function",
+                    "function": {
+                        "name": b["name"],
+                        "arguments": b.get("input").unwrap_or(&json!({})).to_string(),
+                    }
+                })
+            })
+            .collect();
+
+        let text = join_text(blocks);
+        let mut out = json!({"role": "assistant"});
+        out["content"] = if text.is_empty() && !tool_calls.is_empty() {
+            Value::Null
+        } else {
+            json!(text)
+        };
+        if !tool_calls.is_empty() {
+            out["tool_calls"] = json!(tool_calls);
+        }
+        return Ok(vec![out]);
+    }
+
+    // user: tool_result는 직전 assistant의 tool_calls 바로 뒤에 와야 하므로 먼저 배치
+    // tool 메시지는 텍스트만 허용하므로 tool_result 안의 이미지는 뒤따르는 user 메시지로 옮김
+    let mut out = Vec::new();
+    let mut rest: Vec<&Value> = Vec::new();
+    for block in blocks {
+        if block["type"] != "tool_result" {
+            rest.push(block);
+            continue;
+        }
+        let mut text = tool_result_text(&block["content"]);
+        if block["is_error"].as_bool() == Some(true) {
+            text = format!("[error] {text}");
+        }
+        out.push(json!({
+            "role": "tool",
+            "tool_call_id": block["tool_use_id"],
+            "content": text,
+        }));
+        if let Some(nested) = block["content"].as_array() {
+            rest.extend(nested.iter().filter(|b| b["type"] == "image" || b["type"] == "document"));
+        }
+    }
+
+    let content = user_content(&rest)?;
+    if content.as_str().is_none_or(|t| !t.is_empty()) || out.is_empty() {
+        out.push(json!({"role": role, "content": content}));
+    }
+    Ok(out)
+}
+
+/// Anthropic tool 정의 → OpenAI function 정의
+fn convert_tool(tool: &Value) -> Value {
+    let mut function = json!({
+        "name": tool["name"],
+        "parameters": tool
+            .get("input_schema")
+            .map(|s| schema::sanitize(s, &schema::OPENAI))
+            .unwrap_or_else(|| json!({"type": "object"})),
+    });
+    if let Some(desc) = tool.get("description") {
+        function["description"] = desc.clone();
+    }
+    json!({"type": "function", "function": function})
+}
+
+/// Anthropic tool_choice → OpenAI tool_choice
+fn convert_tool_choice(choice: &Value) -> Option<Value> {
+    match choice["type"].as_str()? {
+        "auto" => Some(json!("auto")),
+        "any" => Some(json!("required")),
+        "none" => Some(json!("none")),
+        "tool" => Some(json!({
+            "type": "function",
+            "function": {"name": choice["name"]}
+        })),
+        _ => None,
+    }
+}
 
 impl Transformer for OpenAITransformer {
     fn transform_request(
         &self,
         mut body: Value,
         model_map: Option<&str>,
-        _is_stream: bool,
+        is_stream: bool,
     ) -> Result<TransformedRequest, TransformError> {
         let obj = body.as_object_mut().ok_or_else(|| {
             TransformError("요청 본문이 JSON 객체가 아닙니다".into())
@@ -21,6 +237,15 @@ impl Transformer for OpenAITransformer {
             obj.insert("model".into(), json!(mapped));
         }
 
+        // content 블록 배열 → OpenAI 메시지 (tool_use → tool_calls, tool_result → role: tool)
+        if let Some(messages) = obj.remove("messages") {
+            let mut converted = Vec::new();
+            for msg in messages.as_array().into_iter().flatten() {
+                converted.extend(convert_message(msg)?);
+            }
+            obj.insert("messages".into(), Value::Array(converted));
+        }
+
         // system 필드 → messages 첫 항목으로 이동
         if let Some(system) = obj.remove("system") {
             let system_msg = json!({"role": "system", "content": system});
@@ -39,8 +264,40 @@ impl Transformer for OpenAITransformer {
             obj.insert("stop".into(), stop);
         }
 
+        // tools → function 정의
+        if let Some(tools) = obj.remove("tools") {
+            let functions: Vec<Value> = tools
+                .as_array()
+                .map(|arr| arr.iter().map(convert_tool).collect())
+                .unwrap_or_default();
+            if !functions.is_empty() {
+                obj.insert("tools".into(), Value::Array(functions));
+            }
+        }
+
+        // tool_choice 변환 (disable_parallel_tool_use → parallel_tool_calls)
+        if let Some(choice) = obj.remove("tool_choice") {
+            if choice["disable_parallel_tool_use"].as_bool() == Some(true) {
+                obj.insert("parallel_tool_calls".into(), json!(false));
+            }
+            if let Some(mapped) = convert_tool_choice(&choice) {
+                obj.insert("tool_choice".into(), mapped);
+            }
+        }
+
+        // thinking.budget_tokens → reasoning_effort (라우트에서 켠 경우만)
+        let effort = obj.get("thinking").and_then(thinking_budget).and_then(|b| self.reasoning_effort.level(b));
+        if let Some(effort) = effort {
+            obj.insert("reasoning_effort".into(), json!(effort));
+        }
+
+        // 스트리밍 시 마지막 청크로 실제 usage 수신
+        if is_stream {
+            obj.insert("stream_options".into(), json!({"include_usage": true}));
+        }
+
         // Anthropic 전용 필드 제거
-        for key in &["top_k", "metadata", "anthropic_version"] {
+        for key in &["top_k", "metadata", "anthropic_version", "thinking"] {
             obj.remove(*key);
         }
 
@@ -56,55 +313,62 @@ impl Transformer for OpenAITransformer {
         body: Value,
         model: &str,
     ) -> Result<Value, TransformError> {
-        let text = body["choices"]
-            .get(0)
-            .and_then(|c| c["message"]["content"].as_str())
-            .unwrap_or("");
+        let message = &body["choices"][0]["message"];
+        let text = message["content"].as_str().unwrap_or("");
 
         let finish = body["choices"]
             .get(0)
             .and_then(|c| c["finish_reason"].as_str())
             .unwrap_or("stop");
 
-        let stop_reason = match finish {
-            "stop" => "end_turn",
-            "length" => "max_tokens",
-            other => other,
-        };
+        let stop_reason = map_finish_reason(finish);
 
-        let input_tokens = body["usage"]["prompt_tokens"].as_u64().unwrap_or(0);
-        let output_tokens = body["usage"]["completion_tokens"].as_u64().unwrap_or(0);
+        // 추론 + 텍스트 + tool_calls → content 블록
+        let mut content = Vec::new();
+        if let Some(reasoning) = reasoning_text(message) {
+            content.push(json!({"type": "thinking", "thinking": reasoning, "signature": ""}));
+        }
+        let tool_calls = message["tool_calls"].as_array();
+        if !text.is_empty() || tool_calls.is_none_or(|c| c.is_empty()) {
+            content.push(json!({"type": "text", "text": text}));
+        }
+        for call in tool_calls.into_iter().flatten() {
+            let args = call["function"]["arguments"].as_str().unwrap_or("");
+            let input: Value = if args.trim().is_empty() {
+                json!({})
+            } else {
+                serde_json::from_str(args).map_err(|e| {
+                    TransformError(format!("tool_calls arguments 파싱 실패: {e}"))
+                })?
+            };
+            content.push(json!({
+                "type": "tool_use",
+                "id": call["id"],
+                "name": call["function"]["name"],
+                "input": input,
+            }));
+        }
+
+        let (input_tokens, output_tokens, cached_tokens) = usage_tokens(&body["usage"]);
 
         Ok(json!({
             "id": format!("msg_{}", Uuid::new_v4().simple()),
             "type": "message",
             "role": "assistant",
             "model": model,
-            "content": [{"type": "text", "text": text}],
+            "content": content,
             "stop_reason": stop_reason,
             "stop_sequence": null,
             "usage": {
                 "input_tokens": input_tokens,
                 "output_tokens": output_tokens,
+                "cache_read_input_tokens": cached_tokens,
             }
         }))
     }
 
-    fn stream_start_events(&self, ctx: &StreamContext) -> Vec<String> {
-        let msg_start = json!({
-            "type": "message_start",
-            "message": {
-                "id": &ctx.message_id,
-                "type": "message",
-                "role": "assistant",
-                "model": &ctx.model,
-                "content": [],
-                "stop_reason": null,
-                "stop_sequence": null,
-                "usage": {"input_tokens": 0, "output_tokens": 0}
-            }
-        });
-        vec![format!("event: message_start\ndata: {}\n\n", msg_start)]
+    fn transform_error(&self, status: u16, body: &[u8]) -> ApiError {
+        error::from_openai(status, body)
     }
 
     fn transform_stream_chunk(
@@ -120,87 +384,66 @@ impl Transformer for OpenAITransformer {
         let data: Value = serde_json::from_str(chunk)
             .map_err(|e| TransformError(format!("SSE JSON 파싱 실패: {e}")))?;
 
+        // 스트림 중간 에러 청크
+        if data.get("error").is_some_and(|e| !e.is_null()) {
+            return Ok(ctx.error(&self.transform_error(500, chunk.as_bytes())));
+        }
+
         let mut events = Vec::new();
         let choice = &data["choices"][0];
+        let delta = &choice["delta"];
 
-        // 첫 번째 청크: content_block_start
-        if !ctx.started {
-            ctx.started = true;
-            let block_start = json!({
-                "type": "content_block_start",
-                "index": ctx.block_index,
-                "content_block": {"type": "text", "text": ""}
-            });
-            events.push(format!("event: content_block_start\ndata: {}\n\n", block_start));
+        // 추론 델타 → thinking 블록
+        if let Some(reasoning) = reasoning_text(delta) {
+            events.extend(ctx.thinking_delta(reasoning));
         }
 
         // 텍스트 델타
-        if let Some(content) = choice["delta"]["content"].as_str() {
+        if let Some(content) = delta["content"].as_str() {
             if !content.is_empty() {
-                ctx.output_tokens += 1; // 근사치
-                let delta = json!({
-                    "type": "content_block_delta",
-                    "index": ctx.block_index,
-                    "delta": {"type": "text_delta", "text": content}
-                });
-                events.push(format!("event: content_block_delta\ndata: {}\n\n", delta));
+                events.extend(ctx.text_delta(content));
             }
         }
 
-        // finish_reason이 있으면 종료 이벤트 생성
-        if let Some(reason) = choice["finish_reason"].as_str() {
-            let stop_reason = match reason {
-                "stop" => "end_turn",
-                "length" => "max_tokens",
-                other => other,
-            };
+        // tool_calls 델타 → tool_use 블록 + input_json_delta
+        for call in delta["tool_calls"].as_array().into_iter().flatten() {
+            events.extend(ctx.tool_call_delta(
+                call["index"].as_u64().unwrap_or(0),
+                call["id"].as_str(),
+                call["function"]["name"].as_str(),
+                call["function"]["arguments"].as_str().unwrap_or(""),
+            ));
+        }
 
-            // usage 정보 (존재 시)
-            if let Some(usage) = data.get("usage") {
-                ctx.input_tokens = usage["prompt_tokens"].as_u64().unwrap_or(0) as u32;
-                ctx.output_tokens = usage["completion_tokens"].as_u64().unwrap_or(0) as u32;
-            }
+        // usage 정보 (include_usage: finish_reason 이후 choices가 빈 청크로 도착)
+        if let Some(usage) = data.get("usage").filter(|u| u.is_object()) {
+            let (input, output, cached) = usage_tokens(usage);
+            ctx.set_usage(Some(input), Some(output), Some(cached));
+        }
 
-            let block_stop = json!({
-                "type": "content_block_stop",
-                "index": ctx.block_index,
-            });
-            events.push(format!("event: content_block_stop\ndata: {}\n\n", block_stop));
-
-            let msg_delta = json!({
-                "type": "message_delta",
-                "delta": {"stop_reason": stop_reason, "stop_sequence": null},
-                "usage": {"output_tokens": ctx.output_tokens}
-            });
-            events.push(format!("event: message_delta\ndata: {}\n\n", msg_delta));
+        // finish_reason이 있으면 블록 종료, usage가 확보되면 message_delta 전송
+        if let Some(reason) = choice["finish_reason"].as_str() {
+            events.extend(ctx.stop(map_finish_reason(reason)));
+        }
+        if ctx.stop_reason.is_some() && ctx.usage_reported {
+            events.extend(ctx.finish(None));
         }
 
         Ok(events)
     }
-
-    fn stream_end_events(&self, _ctx: &mut StreamContext) -> Vec<String> {
-        let msg_stop = json!({"type": "message_stop"});
-        vec![format!("event: message_stop\ndata: {}\n\n", msg_stop)]
-    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::transformer::stream::BlockKind;
 
     fn make_transformer() -> OpenAITransformer {
-        OpenAITransformer
+        OpenAITransformer::default()
     }
 
     fn make_ctx() -> StreamContext {
-        StreamContext {
-            model: "gpt-4o".into(),
-            message_id: "msg_test123".into(),
-            input_tokens: 0,
-            output_tokens: 0,
-            block_index: 0,
-            started: false,
-        }
+        StreamContext::new("gpt-4o", "msg_test123")
     }
 
     /// 요청 변환: system → messages, max_tokens → max_completion_tokens
@@ -266,6 +509,68 @@ mod tests {
         assert_eq!(result.body["stop"][0], "END");
     }
 
+    /// 요청 변환: image → image_url data URI, PDF document → file part
+    #[test]
+    fn test_transform_request_images_and_documents() {
+        let t = make_transformer();
+        let body = json!({
+            "model": "gpt-4o",
+            "max_tokens": 10,
+            "messages": [{"role": "user", "content": [
+                {"type": "text", "text": "이 화면 봐줘"},
+                {"type": "image", "source": {"type": "base64", "media_type": "image/png", "data": "iVBOR"}},
+                {"type": "image", "source": {"type": "url", "url": "https://example.com/a.png"}},
+                {"type": "document", "source": {"type": "base64", "media_type": "application/pdf", "data": "JVBER"}}
+            ]}]
+        });
+        let result = t.transform_request(body, None, false).unwrap();
+        let parts = result.body["messages"][0]["content"].as_array().unwrap();
+
+        assert_eq!(parts.len(), 4);
+        assert_eq!(parts[0]["type"], "text");
+        assert_eq!(parts[1]["image_url"]["url"], "data:image/png;base64,iVBOR");
+        assert_eq!(parts[2]["image_url"]["url"], "https://example.com/a.png");
+        assert_eq!(parts[3]["type"], "file");
+        assert_eq!(parts[3]["file"]["file_data"], "data:application/pdf;base64,JVBER");
+    }
+
+    /// 요청 변환: tool_result 안의 이미지는 뒤따르는 user 메시지로 이동
+    #[test]
+    fn test_transform_request_tool_result_image() {
+        let t = make_transformer();
+        let body = json!({
+            "model": "gpt-4o",
+            "max_tokens": 10,
+            "messages": [{"role": "user", "content": [
+                {"type": "tool_result", "tool_use_id": "toolu_1", "content": [
+                    {"type": "image", "source": {"type": "base64", "media_type": "image/jpeg", "data": "/9j/"}}
+                ]}
+            ]}]
+        });
+        let result = t.transform_request(body, None, false).unwrap();
+        let msgs = result.body["messages"].as_array().unwrap();
+
+        assert_eq!(msgs.len(), 2);
+        assert_eq!(msgs[0]["role"], "tool");
+        assert_eq!(msgs[1]["role"], "user");
+        assert_eq!(msgs[1]["content"][0]["image_url"]["url"], "data:image/jpeg;base64,/9j/");
+    }
+
+    /// 요청 변환: 네이티브 형식이 없는 PDF URL은 명확한 오류
+    #[test]
+    fn test_transform_request_unsupported_document() {
+        let t = make_transformer();
+        let body = json!({
+            "model": "gpt-4o",
+            "max_tokens": 10,
+            "messages": [{"role": "user", "content": [
+                {"type": "document", "source": {"type": "url", "url": "https://example.com/a.pdf"}}
+            ]}]
+        });
+        let err = t.transform_request(body, None, false).err().unwrap();
+        assert!(err.0.contains("document"));
+    }
+
     /// 응답 변환: OpenAI → Anthropic
     #[test]
     fn test_transform_response() {
@@ -306,7 +611,7 @@ mod tests {
         let chunk = r#"{"choices":[{"delta":{"content":"안녕"},"finish_reason":null}]}"#;
         let events = t.transform_stream_chunk(chunk, &mut ctx).unwrap();
 
-        assert!(ctx.started);
+        assert_eq!(ctx.open_block, Some(BlockKind::Text));
         assert_eq!(events.len(), 2); // block_start + delta
         assert!(events[0].contains("content_block_start"));
         assert!(events[1].contains("text_delta"));
@@ -327,7 +632,7 @@ mod tests {
     fn test_stream_chunk_finish() {
         let t = make_transformer();
         let mut ctx = make_ctx();
-        ctx.started = true;
+        ctx.text_delta("");
         let chunk = r#"{"choices":[{"delta":{},"finish_reason":"stop"}],"usage":{"prompt_tokens":10,"completion_tokens":5}}"#;
         let events = t.transform_stream_chunk(chunk, &mut ctx).unwrap();
 
@@ -338,6 +643,359 @@ mod tests {
         assert_eq!(ctx.output_tokens, 5);
     }
 
+    /// 요청 변환: tools / tool_choice → OpenAI function 정의
+    #[test]
+    fn test_transform_request_tools() {
+        let t = make_transformer();
+        let body = json!({
+            "model": "gpt-4o",
+            "max_tokens": 10,
+            "tools": [{
+                "name": "Bash",
+                "description": "셸 명령 실행",
+                "input_schema": {"type": "object", "properties": {"command": {"type": "string"}}}
+            }],
+            "tool_choice": {"type": "tool", "name": "Bash", "disable_parallel_tool_use": true},
+            "messages": []
+        });
+        let result = t.transform_request(body, None, false).unwrap();
+
+        let tools = result.body["tools"].as_array().unwrap();
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0]["type"], "function");
+        assert_eq!(tools[0]["function"]["name"], "Bash");
+        assert_eq!(tools[0]["function"]["description"], "셸 명령 실행");
+        assert_eq!(tools[0]["function"]["parameters"]["properties"]["command"]["type"], "string");
+        assert_eq!(result.body["tool_choice"]["function"]["name"], "Bash");
+        assert_eq!(result.body["parallel_tool_calls"], false);
+    }
+
+    /// 요청 변환: tool_use → tool_calls, tool_result → role: tool
+    #[test]
+    fn test_transform_request_tool_messages() {
+        let t = make_transformer();
+        let body = json!({
+            "model": "gpt-4o",
+            "max_tokens": 10,
+            "messages": [
+                {"role": "user", "content": "ls 실행해줘"},
+                {"role": "assistant", "content": [
+                    {"type": "text", "text": "실행합니다."},
+                    {"type": "tool_use", "id": "toolu_1", "name": "Bash", "input": {"command": "ls"}}
+                ]},
+                {"role": "user", "content": [
+                    {"type": "tool_result", "tool_use_id": "toolu_1", "content": [{"type": "text", "text": "a.txt"}]},
+                    {"type": "text", "text": "다음은?"}
+                ]}
+            ]
+        });
+        let result = t.transform_request(body, None, false).unwrap();
+        let msgs = result.body["messages"].as_array().unwrap();
+
+        assert_eq!(msgs.len(), 4);
+        assert_eq!(msgs[1]["role"], "assistant");
+        assert_eq!(msgs[1]["content"], "실행합니다.");
+        assert_eq!(msgs[1]["tool_calls"][0]["id"], "toolu_1");
+        assert_eq!(msgs[1]["tool_calls"][0]["function"]["name"], "Bash");
+        let args: Value =
+            serde_json::from_str(msgs[1]["tool_calls"][0]["function"]["arguments"].as_str().unwrap()).unwrap();
+        assert_eq!(args["command"], "ls");
+
+        assert_eq!(msgs[2]["role"], "tool");
+        assert_eq!(msgs[2]["tool_call_id"], "toolu_1");
+        assert_eq!(msgs[2]["content"], "a.txt");
+        assert_eq!(msgs[3]["role"], "user");
+        assert_eq!(msgs[3]["content"], "다음은?");
+    }
+
+    /// 응답 변환: tool_calls → tool_use 블록
+    #[test]
+    fn test_transform_response_tool_calls() {
+        let t = make_transformer();
+        let body = json!({
+            "choices": [{
+                "message": {
+                    "content": null,
+                    "tool_calls": [{
+                        "id": "call_1",
+                        "type": "function",
+                        "function": {"name": "Read", "arguments": "{\"path\":\"a.rs\"}"}
+                    }]
+                },
+                "finish_reason": "tool_calls"
+            }],
+            "usage": {"prompt_tokens": 10, "completion_tokens": 5}
+        });
+        let result = t.transform_response(body, "gpt-4o").unwrap();
+
+        let content = result["content"].as_array().unwrap();
+        assert_eq!(content.len(), 1);
+        assert_eq!(content[0]["type"], "tool_use");
+        assert_eq!(content[0]["id"], "call_1");
+        assert_eq!(content[0]["name"], "Read");
+        assert_eq!(content[0]["input"]["path"], "a.rs");
+        assert_eq!(result["stop_reason"], "tool_use");
+    }
+
+    /// SSE 변환: 텍스트 후 tool_calls 델타 → 텍스트 블록 닫고 tool_use 블록 + input_json_delta
+    #[test]
+    fn test_stream_chunk_tool_calls() {
+        let t = make_transformer();
+        let mut ctx = make_ctx();
+
+        let text = r#"{"choices":[{"delta":{"content":"확인"},"finish_reason":null}]}"#;
+        t.transform_stream_chunk(text, &mut ctx).unwrap();
+
+        let call_start = r#"{"choices":[{"delta":{"tool_calls":[{"index":0,"id":"call_1","type":"function","function":{"name":"Bash","arguments":""}}]},"finish_reason":null}]}"#;
+        let events = t.transform_stream_chunk(call_start, &mut ctx).unwrap();
+        assert_eq!(events.len(), 2);
+        assert!(events[0].contains("content_block_stop"));
+        assert!(events[1].contains("\"tool_use\""));
+        assert!(events[1].contains("call_1"));
+        assert_eq!(ctx.block_index, 1);
+
+        let call_args = r#"{"choices":[{"delta":{"tool_calls":[{"index":0,"function":{"arguments":"{\"command\":"}}]},"finish_reason":null}]}"#;
+        let events = t.transform_stream_chunk(call_args, &mut ctx).unwrap();
+        assert_eq!(events.len(), 1);
+        assert!(events[0].contains("input_json_delta"));
+        assert!(events[0].contains(r#""index":1"#));
+
+        // 다른 호출은 앞 호출이 더 이어질 수 있으므로 종료 시까지 버퍼
+        let second = r#"{"choices":[{"delta":{"tool_calls":[{"index":1,"id":"call_2","function":{"name":"Read","arguments":"{}"}}]},"finish_reason":null}]}"#;
+        let events = t.transform_stream_chunk(second, &mut ctx).unwrap();
+        assert!(events.is_empty());
+
+        let finish = r#"{"choices":[{"delta":{},"finish_reason":"tool_calls"}]}"#;
+        let events = t.transform_stream_chunk(finish, &mut ctx).unwrap();
+        assert!(events[0].contains("content_block_stop"));
+        assert!(events[0].contains(r#""index":1"#));
+        assert!(events[1].contains("call_2"));
+        assert!(events[1].contains(r#""index":2"#));
+        assert!(events[2].contains(r#""partial_json":"{}""#));
+        assert!(events[3].contains("content_block_stop"));
+        // usage 청크 없이 끝나면 message_delta는 스트림 종료 시 전송
+        assert_eq!(events.len(), 4);
+
+        let end = t.stream_end_events(&mut ctx);
+        assert!(end[0].contains(r#""stop_reason":"tool_use""#));
+    }
+
+    /// 병렬 tool_calls 델타가 인덱스 0/1로 섞여 도착해도 호출마다 실제 id/name으로 블록 하나씩
+    #[test]
+    fn test_stream_chunk_interleaved_tool_calls() {
+        let t = make_transformer();
+        let mut ctx = make_ctx();
+
+        let chunks = [
+            r#"{"choices":[{"delta":{"tool_calls":[{"index":0,"id":"call_a","function":{"name":"Read","arguments":""}},{"index":1,"id":"call_b","function":{"name":"Glob","arguments":""}}]},"finish_reason":null}]}"#,
+            r#"{"choices":[{"delta":{"tool_calls":[{"index":1,"function":{"arguments":"{\"pattern\":"}}]},"finish_reason":null}]}"#,
+            r#"{"choices":[{"delta":{"tool_calls":[{"index":0,"function":{"arguments":"{\"file_path\":"}}]},"finish_reason":null}]}"#,
+            r#"{"choices":[{"delta":{"tool_calls":[{"index":1,"function":{"arguments":"\"*.rs\"}"}}]},"finish_reason":null}]}"#,
+            r#"{"choices":[{"delta":{"tool_calls":[{"index":0,"function":{"arguments":"\"a.rs\"}"}}]},"finish_reason":null}]}"#,
+            r#"{"choices":[{"delta":{},"finish_reason":"tool_calls"}]}"#,
+        ];
+        let mut events = Vec::new();
+        for chunk in chunks {
+            events.extend(t.transform_stream_chunk(chunk, &mut ctx).unwrap());
+        }
+        events.extend(t.stream_end_events(&mut ctx));
+
+        // 블록별로 (id, name, 이어붙인 arguments) 재구성
+        let mut blocks: Vec<(String, String, String)> = Vec::new();
+        for event in &events {
+            let data: Value = serde_json::from_str(event.split("data: ").nth(1).unwrap().trim()).unwrap();
+            match data["type"].as_str().unwrap() {
+                "content_block_start" => {
+                    assert_eq!(data["index"], blocks.len());
+                    let block = &data["content_block"];
+                    blocks.push((block["id"].to_string(), block["name"].to_string(), String::new()));
+                }
+                "content_block_delta" => {
+                    assert_eq!(data["index"], blocks.len() - 1);
+                    blocks.last_mut().unwrap().2.push_str(data["delta"]["partial_json"].as_str().unwrap());
+                }
+                _ => {}
+            }
+        }
+        assert_eq!(
+            blocks,
+            vec![
+                ("\"call_a\"".into(), "\"Read\"".into(), r#"{"file_path":"a.rs"}"#.into()),
+                ("\"call_b\"".into(), "\"Glob\"".into(), r#"{"pattern":"*.rs"}"#.into()),
+            ]
+        );
+    }
+
+    /// 같은 호출의 arguments 사이에 텍스트가 끼어도 arguments를 잃지 않고, 텍스트는 tool_use 블록 뒤로
+    #[test]
+    fn test_stream_chunk_text_between_tool_call_arguments() {
+        let t = make_transformer();
+        let mut ctx = make_ctx();
+
+        let chunks = [
+            r#"{"choices":[{"delta":{"tool_calls":[{"index":0,"id":"call_a","function":{"name":"Read","arguments":"{\"file_path\":"}}]},"finish_reason":null}]}"#,
+            r#"{"choices":[{"delta":{"content":"잠시만요"},"finish_reason":null}]}"#,
+            r#"{"choices":[{"delta":{"tool_calls":[{"index":0,"function":{"arguments":"\"a.rs\"}"}}]},"finish_reason":null}]}"#,
+            r#"{"choices":[{"delta":{},"finish_reason":"tool_calls"}]}"#,
+        ];
+        let mut events = Vec::new();
+        for chunk in chunks {
+            events.extend(t.transform_stream_chunk(chunk, &mut ctx).unwrap());
+        }
+        events.extend(t.stream_end_events(&mut ctx));
+
+        // 블록별로 (type, 이어붙인 텍스트/arguments) 재구성
+        let mut blocks: Vec<(String, String)> = Vec::new();
+        let mut open = false;
+        for event in &events {
+            let data: Value = serde_json::from_str(event.split("data: ").nth(1).unwrap().trim()).unwrap();
+            match data["type"].as_str().unwrap() {
+                "content_block_start" => {
+                    assert!(!open);
+                    open = true;
+                    assert_eq!(data["index"], blocks.len());
+                    blocks.push((data["content_block"]["type"].as_str().unwrap().into(), String::new()));
+                }
+                "content_block_delta" => {
+                    assert!(open);
+                    assert_eq!(data["index"], blocks.len() - 1);
+                    let delta = &data["delta"];
+                    let piece = delta["partial_json"].as_str().or(delta["text"].as_str()).unwrap();
+                    blocks.last_mut().unwrap().1.push_str(piece);
+                }
+                "content_block_stop" => open = false,
+                _ => {}
+            }
+        }
+        assert!(!open);
+        assert_eq!(
+            blocks,
+            vec![
+                ("tool_use".into(), r#"{"file_path":"a.rs"}"#.into()),
+                ("text".into(), "잠시만요".into()),
+            ]
+        );
+        assert!(events.iter().any(|e| e.contains(r#""stop_reason":"tool_use""#)));
+    }
+
+    /// 스트림 중간 에러 청크 → event: error, 이후 종료 이벤트 없음
+    #[test]
+    fn test_stream_error_chunk() {
+        let t = make_transformer();
+        let mut ctx = make_ctx();
+
+        let chunk = r#"{"error":{"message":"Rate limit reached","code":"rate_limit_exceeded"}}"#;
+        let events = t.transform_stream_chunk(chunk, &mut ctx).unwrap();
+        assert_eq!(events.len(), 1);
+        assert!(events[0].starts_with("event: error"));
+        assert!(events[0].contains("rate_limit_error"));
+        assert!(t.stream_end_events(&mut ctx).is_empty());
+    }
+
+    /// include_usage: finish_reason 이후 도착하는 usage 청크로 message_delta 전송
+    #[test]
+    fn test_stream_usage_after_finish() {
+        let t = make_transformer();
+        let mut ctx = make_ctx();
+        ctx.input_tokens = 50;
+
+        t.transform_stream_chunk(r#"{"choices":[{"delta":{"content":"Hi"},"finish_reason":null}]}"#, &mut ctx)
+            .unwrap();
+        let events = t
+            .transform_stream_chunk(r#"{"choices":[{"delta":{},"finish_reason":"stop"}],"usage":null}"#, &mut ctx)
+            .unwrap();
+        assert_eq!(events.len(), 1);
+        assert!(!ctx.finished);
+
+        let usage = r#"{"choices":[],"usage":{"prompt_tokens":120,"completion_tokens":9,"prompt_tokens_details":{"cached_tokens":100}}}"#;
+        let events = t.transform_stream_chunk(usage, &mut ctx).unwrap();
+        assert_eq!(events.len(), 1);
+        assert!(events[0].contains(r#""stop_reason":"end_turn""#));
+        assert!(events[0].contains(r#""input_tokens":20"#));
+        assert!(events[0].contains(r#""output_tokens":9"#));
+        assert!(events[0].contains(r#""cache_read_input_tokens":100"#));
+
+        let end = t.stream_end_events(&mut ctx);
+        assert_eq!(end.len(), 1);
+    }
+
+    /// 스트리밍 요청에 stream_options.include_usage 추가, 비스트리밍 응답의 캐시 토큰 매핑
+    #[test]
+    fn test_usage_include_and_cache_mapping() {
+        let t = make_transformer();
+        let body = json!({"model": "gpt-4o", "max_tokens": 10, "stream": true, "messages": []});
+        let result = t.transform_request(body, None, true).unwrap();
+        assert_eq!(result.body["stream_options"]["include_usage"], true);
+
+        let resp = json!({
+            "choices": [{"message": {"content": "ok"}, "finish_reason": "stop"}],
+            "usage": {"prompt_tokens": 30, "completion_tokens": 2, "prompt_cache_hit_tokens": 10}
+        });
+        let result = t.transform_response(resp, "deepseek-chat").unwrap();
+        assert_eq!(result["usage"]["input_tokens"], 20);
+        assert_eq!(result["usage"]["cache_read_input_tokens"], 10);
+    }
+
+    /// 요청 변환: thinking.budget_tokens → reasoning_effort (라우트에서 켠 경우만)
+    #[test]
+    fn test_transform_request_thinking_budget() {
+        let body = json!({
+            "model": "deepseek-reasoner",
+            "max_tokens": 10,
+            "thinking": {"type": "enabled", "budget_tokens": 8000},
+            "messages": []
+        });
+
+        // 기본값: 추론 모델이 아닐 수 있으므로 보내지 않음
+        let result = make_transformer().transform_request(body.clone(), None, false).unwrap();
+        assert!(result.body.get("thinking").is_none());
+        assert!(result.body.get("reasoning_effort").is_none());
+
+        let t = OpenAITransformer::new(ReasoningEffort::Budget);
+        let result = t.transform_request(body.clone(), None, false).unwrap();
+        assert_eq!(result.body["reasoning_effort"], "medium");
+
+        let t = OpenAITransformer::new(ReasoningEffort::Level("minimal".into()));
+        let result = t.transform_request(body, None, false).unwrap();
+        assert_eq!(result.body["reasoning_effort"], "minimal");
+    }
+
+    /// 응답 변환: reasoning_content → thinking 블록
+    #[test]
+    fn test_transform_response_reasoning() {
+        let t = make_transformer();
+        let body = json!({
+            "choices": [{"message": {"content": "답", "reasoning_content": "생각 중"}, "finish_reason": "stop"}],
+            "usage": {"prompt_tokens": 10, "completion_tokens": 5}
+        });
+        let result = t.transform_response(body, "deepseek-reasoner").unwrap();
+        assert_eq!(result["content"][0]["type"], "thinking");
+        assert_eq!(result["content"][0]["thinking"], "생각 중");
+        assert_eq!(result["content"][1]["text"], "답");
+    }
+
+    /// SSE 변환: reasoning_content 델타 → thinking 블록, 이후 텍스트는 새 블록
+    #[test]
+    fn test_stream_chunk_reasoning() {
+        let t = make_transformer();
+        let mut ctx = make_ctx();
+
+        let reasoning = r#"{"choices":[{"delta":{"role":"assistant","reasoning_content":"음..."},"finish_reason":null}]}"#;
+        let events = t.transform_stream_chunk(reasoning, &mut ctx).unwrap();
+        assert_eq!(events.len(), 2);
+        assert!(events[0].contains(r#""type":"thinking""#));
+        assert!(events[1].contains("thinking_delta"));
+        assert!(events[1].contains(r#""index":0"#));
+
+        let text = r#"{"choices":[{"delta":{"content":"답"},"finish_reason":null}]}"#;
+        let events = t.transform_stream_chunk(text, &mut ctx).unwrap();
+        assert_eq!(events.len(), 3);
+        assert!(events[0].contains("content_block_stop"));
+        assert!(events[1].contains(r#""type":"text""#));
+        assert!(events[2].contains("text_delta"));
+        assert!(events[2].contains(r#""index":1"#));
+    }
+
     /// 스트림 시작/종료 이벤트
     #[test]
     fn test_stream_start_end_events() {
@@ -349,6 +1007,8 @@ mod tests {
         assert!(start[0].contains("message_start"));
         assert!(start[0].contains("msg_test123"));
 
+        // finish 이후 종료: message_stop만 전송
+        ctx.finish(Some("end_turn"));
         let end = t.stream_end_events(&mut ctx);
         assert_eq!(end.len(), 1);
         assert!(end[0].contains("message_stop"));
@@ -0,0 +1,566 @@
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use super::openai::{join_text, tool_result_text};
+use super::{error, schema, thinking_budget, ApiError, StreamContext, TransformError, TransformedRequest, Transformer};
+use crate::config::ReasoningEffort;
+
+/// OpenAI Responses API (`/v1/responses`) 트랜스포머
+#[derive(Default)]
+pub struct ResponsesTransformer {
+    /// thinking 요청에 reasoning.effort를 보낼지 (라우트 설정)
+    reasoning_effort: ReasoningEffort,
+}
+
+impl ResponsesTransformer {
+    pub fn new(reasoning_effort: ReasoningEffort) -> Self {
+        ResponsesTransformer { reasoning_effort }
+    }
+}
+
+/// system (문자열 또는 텍스트 블록 배열) → instructions 문자열
+fn instructions(system: &Value) -> String {
+    match system {
+        Value::String(s) => s.clone(),
+        Value::Array(blocks) => join_text(blocks),
+        _ => String::new(),
+    }
+}
+
+/// image / document 블록 → Responses input part
+fn media_part(block: &Value) -> Result<Value, TransformError> {
+    let source = &block["source"];
+    let media_type = source["media_type"].as_str().unwrap_or("");
+    match (block["type"].as_str(), source["type"].as_str()) {
+        (Some("image"), Some("base64")) => Ok(json!({
+            "type": "input_image",
+            "image_url": format!("data:{};base64,{}", media_type, source["data"].as_str().unwrap_or("")),
+        })),
+        (Some("image"), Some("url")) => Ok(json!({"type": "input_image", "image_url": source["url"]})),
+        (Some("document"), Some("base64")) if media_type == "application/pdf" => Ok(json!({
+            "type": "input_file",
+            "filename": block["title"].as_str().unwrap_or("document.pdf"),
+            "file_data": format!("data:application/pdf;base64,{}", source["data"].as_str().unwrap_or("")),
+        })),
+        (Some("document"), Some("url")) => Ok(json!({"type": "input_file", "file_url": source["url"]})),
+        (Some("document"), Some("text")) => Ok(json!({"type": "input_text", "text": source["data"]})),
+        (Some("document"), Some("content")) => Ok(json!({
+            "type": "input_text",
+            "text": tool_result_text(&source["content"]),
+        })),
+        (kind, source_type) => Err(TransformError(format!(
+            "Responses API는 {} 블록의 source 타입 {}을(를) 지원하지 않습니다",
+            kind.unwrap_or("unknown"),
+            source_type.unwrap_or("unknown"),
+        ))),
+    }
+}
+
+/// Anthropic 메시지 1개 → Responses input 항목들
+/// - assistant: 텍스트 → message(output_text), tool_use → function_call
+/// - user: tool_result → function_call_output (먼저 배치), 나머지 → message(input_*)
+/// - thinking 블록은 암호화된 reasoning 항목을 복원할 수 없으므로 생략
+fn convert_message(msg: &Value) -> Result<Vec<Value>, TransformError> {
+    let role = msg["role"].as_str().unwrap_or("user");
+    let blocks = match &msg["content"] {
+        Value::Array(arr) => arr,
+        Value::String(text) => {
+            let part_type = if role == "assistant" { "output_text" } else { "input_text" };
+            return Ok(vec![json!({
+                "role": role,
+                "content": [{"type": part_type, "text": text}],
+            })]);
+        }
+        _ => return Ok(vec![]),
+    };
+
+    let mut out = Vec::new();
+    if role == "assistant" {
+        let text = join_text(blocks);
+        if !text.is_empty() {
+            out.push(json!({
+                "role": "assistant",
+                "content": [{"type": "output_text", "text": text}],
+            }));
+        }
+        for block in blocks.iter().filter(|b| b["type"] == "tool_use") {
+            out.push(json!({
+                "type": "function_call",
+                "call_id": block["id"],
+                "name": block["name"],
+                "arguments": block.get("input").unwrap_or(&json!({})).to_string(),
+            }));
+        }
+        return Ok(out);
+    }
+
+    // function_call_output은 텍스트만 담으므로 tool_result 안의 이미지는 뒤따르는 message로 옮김
+    let mut rest: Vec<&Value> = Vec::new();
+    for block in blocks {
+        if block["type"] != "tool_result" {
+            rest.push(block);
+            continue;
+        }
+        let mut text = tool_result_text(&block["content"]);
+        if block["is_error"].as_bool() == Some(true) {
+            text = format!("[error] {text}");
+        }
+        out.push(json!({
+            "type": "function_call_output",
+            "call_id": block["tool_use_id"],
+            "output": text,
+        }));
+        if let Some(nested) = block["content"].as_array() {
+            rest.extend(nested.iter().filter(|b| b["type"] == "image" || b["type"] == "document"));
+        }
+    }
+
+    let mut parts = Vec::new();
+    for block in rest {
+        match block["type"].as_str().unwrap_or("text") {
+            "text" => {
+                if let Some(text) = block["text"].as_str() {
+                    parts.push(json!({"type": "input_text", "text": text}));
+                }
+            }
+            "image" | "document" => parts.push(media_part(block)?),
+            _ => {}
+        }
+    }
+    if !parts.is_empty() {
+        out.push(json!({"role": role, "content": parts}));
+    }
+    Ok(out)
+}
+
+/// Anthropic tool 정의 → Responses function 정의 (function 필드가 최상위)
+fn convert_tool(tool: &Value) -> Value {
+    let mut function = json!({
+        "type": "function",
+        "name": tool["name"],
+        "parameters": tool
+            .get("input_schema")
+            .map(|s| schema::sanitize(s, &schema::OPENAI))
+            .unwrap_or_else(|| json!({"type": "object"})),
+        "strict": false,
+    });
+    if let Some(desc) = tool.get("description") {
+        function["description"] = desc.clone();
+    }
+    function
+}
+
+/// Anthropic tool_choice → Responses tool_choice
+fn convert_tool_choice(choice: &Value) -> Option<Value> {
+    match choice["type"].as_str()? {
+        "auto" => Some(json!("auto")),
+        "any" => Some(json!("required")),
+        "none" => Some(json!("none")),
+        "tool" => Some(json!({"type": "function", "name": choice["name"]})),
+        _ => None,
+    }
+}
+
+/// Responses usage → (입력, 출력, 캐시 적중) 토큰 수 (input_tokens는 캐시 적중분 포함)
+fn usage_tokens(usage: &Value) -> (u64, u64, u64) {
+    let input = usage["input_tokens"].as_u64().unwrap_or(0);
+    let output = usage["output_tokens"].as_u64().unwrap_or(0);
+    let cached = usage["input_tokens_details"]["cached_tokens"].as_u64().unwrap_or(0);
+    (input.saturating_sub(cached), output, cached)
+}
+
+/// 응답 status → stop_reason (None이면 tool_use 여부로 결정)
+fn status_stop_reason(response: &Value) -> Option<&'static str> {
+    if response["status"] != "incomplete" {
+        return None;
+    }
+    match response["incomplete_details"]["reason"].as_str() {
+        Some("content_filter") => Some("refusal"),
+        _ => Some("max_tokens"),
+    }
+}
+
+/// reasoning 항목의 요약 텍스트 (없으면 원문 reasoning_text)
+fn reasoning_item_text(item: &Value) -> String {
+    let summary: Vec<&str> = item["summary"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|s| s["text"].as_str())
+        .collect();
+    if !summary.is_empty() {
+        return summary.join("\n\n");
+    }
+    item["content"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|c| c["text"].as_str())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+impl Transformer for ResponsesTransformer {
+    fn transform_request(
+        &self,
+        mut body: Value,
+        model_map: Option<&str>,
+        _is_stream: bool,
+    ) -> Result<TransformedRequest, TransformError> {
+        let obj = body.as_object_mut().ok_or_else(|| {
+            TransformError("요청 본문이 JSON 객체가 아닙니다".into())
+        })?;
+
+        if let Some(mapped) = model_map {
+            obj.insert("model".into(), json!(mapped));
+        }
+
+        // messages → input 항목
+        let mut input = Vec::new();
+        if let Some(messages) = obj.remove("messages") {
+            for msg in messages.as_array().into_iter().flatten() {
+                input.extend(convert_message(msg)?);
+            }
+        }
+        obj.insert("input".into(), Value::Array(input));
+
+        // system → instructions
+        if let Some(system) = obj.remove("system") {
+            let text = instructions(&system);
+            if !text.is_empty() {
+                obj.insert("instructions".into(), json!(text));
+            }
+        }
+
+        if let Some(max) = obj.remove("max_tokens") {
+            obj.insert("max_output_tokens".into(), max);
+        }
+
+        if let Some(tools) = obj.remove("tools") {
+            let functions: Vec<Value> = tools
+                .as_array()
+                .map(|arr| arr.iter().map(convert_tool).collect())
+                .unwrap_or_default();
+            if !functions.is_empty() {
+                obj.insert("tools".into(), Value::Array(functions));
+            }
+        }
+
+        if let Some(choice) = obj.remove("tool_choice") {
+            if choice["disable_parallel_tool_use"].as_bool() == Some(true) {
+                obj.insert("parallel_tool_calls".into(), json!(false));
+            }
+            if let Some(mapped) = convert_tool_choice(&choice) {
+                obj.insert("tool_choice".into(), mapped);
+            }
+        }
+
+        // thinking → reasoning (라우트에서 켠 경우만, 요약을 받아 thinking 블록으로 표시)
+        let effort = obj.get("thinking").and_then(thinking_budget).and_then(|b| self.reasoning_effort.level(b));
+        if let Some(effort) = effort {
+            obj.insert("reasoning".into(), json!({"effort": effort, "summary": "auto"}));
+        }
+
+        // 대화 상태는 클라이언트가 매번 전체를 보내므로 서버 저장 불필요
+        obj.insert("store".into(), json!(false));
+
+        // Anthropic 전용 / Responses 미지원 필드 제거
+        for key in &["top_k", "metadata", "anthropic_version", "thinking", "stop_sequences"] {
+            obj.remove(*key);
+        }
+
+        Ok(TransformedRequest {
+            path: "/v1/responses".to_string(),
+            body,
+            extra_headers: vec![],
+        })
+    }
+
+    fn transform_response(&self, body: Value, model: &str) -> Result<Value, TransformError> {
+        let mut content = Vec::new();
+        let mut tool_used = false;
+        for item in body["output"].as_array().into_iter().flatten() {
+            match item["type"].as_str() {
+                Some("reasoning") => {
+                    let text = reasoning_item_text(item);
+                    if !text.is_empty() {
+                        content.push(json!({"type": "thinking", "thinking": text, "signature": ""}));
+                    }
+                }
+                Some("message") => {
+                    for part in item["content"].as_array().into_iter().flatten() {
+                        let text = part["text"].as_str().or_else(|| part["refusal"].as_str());
+                        if let Some(text) = text {
+                            content.push(json!({"type": "text", "text": text}));
+                        }
+                    }
+                }
+                Some("function_call") => {
+                    let args = item["arguments"].as_str().unwrap_or("");
+                    let input: Value = if args.trim().is_empty() {
+                        json!({})
+                    } else {
+                        serde_json::from_str(args).map_err(|e| {
+                            TransformError(format!("function_call arguments 파싱 실패: {e}"))
+                        })?
+                    };
+                    tool_used = true;
+                    content.push(json!({
+                        "type": "tool_use",
+                        "id": item["call_id"],
+                        "name": item["name"],
+                        "input": input,
+                    }));
+                }
+                _ => {}
+            }
+        }
+        if content.is_empty() {
+            content.push(json!({"type": "text", "text": ""}));
+        }
+
+        let stop_reason = status_stop_reason(&body)
+            .unwrap_or(if tool_used { "tool_use" } else { "end_turn" });
+        let (input_tokens, output_tokens, cached_tokens) = usage_tokens(&body["usage"]);
+
+        Ok(json!({
+            "id": format!("msg_{}", Uuid::new_v4().simple()),
+            "type": "message",
+            "role": "assistant",
+            "model": model,
+            "content": content,
+            "stop_reason": stop_reason,
+            "stop_sequence": null,
+            "usage": {
+                "input_tokens": input_tokens,
+                "output_tokens": output_tokens,
+                "cache_read_input_tokens": cached_tokens,
+            }
+        }))
+    }
+
+    fn transform_error(&self, status: u16, body: &[u8]) -> ApiError {
+        error::from_openai(status, body)
+    }
+
+    fn transform_stream_chunk(
+        &self,
+        chunk: &str,
+        ctx: &mut StreamContext,
+    ) -> Result<Vec<String>, TransformError> {
+        let data: Value = serde_json::from_str(chunk)
+            .map_err(|e| TransformError(format!("SSE JSON 파싱 실패: {e}")))?;
+
+        let mut events = Vec::new();
+        match data["type"].as_str().unwrap_or("") {
+            "response.output_text.delta" | "response.refusal.delta" => {
+                if let Some(delta) = data["delta"].as_str().filter(|d| !d.is_empty()) {
+                    events.extend(ctx.text_delta(delta));
+                }
+            }
+            "response.reasoning_summary_text.delta" | "response.reasoning_text.delta" => {
+                if let Some(delta) = data["delta"].as_str().filter(|d| !d.is_empty()) {
+                    events.extend(ctx.thinking_delta(delta));
+                }
+            }
+            // 요약 단락 사이 구분
+            "response.reasoning_summary_part.added" if data["summary_index"].as_u64() > Some(0) => {
+                events.extend(ctx.thinking_delta("\n\n"));
+            }
+            "response.output_item.added" if data["item"]["type"] == "function_call" => {
+                let item = &data["item"];
+                let output_index = data["output_index"].as_u64().unwrap_or(0);
+                let id = item["call_id"]
+                    .as_str()
+                    .map(String::from)
+                    .unwrap_or_else(|| format!("toolu_{}", Uuid::new_v4().simple()));
+                events.extend(ctx.open_tool_block(output_index, &id, item["name"].as_str().unwrap_or("")));
+                if let Some(args) = item["arguments"].as_str() {
+                    events.extend(ctx.tool_input_delta(args));
+                }
+            }
+            "response.function_call_arguments.delta"
+                if ctx.open_tool_call() == data["output_index"].as_u64() =>
+            {
+                events.extend(ctx.tool_input_delta(data["delta"].as_str().unwrap_or("")));
+            }
+            "response.output_item.done" if data["item"]["type"] == "function_call" => {
+                events.extend(ctx.close_block());
+            }
+            "response.completed" | "response.incomplete" => {
+                let response = &data["response"];
+                if response["usage"].is_object() {
+                    let (input, output, cached) = usage_tokens(&response["usage"]);
+                    ctx.set_usage(Some(input), Some(output), Some(cached));
+                }
+                events.extend(ctx.finish(status_stop_reason(response)));
+            }
+            "response.failed" => {
+                let err = json!({"error": &data["response"]["error"]});
+                events.extend(ctx.error(&self.transform_error(500, err.to_string().as_bytes())));
+            }
+            "error" => {
+                let err = json!({"error": {"message": data["message"], "code": data["code"]}});
+                events.extend(ctx.error(&self.transform_error(500, err.to_string().as_bytes())));
+            }
+            _ => {}
+        }
+        Ok(events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_transformer() -> ResponsesTransformer {
+        ResponsesTransformer::new(ReasoningEffort::Budget)
+    }
+
+    fn make_ctx() -> StreamContext {
+        StreamContext::new("gpt-5", "msg_test123")
+    }
+
+    /// 요청 변환: system → instructions, messages → input 항목, tool 왕복
+    #[test]
+    fn test_transform_request_basic() {
+        let t = make_transformer();
+        let body = json!({
+            "model": "claude-opus",
+            "system": [{"type": "text", "text": "간결하게 답해."}],
+            "max_tokens": 4096,
+            "stop_sequences": ["END"],
+            "thinking": {"type": "enabled", "budget_tokens": 20000},
+            "tools": [{"name": "Bash", "description": "셸 실행", "input_schema": {"type": "object", "properties": {}}}],
+            "tool_choice": {"type": "any"},
+            "messages": [
+                {"role": "user", "content": "목록 보여줘"},
+                {"role": "assistant", "content": [
+                    {"type": "thinking", "thinking": "...", "signature": "x"},
+                    {"type": "text", "text": "실행할게요."},
+                    {"type": "tool_use", "id": "call_1", "name": "Bash", "input": {"command": "ls"}}
+                ]},
+                {"role": "user", "content": [
+                    {"type": "tool_result", "tool_use_id": "call_1", "content": "a.txt"},
+                    {"type": "text", "text": "계속"}
+                ]}
+            ]
+        });
+        let result = t.transform_request(body, Some("gpt-5"), true).unwrap();
+        let b = &result.body;
+
+        assert_eq!(result.path, "/v1/responses");
+        assert_eq!(b["model"], "gpt-5");
+        assert_eq!(b["instructions"], "간결하게 답해.");
+        assert_eq!(b["max_output_tokens"], 4096);
+        assert_eq!(b["reasoning"]["effort"], "high");
+        assert_eq!(b["store"], false);
+        assert!(b.get("stop_sequences").is_none());
+        assert!(b.get("thinking").is_none());
+        assert_eq!(b["tools"][0]["name"], "Bash");
+        assert_eq!(b["tool_choice"], "required");
+
+        let input = b["input"].as_array().unwrap();
+        assert_eq!(input.len(), 5);
+        assert_eq!(input[0]["content"][0]["type"], "input_text");
+        assert_eq!(input[1]["content"][0]["type"], "output_text");
+        assert_eq!(input[2]["type"], "function_call");
+        assert_eq!(input[2]["arguments"], r#"{"command":"ls"}"#);
+        assert_eq!(input[3]["type"], "function_call_output");
+        assert_eq!(input[3]["call_id"], "call_1");
+        assert_eq!(input[4]["content"][0]["text"], "계속");
+
+        // reasoning_effort를 켜지 않은 라우트는 reasoning 생략
+        let body = json!({"model": "gpt-4.1", "thinking": {"type": "enabled", "budget_tokens": 20000}, "messages": []});
+        let result = ResponsesTransformer::default().transform_request(body, None, false).unwrap();
+        assert!(result.body.get("reasoning").is_none());
+    }
+
+    /// 응답 변환: reasoning → thinking, message → text, function_call → tool_use
+    #[test]
+    fn test_transform_response_items() {
+        let t = make_transformer();
+        let body = json!({
+            "status": "completed",
+            "output": [
+                {"type": "reasoning", "summary": [{"type": "summary_text", "text": "파일 목록 필요"}]},
+                {"type": "message", "content": [{"type": "output_text", "text": "확인합니다."}]},
+                {"type": "function_call", "call_id": "call_9", "name": "Bash", "arguments": "{\"command\":\"ls\"}"}
+            ],
+            "usage": {"input_tokens": 100, "output_tokens": 20, "input_tokens_details": {"cached_tokens": 60}}
+        });
+        let result = t.transform_response(body, "claude-opus").unwrap();
+
+        assert_eq!(result["content"][0]["type"], "thinking");
+        assert_eq!(result["content"][0]["thinking"], "파일 목록 필요");
+        assert_eq!(result["content"][1]["text"], "확인합니다.");
+        assert_eq!(result["content"][2]["id"], "call_9");
+        assert_eq!(result["content"][2]["input"]["command"], "ls");
+        assert_eq!(result["stop_reason"], "tool_use");
+        assert_eq!(result["usage"]["input_tokens"], 40);
+        assert_eq!(result["usage"]["cache_read_input_tokens"], 60);
+    }
+
+    /// 응답 변환: incomplete(max_output_tokens) → max_tokens
+    #[test]
+    fn test_transform_response_incomplete() {
+        let t = make_transformer();
+        let body = json!({
+            "status": "incomplete",
+            "incomplete_details": {"reason": "max_output_tokens"},
+            "output": [{"type": "message", "content": [{"type": "output_text", "text": "잘린"}]}]
+        });
+        let result = t.transform_response(body, "claude-opus").unwrap();
+        assert_eq!(result["stop_reason"], "max_tokens");
+    }
+
+    /// 스트림: reasoning 요약 → 텍스트 → function_call → completed
+    #[test]
+    fn test_stream_events() {
+        let t = make_transformer();
+        let mut ctx = make_ctx();
+
+        let events = t
+            .transform_stream_chunk(r#"{"type":"response.reasoning_summary_text.delta","delta":"생각"}"#, &mut ctx)
+            .unwrap();
+        assert!(events[0].contains(r#""type":"thinking""#));
+
+        let events = t
+            .transform_stream_chunk(r#"{"type":"response.output_text.delta","delta":"답"}"#, &mut ctx)
+            .unwrap();
+        assert!(events[0].contains("content_block_stop"));
+        assert!(events[2].contains("text_delta"));
+
+        let added = r#"{"type":"response.output_item.added","output_index":2,"item":{"type":"function_call","call_id":"call_1","name":"Bash","arguments":""}}"#;
+        let events = t.transform_stream_chunk(added, &mut ctx).unwrap();
+        assert!(events[1].contains("call_1"));
+
+        let args = r#"{"type":"response.function_call_arguments.delta","output_index":2,"delta":"{\"command\":\"ls\"}"}"#;
+        let events = t.transform_stream_chunk(args, &mut ctx).unwrap();
+        assert!(events[0].contains("input_json_delta"));
+
+        let done = r#"{"type":"response.output_item.done","output_index":2,"item":{"type":"function_call"}}"#;
+        let events = t.transform_stream_chunk(done, &mut ctx).unwrap();
+        assert!(events[0].contains("content_block_stop"));
+
+        let completed = r#"{"type":"response.completed","response":{"status":"completed","usage":{"input_tokens":10,"output_tokens":5}}}"#;
+        let events = t.transform_stream_chunk(completed, &mut ctx).unwrap();
+        assert_eq!(events.len(), 1);
+        assert!(events[0].contains(r#""stop_reason":"tool_use""#));
+        assert!(events[0].contains(r#""output_tokens":5"#));
+
+        let end = t.stream_end_events(&mut ctx);
+        assert_eq!(end.len(), 1);
+    }
+
+    /// 스트림: response.failed → event: error
+    #[test]
+    fn test_stream_failed() {
+        let t = make_transformer();
+        let mut ctx = make_ctx();
+        let failed = r#"{"type":"response.failed","response":{"status":"failed","error":{"code":"rate_limit_exceeded","message":"slow down"}}}"#;
+        let events = t.transform_stream_chunk(failed, &mut ctx).unwrap();
+        assert!(events[0].starts_with("event: error"));
+        assert!(events[0].contains("rate_limit_error"));
+    }
+}
@@ -0,0 +1,316 @@
+use serde_json::{json, Map, Value};
+
+/// `$ref` 인라인 최대 깊이 (순환 참조 방지)
+const MAX_REF_DEPTH: usize = 8;
+
+/// 제공자별 JSON Schema 허용 범위
+///
+/// | 제공자 | 허용 키워드 | 제거 | 재작성 |
+/// |--------|-------------|------|--------|
+/// | OpenAI 호환 | 대부분 | `$schema`, `$id`, `$comment` | 없음 |
+/// | Gemini | OpenAPI 3.0 부분집합 (`GEMINI_KEYWORDS`) | 그 외 전부 | `const`→`enum`, `oneOf`→`anyOf`, `["T","null"]`→`nullable`, `$ref` 인라인, `allOf` 병합 |
+pub struct SchemaProfile {
+    /// 허용 키워드 목록 (None이면 `removed`를 제외한 전부 허용)
+    pub allowed: Option<&'static [&'static str]>,
+    /// 항상 제거하는 키워드
+    pub removed: &'static [&'static str],
+    /// string 타입에서 허용하는 format 값 (None이면 전부 허용)
+    pub string_formats: Option<&'static [&'static str]>,
+    /// Gemini 계열 재작성 적용 여부
+    pub openapi_subset: bool,
+}
+
+const GEMINI_KEYWORDS: &[&str] = &[
+    "type",
+    "format",
+    "title",
+    "description",
+    "nullable",
+    "enum",
+    "items",
+    "minItems",
+    "maxItems",
+    "properties",
+    "required",
+    "minProperties",
+    "maxProperties",
+    "minLength",
+    "maxLength",
+    "pattern",
+    "minimum",
+    "maximum",
+    "anyOf",
+    "propertyOrdering",
+];
+
+/// OpenAI 호환 제공자 (OpenAI, DeepSeek, GLM, Kimi 등)
+pub const OPENAI: SchemaProfile = SchemaProfile {
+    allowed: None,
+    removed: &["$schema", "$id", "$comment"],
+    string_formats: None,
+    openapi_subset: false,
+};
+
+/// Gemini (generativelanguage / Vertex AI)
+pub const GEMINI: SchemaProfile = SchemaProfile {
+    allowed: Some(GEMINI_KEYWORDS),
+    removed: &[],
+    string_formats: Some(&["enum", "date-time"]),
+    openapi_subset: true,
+};
+
+/// 도구 input_schema를 제공자가 허용하는 형태로 정규화
+pub fn sanitize(schema: &Value, profile: &SchemaProfile) -> Value {
+    let defs = collect_defs(schema);
+    sanitize_node(schema, profile, &defs, 0)
+}
+
+/// 최상위 `$defs` / `definitions` 수집 (`$ref` 인라인용)
+fn collect_defs(schema: &Value) -> Map<String, Value> {
+    let mut defs = Map::new();
+    for key in ["$defs", "definitions"] {
+        if let Some(obj) = schema[key].as_object() {
+            for (name, def) in obj {
+                defs.insert(format!("#/{key}/{name}"), def.clone());
+            }
+        }
+    }
+    defs
+}
+
+fn sanitize_node(node: &Value, profile: &SchemaProfile, defs: &Map<String, Value>, depth: usize) -> Value {
+    let obj = match node {
+        Value::Object(obj) => obj,
+        Value::Array(arr) => {
+            return Value::Array(arr.iter().map(|v| sanitize_node(v, profile, defs, depth)).collect());
+        }
+        other => return other.clone(),
+    };
+
+    let mut obj = obj.clone();
+    if profile.openapi_subset {
+        if let Some(resolved) = inline_ref(&obj, defs, depth) {
+            return resolved
+                .map(|r| sanitize_node(&r, profile, defs, depth + 1))
+                .unwrap_or_else(|| json!({"type": "object"}));
+        }
+        rewrite_openapi(&mut obj);
+    }
+
+    let mut out = Map::new();
+    for (key, value) in obj {
+        if profile.removed.contains(&key.as_str()) {
+            continue;
+        }
+        if profile.allowed.is_some_and(|allowed| !allowed.contains(&key.as_str())) {
+            continue;
+        }
+        let value = match key.as_str() {
+            // 하위 스키마 맵
+            "properties" | "$defs" | "definitions" | "patternProperties" => match value {
+                Value::Object(props) => Value::Object(
+                    props
+                        .into_iter()
+                        .map(|(k, v)| (k, sanitize_node(&v, profile, defs, depth)))
+                        .collect(),
+                ),
+                other => other,
+            },
+            // 하위 스키마 (단일 또는 배열)
+            "items" | "anyOf" | "oneOf" | "allOf" | "not" | "additionalProperties" => {
+                sanitize_node(&value, profile, defs, depth)
+            }
+            _ => value,
+        };
+        out.insert(key, value);
+    }
+
+    // 허용되지 않는 string format 제거
+    if let (Some(formats), Some(format)) = (profile.string_formats, out.get("format").and_then(Value::as_str)) {
+        if out.get("type").and_then(Value::as_str) == Some("string") && !formats.contains(&format) {
+            out.remove("format");
+        }
+    }
+
+    // required는 실제 존재하는 속성만 남김
+    if let (Some(Value::Array(required)), Some(Value::Object(props))) = (out.get("required"), out.get("properties")) {
+        let filtered: Vec<Value> = required
+            .iter()
+            .filter(|r| r.as_str().is_some_and(|name| props.contains_key(name)))
+            .cloned()
+            .collect();
+        out.insert("required".into(), Value::Array(filtered));
+    }
+
+    Value::Object(out)
+}
+
+/// `$ref` 노드를 정의로 치환
+/// - None: `$ref` 없음
+/// - Some(None): 해석 불가 (외부 참조 또는 깊이 초과)
+fn inline_ref(obj: &Map<String, Value>, defs: &Map<String, Value>, depth: usize) -> Option<Option<Value>> {
+    let reference = obj.get("$ref")?.as_str()?;
+    if depth >= MAX_REF_DEPTH {
+        return Some(None);
+    }
+    let mut resolved = defs.get(reference).cloned()?;
+    // $ref 옆의 description 등은 유지
+    if let Value::Object(target) = &mut resolved {
+        for (k, v) in obj {
+            if k != "$ref" {
+                target.entry(k.clone()).or_insert_with(|| v.clone());
+            }
+        }
+    }
+    Some(Some(resolved))
+}
+
+/// OpenAPI 3.0 부분집합으로 재작성 (Gemini)
+fn rewrite_openapi(obj: &mut Map<String, Value>) {
+    // const → enum
+    if let Some(c) = obj.remove("const") {
+        obj.insert("enum".into(), json!([c]));
+    }
+
+    // oneOf → anyOf
+    if let Some(one_of) = obj.remove("oneOf") {
+        obj.entry("anyOf").or_insert(one_of);
+    }
+
+    // allOf → 부모에 병합
+    if let Some(Value::Array(all_of)) = obj.remove("allOf") {
+        for sub in all_of {
+            let Value::Object(sub) = sub else { continue };
+            for (k, v) in sub {
+                match (k.as_str(), obj.get_mut(&k)) {
+                    ("properties", Some(Value::Object(props))) => {
+                        if let Value::Object(more) = v {
+                            props.extend(more);
+                        }
+                    }
+                    ("required", Some(Value::Array(req))) => {
+                        if let Value::Array(more) = v {
+                            req.extend(more);
+                        }
+                    }
+                    (_, None) => {
+                        obj.insert(k, v);
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    // type: ["string", "null"] → type: "string", nullable: true
+    if let Some(Value::Array(types)) = obj.get("type") {
+        let nullable = types.iter().any(|t| t == "null");
+        let first = types.iter().find(|t| *t != "null").cloned();
+        match first {
+            Some(t) => {
+                obj.insert("type".into(), t);
+            }
+            None => {
+                obj.remove("type");
+            }
+        }
+        if nullable {
+            obj.insert("nullable".into(), json!(true));
+        }
+    }
+
+    // anyOf 안의 {"type": "null"} → nullable
+    if let Some(Value::Array(any_of)) = obj.get_mut("anyOf") {
+        let before = any_of.len();
+        any_of.retain(|s| s.get("type").and_then(Value::as_str) != Some("null"));
+        if any_of.len() != before {
+            obj.insert("nullable".into(), json!(true));
+        }
+    }
+
+    // enum은 문자열만 허용
+    if let Some(Value::Array(values)) = obj.get_mut("enum") {
+        for v in values.iter_mut() {
+            if !v.is_string() {
+                *v = Value::String(v.as_str().map(String::from).unwrap_or_else(|| v.to_string()));
+            }
+        }
+        obj.insert("type".into(), json!("string"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn claude_code_schema() -> Value {
+        json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "type": "object",
+            "additionalProperties": false,
+            "properties": {
+                "url": {"type": "string", "format": "uri", "description": "대상 URL"},
+                "timeout": {"type": ["number", "null"], "default": 30},
+                "mode": {"oneOf": [{"const": "fast"}, {"const": "slow"}]},
+                "options": {"$ref": "#/$defs/Options"}
+            },
+            "required": ["url", "missing"],
+            "$defs": {
+                "Options": {"type": "object", "properties": {"depth": {"type": "integer", "exclusiveMinimum": 0}}}
+            }
+        })
+    }
+
+    /// OpenAI: 메타 키워드만 제거하고 나머지는 유지
+    #[test]
+    fn test_sanitize_openai_keeps_most_keywords() {
+        let result = sanitize(&claude_code_schema(), &OPENAI);
+        assert!(result.get("$schema").is_none());
+        assert_eq!(result["additionalProperties"], false);
+        assert_eq!(result["properties"]["url"]["format"], "uri");
+        assert_eq!(result["properties"]["timeout"]["default"], 30);
+    }
+
+    /// Gemini: 미지원 키워드 제거 및 재작성
+    #[test]
+    fn test_sanitize_gemini_strips_and_rewrites() {
+        let result = sanitize(&claude_code_schema(), &GEMINI);
+
+        assert!(result.get("$schema").is_none());
+        assert!(result.get("additionalProperties").is_none());
+        assert!(result.get("$defs").is_none());
+
+        let props = &result["properties"];
+        assert!(props["url"].get("format").is_none());
+        assert_eq!(props["url"]["description"], "대상 URL");
+
+        assert_eq!(props["timeout"]["type"], "number");
+        assert_eq!(props["timeout"]["nullable"], true);
+        assert!(props["timeout"].get("default").is_none());
+
+        assert_eq!(props["mode"]["anyOf"][0]["enum"][0], "fast");
+        assert_eq!(props["mode"]["anyOf"][1]["type"], "string");
+
+        // $ref 인라인
+        assert_eq!(props["options"]["type"], "object");
+        assert_eq!(props["options"]["properties"]["depth"]["type"], "integer");
+        assert!(props["options"]["properties"]["depth"].get("exclusiveMinimum").is_none());
+
+        // 존재하지 않는 required 항목 제거
+        assert_eq!(result["required"], json!(["url"]));
+    }
+
+    /// Gemini: 순환 $ref는 깊이 제한에서 끊김
+    #[test]
+    fn test_sanitize_gemini_recursive_ref() {
+        let schema = json!({
+            "type": "object",
+            "properties": {"node": {"$ref": "#/definitions/Node"}},
+            "definitions": {
+                "Node": {"type": "object", "properties": {"child": {"$ref": "#/definitions/Node"}}}
+            }
+        });
+        let result = sanitize(&schema, &GEMINI);
+        assert_eq!(result["properties"]["node"]["type"], "object");
+    }
+}
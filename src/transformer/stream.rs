@@ -0,0 +1,657 @@
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+use super::error::ApiError;
+use super::tokens::estimate_tokens;
+
+/// SSE 이벤트 문자열 생성
+pub fn sse_event(name: &str, data: &Value) -> String {
+    format!("event: {}\ndata: {}\n\n", name, data)
+}
+
+/// 열린 content 블록 종류
+#[derive(Debug, Clone, PartialEq)]
+pub enum BlockKind {
+    Text,
+    Thinking,
+    /// 업스트림 tool call 식별자 (OpenAI tool_calls 인덱스 등)
+    ToolUse(u64),
+}
+
+/// 인덱스별로 조각나 도착하는 tool call (OpenAI `tool_calls` 델타)
+#[derive(Debug, Default)]
+struct PendingToolCall {
+    id: String,
+    name: String,
+    /// 아직 보내지 못한 arguments 조각
+    arguments: String,
+    /// tool_use 블록을 열었는지 (블록은 호출당 한 번만 연다)
+    opened: bool,
+}
+
+/// 버퍼링 중인 tool_use 블록이 열려 있는 동안 도착한 텍스트/추론 델타
+#[derive(Debug)]
+enum DeferredDelta {
+    Text(String),
+    Thinking(String),
+    Signature(String),
+}
+
+/// SSE 스트림 상태 추적 — Anthropic content 블록 상태 머신
+///
+/// 업스트림이 텍스트 / 추론 / tool call 사이를 오가면 블록을 닫고 새로 열며
+/// `index`를 순서대로 증가시킨다. `finish`는 항상 열린 블록을 먼저 닫은 뒤
+/// `message_delta`를 한 번만 전송한다.
+pub struct StreamContext {
+    pub model: String,
+    pub message_id: String,
+    pub input_tokens: u32,
+    pub output_tokens: u32,
+    /// 현재 열린 블록(또는 다음에 열릴 블록)의 인덱스
+    pub block_index: u32,
+    /// 현재 열린 블록 (None이면 닫힘)
+    pub open_block: Option<BlockKind>,
+    /// 이번 메시지에서 tool_use 블록을 연 적이 있는지 (stop_reason 결정용)
+    pub tool_used: bool,
+    /// message_delta 전송 여부
+    pub finished: bool,
+    /// 업스트림이 보고한 캐시 적중 입력 토큰 수
+    pub cache_read_input_tokens: u32,
+    /// 업스트림이 출력 토큰 수를 보고했는지 (false면 로컬 추정치 사용)
+    pub usage_reported: bool,
+    /// 델타 텍스트로 누적한 출력 토큰 추정치
+    pub estimated_output_tokens: u32,
+    /// 확정된 stop_reason (usage 도착 전까지 message_delta 보류)
+    pub stop_reason: Option<String>,
+    /// error 이벤트 전송 여부 (이후 종료 이벤트 생략)
+    pub errored: bool,
+    /// 클라이언트에 보낸 텍스트 누적 (스트림 이어받기 prefill용)
+    pub partial_text: String,
+    /// 업스트림 인덱스별 tool call 상태 (병렬 호출 델타가 섞여 도착해도 호출마다 블록 하나)
+    tool_calls: BTreeMap<u64, PendingToolCall>,
+    /// tool call 블록을 닫지 않으려고 미룬 델타 (`stop`/`finish`에서 tool call 뒤에 전송)
+    deferred: Vec<DeferredDelta>,
+}
+
+impl StreamContext {
+    pub fn new(model: &str, message_id: &str) -> Self {
+        StreamContext {
+            model: model.to_string(),
+            message_id: message_id.to_string(),
+            input_tokens: 0,
+            output_tokens: 0,
+            block_index: 0,
+            open_block: None,
+            tool_used: false,
+            finished: false,
+            cache_read_input_tokens: 0,
+            usage_reported: false,
+            estimated_output_tokens: 0,
+            stop_reason: None,
+            errored: false,
+            partial_text: String::new(),
+            tool_calls: BTreeMap::new(),
+            deferred: Vec::new(),
+        }
+    }
+
+    /// 업스트림 usage 반영 (None인 항목은 유지)
+    pub fn set_usage(&mut self, input_tokens: Option<u64>, output_tokens: Option<u64>, cache_read: Option<u64>) {
+        if let Some(input) = input_tokens {
+            self.input_tokens = input as u32;
+        }
+        if let Some(output) = output_tokens {
+            self.output_tokens = output as u32;
+            self.usage_reported = true;
+        }
+        if let Some(cached) = cache_read {
+            self.cache_read_input_tokens = cached as u32;
+        }
+    }
+
+    /// 보고된 출력 토큰 수 (없으면 로컬 추정치)
+    pub fn reported_output_tokens(&self) -> u32 {
+        if self.usage_reported {
+            self.output_tokens
+        } else {
+            self.estimated_output_tokens
+        }
+    }
+
+    /// message_start 이벤트
+    pub fn message_start(&self) -> Vec<String> {
+        let msg_start = json!({
+            "type": "message_start",
+            "message": {
+                "id": &self.message_id,
+                "type": "message",
+                "role": "assistant",
+                "model": &self.model,
+                "content": [],
+                "stop_reason": null,
+                "stop_sequence": null,
+                "usage": {"input_tokens": self.input_tokens, "output_tokens": 0}
+            }
+        });
+        vec![sse_event("message_start", &msg_start)]
+    }
+
+    /// 블록 시작 (열린 블록이 있으면 먼저 닫음)
+    fn open_block(&mut self, kind: BlockKind, content_block: Value) -> Vec<String> {
+        let mut events = self.close_block();
+        if matches!(kind, BlockKind::ToolUse(_)) {
+            self.tool_used = true;
+        }
+        self.open_block = Some(kind);
+        let block_start = json!({
+            "type": "content_block_start",
+            "index": self.block_index,
+            "content_block": content_block
+        });
+        events.push(sse_event("content_block_start", &block_start));
+        events
+    }
+
+    fn delta(&mut self, delta: Value) -> String {
+        for key in ["text", "thinking", "partial_json"] {
+            if let Some(text) = delta[key].as_str() {
+                self.estimated_output_tokens += estimate_tokens(text);
+            }
+        }
+        let event = json!({
+            "type": "content_block_delta",
+            "index": self.block_index,
+            "delta": delta
+        });
+        sse_event("content_block_delta", &event)
+    }
+
+    /// 인덱스별로 버퍼링하는 tool call의 블록이 열려 있는지
+    ///
+    /// 블록은 다시 열 수 없으므로, 이때 텍스트/추론 블록을 열면 같은 호출의 남은 arguments를
+    /// 보낼 곳이 없어진다. 그래서 이 동안의 델타는 `deferred`에 모아 뒀다가 나중에 보낸다.
+    fn defers_deltas(&self) -> bool {
+        self.open_tool_call().is_some_and(|index| self.tool_calls.contains_key(&index))
+    }
+
+    /// 텍스트 델타 (텍스트 블록이 열려 있지 않으면 새로 엶)
+    pub fn text_delta(&mut self, text: &str) -> Vec<String> {
+        if self.defers_deltas() {
+            self.deferred.push(DeferredDelta::Text(text.to_string()));
+            return vec![];
+        }
+        let mut events = Vec::new();
+        if self.open_block != Some(BlockKind::Text) {
+            events.extend(self.open_block(BlockKind::Text, json!({"type": "text", "text": ""})));
+        }
+        self.partial_text.push_str(text);
+        events.push(self.delta(json!({"type": "text_delta", "text": text})));
+        events
+    }
+
+    /// thinking 델타 (thinking 블록이 열려 있지 않으면 새로 엶)
+    pub fn thinking_delta(&mut self, text: &str) -> Vec<String> {
+        if self.defers_deltas() {
+            self.deferred.push(DeferredDelta::Thinking(text.to_string()));
+            return vec![];
+        }
+        let mut events = Vec::new();
+        if self.open_block != Some(BlockKind::Thinking) {
+            events.extend(self.open_block(
+                BlockKind::Thinking,
+                json!({"type": "thinking", "thinking": ""}),
+            ));
+        }
+        events.push(self.delta(json!({"type": "thinking_delta", "thinking": text})));
+        events
+    }
+
+    /// 열린 thinking 블록에 서명 추가 (thinking 블록이 아니면 무시)
+    pub fn signature_delta(&mut self, signature: &str) -> Vec<String> {
+        if self.defers_deltas() && matches!(self.deferred.last(), Some(DeferredDelta::Thinking(_))) {
+            self.deferred.push(DeferredDelta::Signature(signature.to_string()));
+            return vec![];
+        }
+        if self.open_block != Some(BlockKind::Thinking) || signature.is_empty() {
+            return vec![];
+        }
+        vec![self.delta(json!({"type": "signature_delta", "signature": signature}))]
+    }
+
+    /// tool_use 블록 시작
+    pub fn open_tool_block(&mut self, call_id: u64, id: &str, name: &str) -> Vec<String> {
+        self.open_block(
+            BlockKind::ToolUse(call_id),
+            json!({"type": "tool_use", "id": id, "name": name, "input": {}}),
+        )
+    }
+
+    /// 열린 tool_use 블록에 input_json_delta 추가 (tool_use 블록이 아니면 무시)
+    pub fn tool_input_delta(&mut self, partial_json: &str) -> Vec<String> {
+        if !matches!(self.open_block, Some(BlockKind::ToolUse(_))) || partial_json.is_empty() {
+            return vec![];
+        }
+        vec![self.delta(json!({"type": "input_json_delta", "partial_json": partial_json}))]
+    }
+
+    /// 인덱스별 tool call 델타 (id/name은 첫 조각에만 오는 경우가 많음)
+    ///
+    /// 같은 호출의 블록이 열려 있으면 arguments를 바로 보내고, 다른 tool_use 블록이 열려 있으면
+    /// 그 호출이 더 이어질 수 있으므로 버퍼에 모아 두었다가 `stop`/`finish`에서 블록을 연다.
+    pub fn tool_call_delta(&mut self, index: u64, id: Option<&str>, name: Option<&str>, arguments: &str) -> Vec<String> {
+        let call = self.tool_calls.entry(index).or_default();
+        if let Some(id) = id.filter(|id| !id.is_empty() && call.id.is_empty()) {
+            call.id = id.to_string();
+        }
+        if let Some(name) = name.filter(|name| !name.is_empty() && call.name.is_empty()) {
+            call.name = name.to_string();
+        }
+        call.arguments.push_str(arguments);
+
+        let can_open = !call.opened && !call.name.is_empty() && self.open_tool_call().is_none();
+        if self.open_tool_call() != Some(index) && !can_open {
+            return vec![];
+        }
+        self.emit_tool_call(index)
+    }
+
+    /// 버퍼에 남은 tool call의 블록을 인덱스 순서대로 열고 arguments 전송, 미룬 델타는 그 뒤에 전송
+    fn flush_tool_calls(&mut self) -> Vec<String> {
+        let pending: Vec<u64> = self
+            .tool_calls
+            .iter()
+            .filter(|(_, call)| !call.opened)
+            .map(|(&index, _)| index)
+            .collect();
+        let mut events: Vec<String> = pending.into_iter().flat_map(|index| self.emit_tool_call(index)).collect();
+        if !self.deferred.is_empty() {
+            events.extend(self.close_block());
+            for delta in std::mem::take(&mut self.deferred) {
+                events.extend(match delta {
+                    DeferredDelta::Text(text) => self.text_delta(&text),
+                    DeferredDelta::Thinking(text) => self.thinking_delta(&text),
+                    DeferredDelta::Signature(signature) => self.signature_delta(&signature),
+                });
+            }
+        }
+        events
+    }
+
+    /// 호출의 블록을 (아직 안 열었으면) 열고 버퍼의 arguments 전송
+    fn emit_tool_call(&mut self, index: u64) -> Vec<String> {
+        let Some(call) = self.tool_calls.get_mut(&index) else {
+            return vec![];
+        };
+        let arguments = std::mem::take(&mut call.arguments);
+        let mut events = Vec::new();
+        if !call.opened {
+            call.opened = true;
+            if call.id.is_empty() {
+                call.id = format!("toolu_{}", Uuid::new_v4().simple());
+            }
+            let (id, name) = (call.id.clone(), call.name.clone());
+            events.extend(self.open_tool_block(index, &id, &name));
+        }
+        events.extend(self.tool_input_delta(&arguments));
+        events
+    }
+
+    /// 현재 열린 tool_use 블록의 업스트림 식별자
+    pub fn open_tool_call(&self) -> Option<u64> {
+        match self.open_block {
+            Some(BlockKind::ToolUse(id)) => Some(id),
+            _ => None,
+        }
+    }
+
+    /// 열린 블록이 있으면 닫고 다음 블록 인덱스로 이동
+    pub fn close_block(&mut self) -> Vec<String> {
+        if self.open_block.take().is_none() {
+            return vec![];
+        }
+        let block_stop = json!({
+            "type": "content_block_stop",
+            "index": self.block_index,
+        });
+        self.block_index += 1;
+        vec![sse_event("content_block_stop", &block_stop)]
+    }
+
+    /// 종료 사유 확정: 열린 블록을 닫고 stop_reason 기록 (message_delta는 `finish`에서)
+    ///
+    /// usage가 종료 청크 뒤에 따로 오는 제공자(OpenAI include_usage)를 위해 분리
+    pub fn stop(&mut self, stop_reason: &str) -> Vec<String> {
+        self.stop_reason = Some(stop_reason.to_string());
+        let mut events = self.flush_tool_calls();
+        events.extend(self.close_block());
+        events
+    }
+
+    /// 열린 블록을 닫고 message_delta 전송 (이미 전송했으면 무시)
+    ///
+    /// stop_reason이 None이면 `stop`으로 기록된 값, 그것도 없으면 tool_use 여부로 결정
+    pub fn finish(&mut self, stop_reason: Option<&str>) -> Vec<String> {
+        if self.finished {
+            return vec![];
+        }
+        self.finished = true;
+        let mut events = self.flush_tool_calls();
+        events.extend(self.close_block());
+        let derived = if self.tool_used { "tool_use" } else { "end_turn" };
+        let stop_reason = stop_reason
+            .or(self.stop_reason.as_deref())
+            .unwrap_or(derived)
+            .to_string();
+        let msg_delta = json!({
+            "type": "message_delta",
+            "delta": {"stop_reason": stop_reason, "stop_sequence": null},
+            "usage": {
+                "input_tokens": self.input_tokens,
+                "output_tokens": self.reported_output_tokens(),
+                "cache_read_input_tokens": self.cache_read_input_tokens,
+            }
+        });
+        events.push(sse_event("message_delta", &msg_delta));
+        events
+    }
+
+    /// 스트림 중간 에러: `event: error` 전송 후 스트림 종료 처리
+    ///
+    /// Anthropic API와 동일하게 열린 블록이나 message_delta를 보충하지 않는다.
+    pub fn error(&mut self, err: &ApiError) -> Vec<String> {
+        self.fail(&err.to_json())
+    }
+
+    fn fail(&mut self, data: &Value) -> Vec<String> {
+        if self.errored {
+            return vec![];
+        }
+        self.errored = true;
+        self.finished = true;
+        self.open_block = None;
+        vec![sse_event("error", data)]
+    }
+
+    /// 스트림 이어받기 요청 본문: 원본 요청 + 지금까지 보낸 텍스트를 assistant prefill로 추가
+    ///
+    /// tool_use 블록을 이미 보냈으면 이어받을 수 없으므로 None. prefill이 있으면 Anthropic API가
+    /// 허용하지 않는 extended thinking을 끄고, 원본 요청이 assistant prefill로 끝나면 그 뒤에 붙인다.
+    pub fn continuation_request(&self, request: &Value) -> Option<Value> {
+        if self.tool_used {
+            return None;
+        }
+        let mut request = request.clone();
+        // API는 prefill의 끝 공백을 거부
+        let prefill = self.partial_text.trim_end();
+        if prefill.is_empty() {
+            return Some(request);
+        }
+        if let Some(obj) = request.as_object_mut() {
+            obj.remove("thinking");
+        }
+        let messages = request["messages"].as_array_mut()?;
+        match messages.last_mut() {
+            Some(last) if last["role"] == "assistant" => {
+                if let Some(text) = last["content"].as_str() {
+                    last["content"] = json!([{"type": "text", "text": text}]);
+                }
+                last["content"].as_array_mut()?.push(json!({"type": "text", "text": prefill}));
+            }
+            _ => messages.push(json!({"role": "assistant", "content": prefill})),
+        }
+        Some(request)
+    }
+
+    /// 이어받기 응답(Anthropic SSE)의 이벤트 하나를 현재 메시지에 이어 붙임
+    ///
+    /// 이어받기 응답의 message_start/message_stop은 버리고, 블록은 이 컨텍스트의 상태 머신으로
+    /// 다시 열어 인덱스를 이어간다. 열려 있던 텍스트 블록은 이어받기의 첫 텍스트 블록과 합친다.
+    /// `base_output_tokens`는 중단 전까지의 출력 토큰 수 (이어받기 usage에 더함)
+    pub fn splice_event(&mut self, event: &Value, base_output_tokens: u32) -> Vec<String> {
+        match event["type"].as_str().unwrap_or("") {
+            "content_block_start" => {
+                let block = &event["content_block"];
+                match block["type"].as_str().unwrap_or("") {
+                    "text" => {
+                        let mut events = Vec::new();
+                        if self.open_block != Some(BlockKind::Text) {
+                            events.extend(self.open_block(BlockKind::Text, json!({"type": "text", "text": ""})));
+                        }
+                        match block["text"].as_str() {
+                            Some(text) if !text.is_empty() => events.extend(self.text_delta(text)),
+                            _ => {}
+                        }
+                        events
+                    }
+                    "thinking" => self.open_block(BlockKind::Thinking, json!({"type": "thinking", "thinking": ""})),
+                    "tool_use" => self.open_tool_block(
+                        event["index"].as_u64().unwrap_or(0),
+                        block["id"].as_str().unwrap_or(""),
+                        block["name"].as_str().unwrap_or(""),
+                    ),
+                    _ => vec![],
+                }
+            }
+            "content_block_delta" => {
+                let delta = &event["delta"];
+                let field = |key: &str| delta[key].as_str().unwrap_or("").to_string();
+                match delta["type"].as_str().unwrap_or("") {
+                    "text_delta" => self.text_delta(&field("text")),
+                    "thinking_delta" => self.thinking_delta(&field("thinking")),
+                    "signature_delta" => self.signature_delta(&field("signature")),
+                    "input_json_delta" => self.tool_input_delta(&field("partial_json")),
+                    _ => vec![],
+                }
+            }
+            "content_block_stop" => self.close_block(),
+            "message_delta" => {
+                let output = event["usage"]["output_tokens"].as_u64();
+                self.set_usage(None, output.map(|n| n + base_output_tokens as u64), None);
+                match event["delta"]["stop_reason"].as_str() {
+                    Some(reason) => self.stop(reason),
+                    None => vec![],
+                }
+            }
+            "error" => self.fail(event),
+            _ => vec![],
+        }
+    }
+
+    /// 스트림 종료: 누락된 종료 이벤트를 보충하고 message_stop 전송 (에러 이후에는 생략)
+    pub fn message_stop(&mut self) -> Vec<String> {
+        if self.errored {
+            return vec![];
+        }
+        let mut events = self.finish(None);
+        events.push(sse_event("message_stop", &json!({"type": "message_stop"})));
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index_of(event: &str) -> Option<u64> {
+        let data = event.split("data: ").nth(1)?;
+        serde_json::from_str::<Value>(data.trim()).ok()?["index"].as_u64()
+    }
+
+    /// 블록 전환 시 이전 블록을 닫고 인덱스 증가
+    #[test]
+    fn test_block_transitions_increment_index() {
+        let mut ctx = StreamContext::new("m", "msg_1");
+
+        let events = ctx.thinking_delta("음");
+        assert_eq!(events.len(), 2);
+        assert_eq!(index_of(&events[1]), Some(0));
+
+        let events = ctx.text_delta("답");
+        assert!(events[0].contains("content_block_stop"));
+        assert_eq!(index_of(&events[0]), Some(0));
+        assert_eq!(index_of(&events[2]), Some(1));
+
+        // 같은 종류의 연속 델타는 블록을 새로 열지 않음
+        assert_eq!(ctx.text_delta("!").len(), 1);
+
+        let events = ctx.open_tool_block(0, "toolu_1", "Bash");
+        assert_eq!(index_of(&events[1]), Some(2));
+        assert_eq!(ctx.open_tool_call(), Some(0));
+        assert_eq!(ctx.tool_input_delta("{}").len(), 1);
+    }
+
+    /// finish: 열린 블록을 먼저 닫고 message_delta는 한 번만
+    #[test]
+    fn test_finish_closes_open_block_once() {
+        let mut ctx = StreamContext::new("m", "msg_1");
+        ctx.open_tool_block(0, "toolu_1", "Bash");
+
+        let events = ctx.finish(None);
+        assert_eq!(events.len(), 2);
+        assert!(events[0].contains("content_block_stop"));
+        assert!(events[1].contains(r#""stop_reason":"tool_use""#));
+
+        assert!(ctx.finish(Some("end_turn")).is_empty());
+
+        let end = ctx.message_stop();
+        assert_eq!(end.len(), 1);
+        assert!(end[0].contains("message_stop"));
+    }
+
+    /// usage 미보고 시 로컬 추정치, 보고 시 업스트림 값 사용
+    #[test]
+    fn test_usage_reported_or_estimated() {
+        let mut ctx = StreamContext::new("m", "msg_1");
+        ctx.text_delta("abcdefgh");
+        let events = ctx.stop("end_turn");
+        assert_eq!(events.len(), 1);
+        assert_eq!(ctx.reported_output_tokens(), 2);
+
+        ctx.set_usage(Some(100), Some(7), Some(40));
+        let events = ctx.finish(None);
+        assert!(events[0].contains(r#""output_tokens":7"#));
+        assert!(events[0].contains(r#""cache_read_input_tokens":40"#));
+        assert!(events[0].contains(r#""stop_reason":"end_turn""#));
+    }
+
+    /// 에러 이벤트 이후에는 종료 이벤트를 보내지 않음
+    #[test]
+    fn test_error_ends_stream() {
+        let mut ctx = StreamContext::new("m", "msg_1");
+        ctx.text_delta("부분");
+
+        let events = ctx.error(&ApiError::new(529, "Overloaded"));
+        assert_eq!(events.len(), 1);
+        assert!(events[0].starts_with("event: error\n"));
+        assert!(events[0].contains(r#""type":"overloaded_error""#));
+
+        assert!(ctx.error(&ApiError::new(500, "again")).is_empty());
+        assert!(ctx.message_stop().is_empty());
+    }
+
+    /// 업스트림이 종료 신호 없이 끝나도 message_stop 전에 블록과 message_delta를 보충
+    #[test]
+    fn test_message_stop_without_finish() {
+        let mut ctx = StreamContext::new("m", "msg_1");
+        ctx.text_delta("잘린 응답");
+
+        let end = ctx.message_stop();
+        assert_eq!(end.len(), 3);
+        assert!(end[0].contains("content_block_stop"));
+        assert!(end[1].contains(r#""stop_reason":"end_turn""#));
+        assert!(end[2].contains("message_stop"));
+    }
+
+    /// 이어받기 요청: 보낸 텍스트를 assistant prefill로 추가하고 thinking 제거
+    #[test]
+    fn test_continuation_request_prefill() {
+        let request = json!({
+            "model": "glm-5",
+            "thinking": {"type": "enabled", "budget_tokens": 1024},
+            "messages": [{"role": "user", "content": "안녕"}]
+        });
+        let mut ctx = StreamContext::new("m", "msg_1");
+        assert_eq!(ctx.continuation_request(&request), Some(request.clone()));
+
+        ctx.thinking_delta("음");
+        ctx.text_delta("반갑");
+        ctx.text_delta("습니다 ");
+        let cont = ctx.continuation_request(&request).unwrap();
+        assert!(cont.get("thinking").is_none());
+        assert_eq!(cont["messages"][1], json!({"role": "assistant", "content": "반갑습니다"}));
+
+        // 원본이 assistant prefill로 끝나면 그 뒤에 붙임
+        let prefilled = json!({"messages": [
+            {"role": "user", "content": "안녕"},
+            {"role": "assistant", "content": "{"}
+        ]});
+        let cont = ctx.continuation_request(&prefilled).unwrap();
+        assert_eq!(cont["messages"].as_array().unwrap().len(), 2);
+        assert_eq!(cont["messages"][1]["content"][0]["text"], "{");
+        assert_eq!(cont["messages"][1]["content"][1]["text"], "반갑습니다");
+
+        // tool_use 이후에는 이어받기 불가
+        ctx.open_tool_block(0, "toolu_1", "Bash");
+        assert!(ctx.continuation_request(&request).is_none());
+    }
+
+    /// 이어받기 이벤트: 열린 텍스트 블록에 합치고 이후 블록 인덱스를 이어감
+    #[test]
+    fn test_splice_event_continues_indices() {
+        let mut ctx = StreamContext::new("m", "msg_1");
+        ctx.thinking_delta("음");
+        ctx.text_delta("앞부분");
+        ctx.set_usage(None, Some(5), None);
+        let base = ctx.reported_output_tokens();
+
+        let splice = |ctx: &mut StreamContext, event: Value| ctx.splice_event(&event, base);
+        assert!(splice(&mut ctx, json!({"type": "message_start", "message": {}})).is_empty());
+        assert!(splice(&mut ctx, json!({
+            "type": "content_block_start", "index": 0, "content_block": {"type": "text", "text": ""}
+        }))
+        .is_empty());
+
+        let events = splice(&mut ctx, json!({
+            "type": "content_block_delta", "index": 0, "delta": {"type": "text_delta", "text": " 뒷부분"}
+        }));
+        assert_eq!(events.len(), 1);
+        assert_eq!(index_of(&events[0]), Some(1));
+
+        let events = splice(&mut ctx, json!({"type": "content_block_stop", "index": 0}));
+        assert_eq!(index_of(&events[0]), Some(1));
+
+        let events = splice(&mut ctx, json!({
+            "type": "content_block_start", "index": 1,
+            "content_block": {"type": "tool_use", "id": "toolu_1", "name": "Bash", "input": {}}
+        }));
+        assert_eq!(index_of(&events[0]), Some(2));
+        assert_eq!(splice(&mut ctx, json!({
+            "type": "content_block_delta", "index": 1, "delta": {"type": "input_json_delta", "partial_json": "{}"}
+        }))
+        .len(), 1);
+
+        let events = splice(&mut ctx, json!({
+            "type": "message_delta", "delta": {"stop_reason": "tool_use"}, "usage": {"output_tokens": 3}
+        }));
+        assert!(events[0].contains("content_block_stop"));
+        assert!(splice(&mut ctx, json!({"type": "message_stop"})).is_empty());
+
+        let end = ctx.message_stop();
+        assert!(end[0].contains(r#""stop_reason":"tool_use""#));
+        assert!(end[0].contains(r#""output_tokens":8"#));
+        assert!(end[1].contains("message_stop"));
+    }
+
+    /// 이어받기 응답의 error 이벤트는 그대로 전달하고 스트림 종료
+    #[test]
+    fn test_splice_event_error() {
+        let mut ctx = StreamContext::new("m", "msg_1");
+        ctx.text_delta("부분");
+        let error = json!({"type": "error", "error": {"type": "overloaded_error", "message": "Overloaded"}});
+        let events = ctx.splice_event(&error, 0);
+        assert_eq!(events.len(), 1);
+        assert!(events[0].starts_with("event: error\n"));
+        assert!(ctx.message_stop().is_empty());
+    }
+}
@@ -0,0 +1,81 @@
+use serde_json::Value;
+
+/// 로컬 토큰 수 추정 (업스트림이 usage를 주지 않을 때의 대체값)
+///
+/// BPE 토크나이저의 평균적인 비율을 근사한다.
+/// - ASCII: 약 4자당 1토큰
+/// - CJK 등 비 ASCII 문자: 문자당 약 1토큰
+pub fn estimate_tokens(text: &str) -> u32 {
+    let mut ascii = 0u32;
+    let mut other = 0u32;
+    for c in text.chars() {
+        if c.is_ascii() {
+            ascii += 1;
+        } else {
+            other += 1;
+        }
+    }
+    ascii.div_ceil(4) + other
+}
+
+/// Anthropic 요청 본문의 입력 토큰 수 추정 (system + messages + tools)
+pub fn estimate_request_tokens(body: &Value) -> u32 {
+    let mut total = estimate_value(&body["system"]);
+    for msg in body["messages"].as_array().into_iter().flatten() {
+        // 메시지당 역할/구분자 오버헤드
+        total += 4 + estimate_value(&msg["content"]);
+    }
+    if let Some(tools) = body.get("tools") {
+        total += estimate_tokens(&tools.to_string());
+    }
+    total
+}
+
+/// content 값(문자열 또는 블록 배열)의 토큰 수 추정
+fn estimate_value(value: &Value) -> u32 {
+    match value {
+        Value::String(s) => estimate_tokens(s),
+        Value::Array(blocks) => blocks.iter().map(estimate_block).sum(),
+        _ => 0,
+    }
+}
+
+fn estimate_block(block: &Value) -> u32 {
+    match block["type"].as_str() {
+        Some("text") => estimate_tokens(block["text"].as_str().unwrap_or("")),
+        Some("thinking") => estimate_tokens(block["thinking"].as_str().unwrap_or("")),
+        Some("tool_use") => estimate_tokens(&block["input"].to_string()),
+        Some("tool_result") => estimate_value(&block["content"]),
+        // 이미지/문서는 크기와 무관하게 고정 근사치
+        Some("image") => 1600,
+        Some("document") => 3000,
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_estimate_tokens_ascii_and_cjk() {
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("abcd"), 1);
+        assert_eq!(estimate_tokens("abcde"), 2);
+        assert_eq!(estimate_tokens("안녕"), 2);
+    }
+
+    #[test]
+    fn test_estimate_request_tokens() {
+        let body = json!({
+            "system": "abcdefgh",
+            "messages": [
+                {"role": "user", "content": "abcd"},
+                {"role": "user", "content": [{"type": "text", "text": "안녕"}, {"type": "image", "source": {}}]}
+            ]
+        });
+        // system 2 + (4 + 1) + (4 + 2 + 1600)
+        assert_eq!(estimate_request_tokens(&body), 1613);
+    }
+}
@@ -0,0 +1,66 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// RFC 3986 unreserved 문자 외 전부 인코딩
+pub fn uri_encode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for b in s.bytes() {
+        if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.' | b'~') {
+            out.push(b as char);
+        } else {
+            out.push_str(&format!("%{b:02X}"));
+        }
+    }
+    out
+}
+
+/// 경로 템플릿의 `{model}`을 모델명으로 치환
+///
+/// 라우트 업스트림의 `path`와 선언형 트랜스포머의 `path`/`stream_path`가 같은 규칙을 쓴다.
+pub fn expand_path_template(template: &str, model: &str) -> String {
+    template.replace("{model}", model)
+}
+
+/// 카운터 → 의사 난수 (SplitMix64, 암호학적 용도 아님)
+pub fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// 재시도 지터용 난수 (암호학적 용도 아님)
+///
+/// 현재 시각(나노초)과 호출마다 증가하는 카운터를 SplitMix64로 섞는다.
+/// 같은 순간에 실패한 요청들도 카운터가 달라 서로 다른 대기 시간을 받는다.
+pub fn jitter_random() -> u64 {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos() as u64);
+    splitmix64(nanos ^ splitmix64(COUNTER.fetch_add(1, Ordering::Relaxed)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uri_encode_keeps_unreserved() {
+        assert_eq!(uri_encode("a-Z_0.~"), "a-Z_0.~");
+        assert_eq!(uri_encode("claude:0 v/1"), "claude%3A0%20v%2F1");
+    }
+
+    #[test]
+    fn test_expand_path_template() {
+        assert_eq!(expand_path_template("/deployments/{model}/chat", "gpt-4o"), "/deployments/gpt-4o/chat");
+        assert_eq!(expand_path_template("/v2/chat?model={model}", "m"), "/v2/chat?model=m");
+        assert_eq!(expand_path_template("/v1/chat", "m"), "/v1/chat");
+    }
+
+    #[test]
+    fn test_jitter_random_differs_per_call() {
+        let values: std::collections::HashSet<u64> = (0..100).map(|_| jitter_random()).collect();
+        assert_eq!(values.len(), 100);
+    }
+}